@@ -4,11 +4,13 @@ use argon2::{
 };
 use axum::{extract::State, Json};
 use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::broadcast;
 
 use crate::error::{AppError, AppResult};
 use crate::models::{
-    AuthResponse, LoginRequest, MessageResponse, RegisterRequest, ResetPasswordRequest, User,
-    UserResponse,
+    AuthResponse, LiveWeekPoints, LoginRequest, MessageResponse, RegisterRequest,
+    ResetPasswordRequest, User, UserResponse,
 };
 
 use super::jwt::create_token;
@@ -18,6 +20,8 @@ use super::jwt::create_token;
 pub struct AppState {
     pub pool: PgPool,
     pub jwt_secret: String,
+    /// Scored gameweeks, pushed to live subscribers as stats come in.
+    pub live: broadcast::Sender<Arc<LiveWeekPoints>>,
 }
 
 /// POST /api/auth/register
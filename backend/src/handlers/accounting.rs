@@ -9,6 +9,7 @@ use uuid::Uuid;
 use crate::auth::handler::AppState;
 use crate::auth::middleware::AuthUser;
 use crate::error::{AppError, AppResult};
+use crate::services::audit::AuditEntry;
 
 // ── Response types ──────────────────────────────────────────────────────────
 
@@ -124,18 +125,20 @@ async fn recalculate_amounts(
 
     let per_player = total / Decimal::from(count);
 
-    sqlx::query(
-        "UPDATE futsal_session_players SET amount_due = $1 WHERE session_id = $2",
-    )
-    .bind(per_player)
-    .bind(session_id)
-    .execute(&mut **tx)
-    .await?;
+    sqlx::query("UPDATE futsal_session_players SET amount_due = $1 WHERE session_id = $2")
+        .bind(per_player)
+        .bind(session_id)
+        .execute(&mut **tx)
+        .await?;
 
     Ok(())
 }
 
-fn format_session(row: FutsalSessionRow, player_count: i64, paid_count: i64) -> FutsalSessionResponse {
+fn format_session(
+    row: FutsalSessionRow,
+    player_count: i64,
+    paid_count: i64,
+) -> FutsalSessionResponse {
     FutsalSessionResponse {
         id: row.id,
         title: row.title,
@@ -176,6 +179,8 @@ pub async fn create_session(
     let amount = Decimal::try_from(body.total_amount)
         .map_err(|_| AppError::BadRequest("Invalid amount".into()))?;
 
+    let mut tx = state.pool.begin().await?;
+
     let row = sqlx::query_as::<_, FutsalSessionRow>(
         r#"INSERT INTO futsal_sessions (title, total_amount, created_by)
            VALUES ($1, $2, $3)
@@ -184,9 +189,21 @@ pub async fn create_session(
     .bind(body.title.trim())
     .bind(amount)
     .bind(auth.user_id)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *tx)
     .await?;
 
+    AuditEntry::new(
+        auth.user_id,
+        "accounting.session.create",
+        "futsal_session",
+        row.id,
+    )
+    .after(&row)
+    .record(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
     Ok(Json(format_session(row, 0, 0)))
 }
 
@@ -255,23 +272,41 @@ pub async fn get_session(
 /// DELETE /api/accounting/sessions/:id
 pub async fn delete_session(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
     Path(session_id): Path<Uuid>,
 ) -> AppResult<Json<serde_json::Value>> {
+    let before = get_session_inner(&state.pool, session_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
     let result = sqlx::query("DELETE FROM futsal_sessions WHERE id = $1")
         .bind(session_id)
-        .execute(&state.pool)
+        .execute(&mut *tx)
         .await?;
 
     if result.rows_affected() == 0 {
         return Err(AppError::NotFound("Session not found".into()));
     }
 
+    AuditEntry::new(
+        auth.user_id,
+        "accounting.session.delete",
+        "futsal_session",
+        session_id,
+    )
+    .before(&before.0)
+    .record(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
     Ok(Json(serde_json::json!({ "ok": true })))
 }
 
 /// POST /api/accounting/sessions/:id/players
 pub async fn add_player(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
     Path(session_id): Path<Uuid>,
     Json(body): Json<AddPlayerRequest>,
 ) -> AppResult<Json<SessionDetailResponse>> {
@@ -280,28 +315,37 @@ pub async fn add_player(
     }
 
     // Verify session exists
-    let _session = sqlx::query_scalar::<_, Uuid>(
-        "SELECT id FROM futsal_sessions WHERE id = $1",
-    )
-    .bind(session_id)
-    .fetch_optional(&state.pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound("Session not found".into()))?;
+    let _session = sqlx::query_scalar::<_, Uuid>("SELECT id FROM futsal_sessions WHERE id = $1")
+        .bind(session_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Session not found".into()))?;
 
     let mut tx = state.pool.begin().await?;
 
-    sqlx::query(
+    let entry = sqlx::query_as::<_, SessionPlayerRow>(
         r#"INSERT INTO futsal_session_players (session_id, user_id, player_name)
-           VALUES ($1, $2, $3)"#,
+           VALUES ($1, $2, $3)
+           RETURNING id, session_id, user_id, player_name, amount_due, is_paid, paid_at, marked_paid_by"#,
     )
     .bind(session_id)
     .bind(body.user_id)
     .bind(body.player_name.trim())
-    .execute(&mut *tx)
+    .fetch_one(&mut *tx)
     .await?;
 
     recalculate_amounts(&mut tx, session_id).await?;
 
+    AuditEntry::new(
+        auth.user_id,
+        "accounting.player.add",
+        "futsal_session_player",
+        entry.id,
+    )
+    .after(&entry)
+    .record(&mut *tx)
+    .await?;
+
     tx.commit().await?;
 
     // Return updated session detail
@@ -311,24 +355,33 @@ pub async fn add_player(
 /// DELETE /api/accounting/sessions/:session_id/players/:player_id
 pub async fn remove_player(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
     Path((session_id, player_id)): Path<(Uuid, Uuid)>,
 ) -> AppResult<Json<SessionDetailResponse>> {
     let mut tx = state.pool.begin().await?;
 
-    let result = sqlx::query(
-        "DELETE FROM futsal_session_players WHERE id = $1 AND session_id = $2",
+    let removed = sqlx::query_as::<_, SessionPlayerRow>(
+        r#"DELETE FROM futsal_session_players WHERE id = $1 AND session_id = $2
+           RETURNING id, session_id, user_id, player_name, amount_due, is_paid, paid_at, marked_paid_by"#,
     )
     .bind(player_id)
     .bind(session_id)
-    .execute(&mut *tx)
-    .await?;
-
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound("Player entry not found".into()));
-    }
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Player entry not found".into()))?;
 
     recalculate_amounts(&mut tx, session_id).await?;
 
+    AuditEntry::new(
+        auth.user_id,
+        "accounting.player.remove",
+        "futsal_session_player",
+        removed.id,
+    )
+    .before(&removed)
+    .record(&mut *tx)
+    .await?;
+
     tx.commit().await?;
 
     get_session_inner(&state.pool, session_id).await
@@ -358,38 +411,53 @@ pub async fn toggle_pay(
         .await?
         .unwrap_or(false);
 
-    if !is_admin {
-        if entry.user_id != Some(auth.user_id) {
-            return Err(AppError::Auth("You can only mark your own dues as paid".into()));
-        }
+    if !is_admin && entry.user_id != Some(auth.user_id) {
+        return Err(AppError::Auth(
+            "You can only mark your own dues as paid".into(),
+        ));
     }
 
     let new_paid = !entry.is_paid;
 
-    if new_paid {
-        sqlx::query(
-            "UPDATE futsal_session_players SET is_paid = true, paid_at = NOW(), marked_paid_by = $1 WHERE id = $2",
+    let mut tx = state.pool.begin().await?;
+
+    let updated = if new_paid {
+        sqlx::query_as::<_, SessionPlayerRow>(
+            r#"UPDATE futsal_session_players SET is_paid = true, paid_at = NOW(), marked_paid_by = $1 WHERE id = $2
+               RETURNING id, session_id, user_id, player_name, amount_due, is_paid, paid_at, marked_paid_by"#,
         )
         .bind(auth.user_id)
         .bind(player_id)
-        .execute(&state.pool)
-        .await?;
+        .fetch_one(&mut *tx)
+        .await?
     } else {
-        sqlx::query(
-            "UPDATE futsal_session_players SET is_paid = false, paid_at = NULL, marked_paid_by = NULL WHERE id = $1",
+        sqlx::query_as::<_, SessionPlayerRow>(
+            r#"UPDATE futsal_session_players SET is_paid = false, paid_at = NULL, marked_paid_by = NULL WHERE id = $1
+               RETURNING id, session_id, user_id, player_name, amount_due, is_paid, paid_at, marked_paid_by"#,
         )
         .bind(player_id)
-        .execute(&state.pool)
-        .await?;
-    }
+        .fetch_one(&mut *tx)
+        .await?
+    };
+
+    AuditEntry::new(
+        auth.user_id,
+        "accounting.player.toggle_pay",
+        "futsal_session_player",
+        player_id,
+    )
+    .before(&entry)
+    .after(&updated)
+    .record(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
 
     get_session_inner(&state.pool, session_id).await
 }
 
 /// GET /api/accounting/users
-pub async fn list_users(
-    State(state): State<AppState>,
-) -> AppResult<Json<Vec<UserListItem>>> {
+pub async fn list_users(State(state): State<AppState>) -> AppResult<Json<Vec<UserListItem>>> {
     let users = sqlx::query_as::<_, UserListItem>(
         "SELECT id, username, full_name FROM users ORDER BY full_name, username",
     )
@@ -400,9 +468,7 @@ pub async fn list_users(
 }
 
 /// GET /api/accounting/user-summary
-pub async fn user_summary(
-    State(state): State<AppState>,
-) -> AppResult<Json<Vec<UserSummaryItem>>> {
+pub async fn user_summary(State(state): State<AppState>) -> AppResult<Json<Vec<UserSummaryItem>>> {
     #[derive(sqlx::FromRow)]
     struct RawSummary {
         user_id: Option<Uuid>,
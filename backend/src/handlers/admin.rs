@@ -1,48 +1,55 @@
+use std::collections::HashMap;
+
 use axum::{
-    extract::{Path, State},
-    Json,
+    extract::{Path, Query, State},
+    Extension, Json,
 };
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::auth::handler::AppState;
+use crate::auth::middleware::AuthUser;
 use crate::error::{AppError, AppResult};
+use crate::handlers::leagues::record_h2h_results;
+use crate::handlers::points::publish_week;
+use crate::handlers::seasons::resolve_season;
 use crate::handlers::teams::compute_lock_status;
-use crate::models::PlayerPosition;
-use crate::models::{AdminPlayerStats, CreateGameweekRequest, MatchWeek, PlayerStatInput};
-use crate::services::points_engine::PointsEngine;
+use crate::models::{
+    AdminPlayerStats, ChipType, CreateGameweekRequest, CreateScoringRulesRequest,
+    GameweekDeadlineRequest, GameweekStatus, LeagueRankChange, LockSchedule, MatchWeek,
+    PlayerPointsChange, PlayerPriceChange, PlayerStatInput, ReopenGameweekRequest, ScoringRules,
+    StatsImportQuery, StatsImportResponse, StatsImportRow, StatsPreviewResponse, TeamPointsChange,
+};
+use crate::models::{PlayerPosition, PriceModelKind};
+use crate::services::audit::AuditEntry;
+use crate::services::auto_subs::{self, SquadMember};
+use crate::services::deadlines::WeeklyLock;
+use crate::services::free_hit;
+use crate::services::gameweek::{self, GameweekAction};
+use crate::services::matches::{self, Appearance};
+use crate::services::points_engine::{MatchStats, PointsEngine};
 use crate::services::points_sql;
+use crate::services::price_model::PlayerWeek;
+use crate::services::stats_csv;
+use crate::services::transfer_policy;
+
+const WEEK_COLUMNS: &str = "id, week_number, start_date, end_date, is_active, season_id, status, \
+                            finalised_at, deadline_at, unlock_at";
 
 #[derive(sqlx::FromRow)]
 struct TeamLineupSnapshotSource {
     id: Uuid,
     captain_id: Option<Uuid>,
+    vice_captain_id: Option<Uuid>,
 }
 
-fn price_floor() -> Decimal {
+pub fn price_floor() -> Decimal {
     Decimal::new(1, 1) // 0.1
 }
 
-fn top_price_deltas() -> [Decimal; 3] {
-    [
-        Decimal::new(3, 1),
-        Decimal::new(2, 1),
-        Decimal::new(1, 1),
-    ]
-}
-
-fn bottom_price_deltas() -> [Decimal; 3] {
-    [
-        Decimal::new(-3, 1),
-        Decimal::new(-2, 1),
-        Decimal::new(-1, 1),
-    ]
-}
-
-/// Top 3 / bottom 3 by this gameweek's `player_points.total_points`; reverses any prior
-/// adjustment for the same week when stats are resubmitted.
-async fn apply_gameweek_price_adjustments(
+/// Undo the price moves recorded for a week, leaving it with none applied.
+async fn revert_gameweek_price_adjustments(
     tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     match_week_id: Uuid,
 ) -> Result<(), sqlx::Error> {
@@ -60,14 +67,12 @@ async fn apply_gameweek_price_adjustments(
     .await?;
 
     for row in prev {
-        sqlx::query(
-            "UPDATE players SET price = GREATEST(price - $1, $2) WHERE id = $3",
-        )
-        .bind(row.delta)
-        .bind(price_floor())
-        .bind(row.player_id)
-        .execute(&mut **tx)
-        .await?;
+        sqlx::query("UPDATE players SET price = GREATEST(price - $1, $2) WHERE id = $3")
+            .bind(row.delta)
+            .bind(price_floor())
+            .bind(row.player_id)
+            .execute(&mut **tx)
+            .await?;
     }
 
     sqlx::query("DELETE FROM gameweek_price_adjustments WHERE match_week_id = $1")
@@ -75,44 +80,52 @@ async fn apply_gameweek_price_adjustments(
         .execute(&mut **tx)
         .await?;
 
-    #[derive(sqlx::FromRow)]
-    struct PlayerGwRow {
-        id: Uuid,
-    }
+    Ok(())
+}
 
-    let ordered: Vec<PlayerGwRow> = sqlx::query_as(
-        r#"SELECT p.id
+/// Move prices by the season's price model; reverses any prior adjustment for the
+/// same week first, so every delta in `gameweek_price_adjustments` is the one
+/// currently applied.
+async fn apply_gameweek_price_adjustments(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    match_week_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    revert_gameweek_price_adjustments(tx, match_week_id).await?;
+
+    let model = sqlx::query_scalar::<_, PriceModelKind>(
+        r#"SELECT s.price_model FROM match_weeks mw
+           INNER JOIN seasons s ON s.id = mw.season_id
+           WHERE mw.id = $1"#,
+    )
+    .bind(match_week_id)
+    .fetch_one(&mut **tx)
+    .await?
+    .model();
+
+    let players: Vec<PlayerWeek> = sqlx::query_as(
+        r#"SELECT p.id AS player_id, p.name,
+                  COALESCE(pp.total_points, 0) AS week_points,
+                  (SELECT COUNT(*) FROM transfers t
+                   WHERE t.match_week_id = $1 AND t.player_in_id = p.id) AS transfers_in,
+                  (SELECT COUNT(*) FROM transfers t
+                   WHERE t.match_week_id = $1 AND t.player_out_id = p.id) AS transfers_out
            FROM players p
-           LEFT JOIN player_points pp ON pp.player_id = p.id AND pp.match_week_id = $1
-           ORDER BY COALESCE(pp.total_points, 0) DESC, p.name ASC"#,
+           LEFT JOIN player_points pp ON pp.player_id = p.id AND pp.match_week_id = $1"#,
     )
     .bind(match_week_id)
     .fetch_all(&mut **tx)
     .await?;
 
-    let top_ids: Vec<Uuid> = ordered.iter().take(3).map(|r| r.id).collect();
-
-    let mut bottom_ids: Vec<Uuid> = Vec::new();
-    for r in ordered.iter().rev() {
-        if bottom_ids.len() >= 3 {
-            break;
-        }
-        if !top_ids.contains(&r.id) {
-            bottom_ids.push(r.id);
-        }
-    }
-
     async fn apply_one(
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         match_week_id: Uuid,
         player_id: Uuid,
         intended: Decimal,
     ) -> Result<(), sqlx::Error> {
-        let current: Decimal =
-            sqlx::query_scalar("SELECT price FROM players WHERE id = $1")
-                .bind(player_id)
-                .fetch_one(&mut **tx)
-                .await?;
+        let current: Decimal = sqlx::query_scalar("SELECT price FROM players WHERE id = $1")
+            .bind(player_id)
+            .fetch_one(&mut **tx)
+            .await?;
         let new_price = (current + intended).max(price_floor());
         let actual = new_price - current;
         if actual.is_zero() {
@@ -135,17 +148,8 @@ async fn apply_gameweek_price_adjustments(
         Ok(())
     }
 
-    let top_d = top_price_deltas();
-    for (i, &pid) in top_ids.iter().enumerate() {
-        if i < top_d.len() {
-            apply_one(tx, match_week_id, pid, top_d[i]).await?;
-        }
-    }
-    let bot_d = bottom_price_deltas();
-    for (i, &pid) in bottom_ids.iter().enumerate() {
-        if i < bot_d.len() {
-            apply_one(tx, match_week_id, pid, bot_d[i]).await?;
-        }
+    for (player_id, delta) in model.deltas(&players) {
+        apply_one(tx, match_week_id, player_id, delta).await?;
     }
 
     Ok(())
@@ -156,7 +160,7 @@ async fn snapshot_lineups_for_week(
     match_week_id: Uuid,
 ) -> Result<(), sqlx::Error> {
     let teams = sqlx::query_as::<_, TeamLineupSnapshotSource>(
-        "SELECT id, captain_id FROM fantasy_teams",
+        "SELECT id, captain_id, vice_captain_id FROM fantasy_teams",
     )
     .fetch_all(&mut **tx)
     .await?;
@@ -164,8 +168,8 @@ async fn snapshot_lineups_for_week(
     for team in teams {
         // Keep snapshot immutable once created for this team+week.
         let lineup_id = sqlx::query_scalar::<_, Uuid>(
-            r#"INSERT INTO team_gameweek_lineups (team_id, match_week_id, captain_id)
-               VALUES ($1, $2, $3)
+            r#"INSERT INTO team_gameweek_lineups (team_id, match_week_id, captain_id, vice_captain_id)
+               VALUES ($1, $2, $3, $4)
                ON CONFLICT (team_id, match_week_id) DO UPDATE
                  SET captain_id = team_gameweek_lineups.captain_id
                RETURNING id"#,
@@ -173,13 +177,14 @@ async fn snapshot_lineups_for_week(
         .bind(team.id)
         .bind(match_week_id)
         .bind(team.captain_id)
+        .bind(team.vice_captain_id)
         .fetch_one(&mut **tx)
         .await?;
 
         sqlx::query(
             r#"INSERT INTO team_gameweek_lineup_players
-                 (team_gameweek_lineup_id, player_id, is_bench, assigned_position)
-               SELECT $1, tp.player_id, tp.is_bench, tp.assigned_position
+                 (team_gameweek_lineup_id, player_id, is_bench, assigned_position, bench_order)
+               SELECT $1, tp.player_id, tp.is_bench, tp.assigned_position, tp.bench_order
                FROM team_players tp
                WHERE tp.team_id = $2
                ON CONFLICT (team_gameweek_lineup_id, player_id) DO NOTHING"#,
@@ -190,6 +195,8 @@ async fn snapshot_lineups_for_week(
         .await?;
     }
 
+    free_hit::revert_finished(tx, match_week_id).await?;
+
     Ok(())
 }
 
@@ -202,39 +209,102 @@ pub struct SetLineupLockRequest {
 pub struct AdminLineupLockResponse {
     pub force_unlock: bool,
     pub effective_locked: bool,
+    pub deadline_at: Option<String>,
     pub unlock_at: Option<String>,
+    pub schedule: LockSchedule,
+}
+
+/// Check a gameweek's own lock window: both times or neither, in order.
+fn validate_deadline(
+    deadline_at: Option<chrono::DateTime<chrono::Utc>>,
+    unlock_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> AppResult<()> {
+    match (deadline_at, unlock_at) {
+        (None, None) => Ok(()),
+        (Some(deadline_at), Some(unlock_at)) if unlock_at > deadline_at => Ok(()),
+        (Some(_), Some(_)) => Err(AppError::BadRequest(
+            "unlock_at must be after deadline_at".to_string(),
+        )),
+        _ => Err(AppError::BadRequest(
+            "Set both deadline_at and unlock_at, or neither".to_string(),
+        )),
+    }
 }
 
 /// POST /api/admin/gameweek
 ///
-/// Create a new match week. Deactivates any previously active week.
+/// Create a new match week. Deactivates any previously active week. Without a
+/// `deadline_at` and `unlock_at` the week follows the weekly lock schedule.
 pub async fn create_gameweek(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
     Json(body): Json<CreateGameweekRequest>,
 ) -> AppResult<Json<MatchWeek>> {
+    validate_deadline(body.deadline_at, body.unlock_at)?;
+    let season = resolve_season(&state.pool, None).await?;
+    if body.week_number < season.start_week {
+        return Err(AppError::BadRequest(format!(
+            "{} {} starts at gameweek {}",
+            season.name, season.year, season.start_week
+        )));
+    }
+
     let mut tx = state.pool.begin().await?;
 
+    let existing = sqlx::query_as::<_, MatchWeek>(&format!(
+        "SELECT {WEEK_COLUMNS} FROM match_weeks WHERE week_number = $1"
+    ))
+    .bind(body.week_number)
+    .fetch_optional(&mut *tx)
+    .await?;
+
     sqlx::query("UPDATE match_weeks SET is_active = false WHERE is_active = true")
         .execute(&mut *tx)
         .await?;
 
-    let week = sqlx::query_as::<_, MatchWeek>(
-        r#"INSERT INTO match_weeks (week_number, start_date, end_date, is_active)
-           VALUES ($1, $2, $3, true)
+    let status = gameweek::transition(
+        existing
+            .as_ref()
+            .map_or(GameweekStatus::Upcoming, |w| w.status),
+        GameweekAction::Activate,
+    )
+    .map_err(|e| AppError::Conflict(format!("Gameweek {} {e}", body.week_number)))?;
+
+    let week = sqlx::query_as::<_, MatchWeek>(&format!(
+        r#"INSERT INTO match_weeks
+             (week_number, start_date, end_date, is_active, status, deadline_at, unlock_at)
+           VALUES ($1, $2, $3, true, $4, $5, $6)
            ON CONFLICT (week_number) DO UPDATE
              SET start_date = EXCLUDED.start_date,
                  end_date = EXCLUDED.end_date,
-                 is_active = true
-           RETURNING id, week_number, start_date, end_date, is_active"#,
-    )
+                 is_active = true,
+                 status = EXCLUDED.status,
+                 deadline_at = EXCLUDED.deadline_at,
+                 unlock_at = EXCLUDED.unlock_at
+           RETURNING {WEEK_COLUMNS}"#
+    ))
     .bind(body.week_number)
     .bind(body.start_date)
     .bind(body.end_date)
+    .bind(status)
+    .bind(body.deadline_at)
+    .bind(body.unlock_at)
     .fetch_one(&mut *tx)
     .await?;
 
     snapshot_lineups_for_week(&mut tx, week.id).await?;
 
+    AuditEntry::new(
+        auth.user_id,
+        "gameweek.create",
+        "gameweek",
+        week.week_number,
+    )
+    .before(&existing)
+    .after(&week)
+    .record(&mut *tx)
+    .await?;
+
     tx.commit().await?;
 
     Ok(Json(week))
@@ -279,9 +349,9 @@ pub async fn get_week_stats(
 ///
 /// List all gameweeks with their status.
 pub async fn get_gameweeks(State(state): State<AppState>) -> AppResult<Json<Vec<MatchWeek>>> {
-    let weeks = sqlx::query_as::<_, MatchWeek>(
-        "SELECT id, week_number, start_date, end_date, is_active FROM match_weeks ORDER BY week_number",
-    )
+    let weeks = sqlx::query_as::<_, MatchWeek>(&format!(
+        "SELECT {WEEK_COLUMNS} FROM match_weeks ORDER BY week_number"
+    ))
     .fetch_all(&state.pool)
     .await?;
 
@@ -294,15 +364,30 @@ pub async fn get_gameweeks(State(state): State<AppState>) -> AppResult<Json<Vec<
 /// When deactivating, simply sets is_active = false (no active gameweek).
 pub async fn toggle_gameweek(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
     Path(week_number): Path<i32>,
 ) -> AppResult<Json<MatchWeek>> {
-    let current = sqlx::query_as::<_, MatchWeek>(
-        "SELECT id, week_number, start_date, end_date, is_active FROM match_weeks WHERE week_number = $1",
-    )
+    let current = sqlx::query_as::<_, MatchWeek>(&format!(
+        "SELECT {WEEK_COLUMNS} FROM match_weeks WHERE week_number = $1"
+    ))
     .bind(week_number)
     .fetch_optional(&state.pool)
     .await?
-    .ok_or_else(|| AppError::NotFound(format!("Gameweek {week_number} not found. Create it first.")))?;
+    .ok_or_else(|| {
+        AppError::NotFound(format!(
+            "Gameweek {week_number} not found. Create it first."
+        ))
+    })?;
+
+    if !current.is_active {
+        let season = resolve_season(&state.pool, Some(current.season_id)).await?;
+        if season.closed_at.is_some() {
+            return Err(AppError::Conflict(format!(
+                "Gameweek {week_number} belongs to {} {}, which is closed",
+                season.name, season.year
+            )));
+        }
+    }
 
     let mut tx = state.pool.begin().await?;
 
@@ -315,15 +400,18 @@ pub async fn toggle_gameweek(
         sqlx::query("UPDATE match_weeks SET is_active = false WHERE is_active = true")
             .execute(&mut *tx)
             .await?;
-        sqlx::query("UPDATE match_weeks SET is_active = true WHERE week_number = $1")
+        let status = gameweek::transition(current.status, GameweekAction::Activate)
+            .map_err(|e| AppError::Conflict(format!("Gameweek {week_number} {e}")))?;
+        sqlx::query("UPDATE match_weeks SET is_active = true, status = $2 WHERE week_number = $1")
             .bind(week_number)
+            .bind(status)
             .execute(&mut *tx)
             .await?;
     }
 
-    let updated = sqlx::query_as::<_, MatchWeek>(
-        "SELECT id, week_number, start_date, end_date, is_active FROM match_weeks WHERE week_number = $1",
-    )
+    let updated = sqlx::query_as::<_, MatchWeek>(&format!(
+        "SELECT {WEEK_COLUMNS} FROM match_weeks WHERE week_number = $1"
+    ))
     .bind(week_number)
     .fetch_one(&mut *tx)
     .await?;
@@ -332,49 +420,717 @@ pub async fn toggle_gameweek(
         snapshot_lineups_for_week(&mut tx, updated.id).await?;
     }
 
+    AuditEntry::new(auth.user_id, "gameweek.toggle", "gameweek", week_number)
+        .before(&current)
+        .after(&updated)
+        .record(&mut *tx)
+        .await?;
+
     tx.commit().await?;
 
     Ok(Json(updated))
 }
 
+/// A gameweek in a season that is not closed.
+async fn season_week(pool: &sqlx::PgPool, week_number: i32) -> AppResult<MatchWeek> {
+    let week = sqlx::query_as::<_, MatchWeek>(&format!(
+        "SELECT {WEEK_COLUMNS} FROM match_weeks WHERE week_number = $1"
+    ))
+    .bind(week_number)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Gameweek {week_number} not found")))?;
+
+    let season = resolve_season(pool, Some(week.season_id)).await?;
+    if season.closed_at.is_some() {
+        return Err(AppError::Conflict(format!(
+            "Gameweek {week_number} belongs to {} {}, which is closed",
+            season.name, season.year
+        )));
+    }
+
+    Ok(week)
+}
+
+/// A gameweek that still accepts stats: one in a season that is not closed and
+/// that has not been finalised.
+pub(crate) async fn open_week(pool: &sqlx::PgPool, week_number: i32) -> AppResult<MatchWeek> {
+    let week = season_week(pool, week_number).await?;
+    gameweek::transition(week.status, GameweekAction::Score)
+        .map_err(|e| AppError::Conflict(format!("Gameweek {week_number} {e}")))?;
+    Ok(week)
+}
+
+/// Lock a gameweek's row for a status change and check the change is allowed.
+///
+/// Writers that score or edit an open week take the lock with
+/// [`GameweekAction::Score`], so a finalise cannot slip in between the check
+/// and the write.
+pub(crate) async fn lock_week_for(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    week_number: i32,
+    action: GameweekAction,
+) -> AppResult<(MatchWeek, GameweekStatus)> {
+    let week = sqlx::query_as::<_, MatchWeek>(&format!(
+        "SELECT {WEEK_COLUMNS} FROM match_weeks WHERE week_number = $1 FOR UPDATE"
+    ))
+    .bind(week_number)
+    .fetch_one(&mut **tx)
+    .await?;
+    let status = gameweek::transition(week.status, action)
+        .map_err(|e| AppError::Conflict(format!("Gameweek {week_number} {e}")))?;
+    Ok((week, status))
+}
+
+/// PUT /api/admin/gameweek/:week/deadline
+///
+/// Give a gameweek its own lineup lock, for weeks played on a weekday or a
+/// holiday, or clear it to follow the weekly schedule again.
+pub async fn set_gameweek_deadline(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(week_number): Path<i32>,
+    Json(body): Json<GameweekDeadlineRequest>,
+) -> AppResult<Json<MatchWeek>> {
+    validate_deadline(body.deadline_at, body.unlock_at)?;
+    let before = season_week(&state.pool, week_number).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let after = sqlx::query_as::<_, MatchWeek>(&format!(
+        "UPDATE match_weeks SET deadline_at = $2, unlock_at = $3 WHERE id = $1 RETURNING {WEEK_COLUMNS}"
+    ))
+    .bind(before.id)
+    .bind(body.deadline_at)
+    .bind(body.unlock_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEntry::new(auth.user_id, "gameweek.deadline", "gameweek", week_number)
+        .before(&before)
+        .after(&after)
+        .record(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Json(after))
+}
+
+/// POST /api/admin/gameweek/:week/finalise
+///
+/// Confirm a provisional gameweek: move prices and record head-to-head and cup
+/// results from its points. Its stats are locked until it is reopened.
+pub async fn finalise_gameweek(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(week_number): Path<i32>,
+) -> AppResult<Json<MatchWeek>> {
+    season_week(&state.pool, week_number).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let (before, status) = lock_week_for(&mut tx, week_number, GameweekAction::Finalise).await?;
+
+    apply_gameweek_price_adjustments(&mut tx, before.id).await?;
+    record_h2h_results(&mut tx, before.id).await?;
+
+    let after = sqlx::query_as::<_, MatchWeek>(&format!(
+        "UPDATE match_weeks SET status = $2, finalised_at = NOW() WHERE id = $1 RETURNING {WEEK_COLUMNS}"
+    ))
+    .bind(before.id)
+    .bind(status)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEntry::new(auth.user_id, "gameweek.finalise", "gameweek", week_number)
+        .before(&before)
+        .after(&after)
+        .record(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    publish_week(&state, week_number).await;
+
+    Ok(Json(after))
+}
+
+/// POST /api/admin/gameweek/:week/reopen
+///
+/// Take a finalised gameweek back to provisional so its stats can be corrected.
+/// Its price moves are undone and its head-to-head results cleared until it is
+/// finalised again. The reason is kept in the audit log.
+pub async fn reopen_gameweek(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(week_number): Path<i32>,
+    Json(body): Json<ReopenGameweekRequest>,
+) -> AppResult<Json<MatchWeek>> {
+    let reason = body.reason.trim();
+    if reason.is_empty() {
+        return Err(AppError::BadRequest(
+            "Give a reason for reopening the gameweek".to_string(),
+        ));
+    }
+    season_week(&state.pool, week_number).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let (before, status) = lock_week_for(&mut tx, week_number, GameweekAction::Reopen).await?;
+
+    revert_gameweek_price_adjustments(&mut tx, before.id).await?;
+    sqlx::query(
+        r#"UPDATE h2h_fixtures SET home_points = NULL, away_points = NULL
+           WHERE season_id = $1 AND week_number = $2"#,
+    )
+    .bind(before.season_id)
+    .bind(week_number)
+    .execute(&mut *tx)
+    .await?;
+
+    let after = sqlx::query_as::<_, MatchWeek>(&format!(
+        "UPDATE match_weeks SET status = $2, finalised_at = NULL WHERE id = $1 RETURNING {WEEK_COLUMNS}"
+    ))
+    .bind(before.id)
+    .bind(status)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEntry::new(auth.user_id, "gameweek.reopen", "gameweek", week_number)
+        .before(&before)
+        .after(serde_json::json!({ "week": &after, "reason": reason }))
+        .record(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    publish_week(&state, week_number).await;
+
+    Ok(Json(after))
+}
+
+/// What scoring a batch of stats produced, before the caller commits or rolls back.
+struct WeekStatsOutcome {
+    /// Points each submitted player scored, in submission order.
+    player_points: Vec<(Uuid, i32)>,
+    teams_scored: i64,
+    total_teams: i64,
+}
+
 /// POST /api/admin/gameweek/:week/stats
 ///
 /// Batch upsert player stats for a gameweek, recalculate points.
 pub async fn submit_week_stats(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
     Path(week_number): Path<i32>,
     Json(stats): Json<Vec<PlayerStatInput>>,
 ) -> AppResult<Json<serde_json::Value>> {
-    let week = sqlx::query_as::<_, MatchWeek>(
-        "SELECT id, week_number, start_date, end_date, is_active FROM match_weeks WHERE week_number = $1",
+    open_week(&state.pool, week_number).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let (week, _) = lock_week_for(&mut tx, week_number, GameweekAction::Score).await?;
+    let before = stored_week_stats(&mut tx, &week, &stats).await?;
+    let outcome = score_week_stats(&mut tx, &week, &stats).await?;
+    let after = stored_week_stats(&mut tx, &week, &stats).await?;
+    AuditEntry::new(
+        auth.user_id,
+        "gameweek.stats.submit",
+        "gameweek",
+        week_number,
     )
-    .bind(week_number)
-    .fetch_optional(&state.pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("Gameweek {week_number} not found")))?;
+    .before(&before)
+    .after(&after)
+    .record(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    publish_week(&state, week_number).await;
+
+    Ok(Json(serde_json::json!({
+        "ok": true,
+        "players_updated": stats.len(),
+        "week": week_number,
+        "teams_scored": outcome.teams_scored,
+        // Teams that had not joined by the end of this week, so they are left out
+        // rather than scored against their current squad.
+        "teams_skipped": outcome.total_teams - outcome.teams_scored,
+    })))
+}
+
+/// POST /api/admin/gameweek/:week/stats/from-events
+///
+/// Build the week's stats from its matches' lineups and logged events, then
+/// score them like a submission. Only events inside fantasy time count.
+pub async fn score_week_from_events(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(week_number): Path<i32>,
+) -> AppResult<Json<serde_json::Value>> {
+    open_week(&state.pool, week_number).await?;
 
     let mut tx = state.pool.begin().await?;
+    let (week, _) = lock_week_for(&mut tx, week_number, GameweekAction::Score).await?;
+    let stats = super::matches::week_stats_from_events(&mut tx, week.id).await?;
+    if stats.is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "Gameweek {week_number} has no match lineups to score"
+        )));
+    }
+    let before = stored_week_stats(&mut tx, &week, &stats).await?;
+    let outcome = score_week_stats(&mut tx, &week, &stats).await?;
+    let after = stored_week_stats(&mut tx, &week, &stats).await?;
+    AuditEntry::new(
+        auth.user_id,
+        "gameweek.stats.events",
+        "gameweek",
+        week_number,
+    )
+    .before(&before)
+    .after(&after)
+    .record(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    publish_week(&state, week_number).await;
 
+    Ok(Json(serde_json::json!({
+        "ok": true,
+        "players_updated": stats.len(),
+        "week": week_number,
+        "teams_scored": outcome.teams_scored,
+        "teams_skipped": outcome.total_teams - outcome.teams_scored,
+    })))
+}
+
+/// POST /api/admin/gameweek/:week/stats/import?dry_run=true
+///
+/// Import a week's stats from a CSV body with one row per player and the same
+/// columns as the JSON submission, identifying players by `player_id` or
+/// `player_name`. Any bad line rejects the whole file. With `dry_run` the stats
+/// are scored exactly as a real submission would, then rolled back.
+pub async fn import_week_stats(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(week_number): Path<i32>,
+    Query(query): Query<StatsImportQuery>,
+    body: String,
+) -> AppResult<Json<StatsImportResponse>> {
+    open_week(&state.pool, week_number).await?;
+
+    let roster = sqlx::query_as::<_, (Uuid, String)>("SELECT id, name FROM players")
+        .fetch_all(&state.pool)
+        .await?;
+
+    let (parsed, errors) = stats_csv::parse_stats_csv(&body, &roster);
+    if !errors.is_empty() {
+        return Ok(Json(StatsImportResponse {
+            week: week_number,
+            dry_run: query.dry_run,
+            committed: false,
+            rows: Vec::new(),
+            errors,
+            teams_scored: 0,
+        }));
+    }
+
+    let stats: Vec<PlayerStatInput> = parsed.iter().map(|row| row.stat.clone()).collect();
+
+    let mut tx = state.pool.begin().await?;
+    let (week, _) = lock_week_for(&mut tx, week_number, GameweekAction::Score).await?;
+    let before = stored_week_stats(&mut tx, &week, &stats).await?;
+    let outcome = score_week_stats(&mut tx, &week, &stats).await?;
+    if query.dry_run {
+        tx.rollback().await?;
+    } else {
+        let after = stored_week_stats(&mut tx, &week, &stats).await?;
+        AuditEntry::new(
+            auth.user_id,
+            "gameweek.stats.import",
+            "gameweek",
+            week_number,
+        )
+        .before(&before)
+        .after(&after)
+        .record(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        publish_week(&state, week_number).await;
+    }
+
+    let rows = parsed
+        .into_iter()
+        .zip(outcome.player_points)
+        .map(|(row, (player_id, total_points))| StatsImportRow {
+            line: row.line,
+            player_id,
+            player_name: row.player_name,
+            total_points,
+        })
+        .collect();
+
+    Ok(Json(StatsImportResponse {
+        week: week_number,
+        dry_run: query.dry_run,
+        committed: !query.dry_run,
+        rows,
+        errors: Vec::new(),
+        teams_scored: outcome.teams_scored,
+    }))
+}
+
+/// POST /api/admin/gameweek/:week/stats/preview
+///
+/// Run a stats submission and roll it back, returning what it would change:
+/// each submitted player's points, the price moves finalising would make, team
+/// gameweek totals and league rank movement.
+pub async fn preview_week_stats(
+    State(state): State<AppState>,
+    Path(week_number): Path<i32>,
+    Json(stats): Json<Vec<PlayerStatInput>>,
+) -> AppResult<Json<StatsPreviewResponse>> {
+    let week = open_week(&state.pool, week_number).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let before = WeekSnapshot::capture(&mut tx, &week).await?;
+    let outcome = score_week_stats(&mut tx, &week, &stats).await?;
+    // Prices only move on finalisation; show where they would land.
+    apply_gameweek_price_adjustments(&mut tx, week.id).await?;
+    let after = WeekSnapshot::capture(&mut tx, &week).await?;
+    tx.rollback().await?;
+
+    let players_before: HashMap<Uuid, &SnapshotPlayer> =
+        before.players.iter().map(|p| (p.id, p)).collect();
+    let players_after: HashMap<Uuid, &SnapshotPlayer> =
+        after.players.iter().map(|p| (p.id, p)).collect();
+
+    let players = outcome
+        .player_points
+        .iter()
+        .map(|(player_id, points_after)| PlayerPointsChange {
+            player_id: *player_id,
+            player_name: players_after[player_id].name.clone(),
+            points_before: players_before.get(player_id).and_then(|p| p.week_points),
+            points_after: *points_after,
+        })
+        .collect();
+
+    let price_changes = after
+        .players
+        .iter()
+        .filter_map(|p| {
+            let price_before = players_before.get(&p.id)?.price;
+            (price_before != p.price).then(|| PlayerPriceChange {
+                player_id: p.id,
+                player_name: p.name.clone(),
+                price_before,
+                price_after: p.price,
+            })
+        })
+        .collect();
+
+    let teams_before: HashMap<Uuid, Option<i32>> =
+        before.teams.iter().map(|t| (t.id, t.week_points)).collect();
+    let teams = after
+        .teams
+        .into_iter()
+        .filter_map(|t| {
+            let points_before = teams_before.get(&t.id).copied().flatten();
+            (points_before != t.week_points).then_some(TeamPointsChange {
+                team_id: t.id,
+                team_name: t.name,
+                points_before,
+                points_after: t.week_points,
+            })
+        })
+        .collect();
+
+    let ranks_before: HashMap<(Uuid, Uuid), i64> = before
+        .ranks
+        .iter()
+        .map(|r| ((r.league_id, r.team_id), r.rank))
+        .collect();
+    let league_ranks = after
+        .ranks
+        .into_iter()
+        .filter_map(|r| {
+            let rank_before = *ranks_before.get(&(r.league_id, r.team_id))?;
+            (rank_before != r.rank).then_some(LeagueRankChange {
+                league_id: r.league_id,
+                league_name: r.league_name,
+                team_id: r.team_id,
+                team_name: r.team_name,
+                rank_before,
+                rank_after: r.rank,
+            })
+        })
+        .collect();
+
+    Ok(Json(StatsPreviewResponse {
+        week: week_number,
+        players,
+        price_changes,
+        teams,
+        league_ranks,
+    }))
+}
+
+#[derive(sqlx::FromRow)]
+struct SnapshotPlayer {
+    id: Uuid,
+    name: String,
+    price: Decimal,
+    week_points: Option<i32>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SnapshotTeam {
+    id: Uuid,
+    name: String,
+    week_points: Option<i32>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SnapshotRank {
+    league_id: Uuid,
+    league_name: String,
+    team_id: Uuid,
+    team_name: String,
+    rank: i64,
+}
+
+/// The state a stats submission touches, read inside the submission's transaction.
+struct WeekSnapshot {
+    players: Vec<SnapshotPlayer>,
+    teams: Vec<SnapshotTeam>,
+    ranks: Vec<SnapshotRank>,
+}
+
+impl WeekSnapshot {
+    async fn capture(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        week: &MatchWeek,
+    ) -> Result<Self, sqlx::Error> {
+        let players = sqlx::query_as::<_, SnapshotPlayer>(
+            r#"SELECT p.id, p.name, p.price, pp.total_points AS week_points
+               FROM players p
+               LEFT JOIN player_points pp ON pp.player_id = p.id AND pp.match_week_id = $1"#,
+        )
+        .bind(week.id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        let teams = sqlx::query_as::<_, SnapshotTeam>(
+            r#"SELECT ft.id, ft.name, tgp.total_points AS week_points
+               FROM fantasy_teams ft
+               LEFT JOIN team_gameweek_points tgp
+                 ON tgp.team_id = ft.id AND tgp.match_week_id = $1
+               ORDER BY ft.name"#,
+        )
+        .bind(week.id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        // Same season totals as the league leaderboard, ranked within each league.
+        let ranks = sqlx::query_as::<_, SnapshotRank>(
+            r#"SELECT lm.league_id,
+                      l.name AS league_name,
+                      ft.id AS team_id,
+                      ft.name AS team_name,
+                      RANK() OVER (PARTITION BY lm.league_id ORDER BY s.pts DESC) AS rank
+               FROM league_members lm
+               INNER JOIN leagues l ON l.id = lm.league_id
+               INNER JOIN fantasy_teams ft ON ft.user_id = lm.user_id
+               CROSS JOIN LATERAL (
+                 SELECT COALESCE(SUM(tgp.total_points), 0) AS pts
+                 FROM team_gameweek_points tgp
+                 INNER JOIN match_weeks mw ON mw.id = tgp.match_week_id
+                 WHERE tgp.team_id = ft.id AND mw.season_id = $1
+               ) s
+               ORDER BY l.name, rank, ft.name"#,
+        )
+        .bind(week.season_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(Self {
+            players,
+            teams,
+            ranks,
+        })
+    }
+}
+
+/// Recompute a lineup snapshot's automatic substitutions from this week's minutes.
+///
+/// Any earlier substitutions are undone first, so a stats correction (or the
+/// season rule being switched off) never leaves a stale sub in place.
+async fn apply_auto_subs(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    lineup_id: Uuid,
+    match_week_id: Uuid,
+    enabled: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"UPDATE team_gameweek_lineup_players SET is_bench = false
+           WHERE team_gameweek_lineup_id = $1
+             AND player_id IN (
+               SELECT auto_sub_for FROM team_gameweek_lineup_players
+               WHERE team_gameweek_lineup_id = $1 AND auto_sub_for IS NOT NULL
+             )"#,
+    )
+    .bind(lineup_id)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"UPDATE team_gameweek_lineup_players
+           SET is_bench = true, assigned_position = NULL, auto_sub_for = NULL
+           WHERE team_gameweek_lineup_id = $1 AND auto_sub_for IS NOT NULL"#,
+    )
+    .bind(lineup_id)
+    .execute(&mut **tx)
+    .await?;
+
+    if !enabled {
+        return Ok(());
+    }
+
+    #[derive(sqlx::FromRow)]
+    struct SquadRow {
+        player_id: Uuid,
+        position: PlayerPosition,
+        secondary_position: Option<PlayerPosition>,
+        assigned_position: Option<PlayerPosition>,
+        is_bench: bool,
+        bench_order: Option<i16>,
+        minutes_played: i32,
+    }
+
+    let squad: Vec<SquadMember> = sqlx::query_as::<_, SquadRow>(
+        r#"SELECT tglp.player_id, p.position, p.secondary_position, tglp.assigned_position,
+                  tglp.is_bench, tglp.bench_order,
+                  COALESCE(pp.minutes_played, 0) AS minutes_played
+           FROM team_gameweek_lineup_players tglp
+           JOIN players p ON p.id = tglp.player_id
+           LEFT JOIN player_points pp ON pp.player_id = tglp.player_id AND pp.match_week_id = $2
+           WHERE tglp.team_gameweek_lineup_id = $1
+           ORDER BY p.name"#,
+    )
+    .bind(lineup_id)
+    .bind(match_week_id)
+    .fetch_all(&mut **tx)
+    .await?
+    .into_iter()
+    .map(|r| SquadMember {
+        player_id: r.player_id,
+        position: r.position,
+        secondary_position: r.secondary_position,
+        assigned_position: r.assigned_position,
+        is_bench: r.is_bench,
+        bench_order: r.bench_order,
+        minutes_played: r.minutes_played,
+    })
+    .collect();
+
+    for sub in auto_subs::auto_substitutions(&squad) {
+        sqlx::query(
+            "UPDATE team_gameweek_lineup_players SET is_bench = true WHERE team_gameweek_lineup_id = $1 AND player_id = $2",
+        )
+        .bind(lineup_id)
+        .bind(sub.player_out)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"UPDATE team_gameweek_lineup_players
+               SET is_bench = false, assigned_position = $3, auto_sub_for = $4
+               WHERE team_gameweek_lineup_id = $1 AND player_id = $2"#,
+        )
+        .bind(lineup_id)
+        .bind(sub.player_in)
+        .bind(&sub.position)
+        .bind(sub.player_out)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// The stored `player_points` rows for the players in `stats`, as the audit log
+/// records them either side of a submission.
+async fn stored_week_stats(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    week: &MatchWeek,
+    stats: &[PlayerStatInput],
+) -> Result<Vec<AdminPlayerStats>, sqlx::Error> {
+    let player_ids: Vec<Uuid> = stats.iter().map(|s| s.player_id).collect();
+
+    sqlx::query_as::<_, AdminPlayerStats>(
+        r#"SELECT
+             p.id AS player_id,
+             p.name AS player_name,
+             p.position::text AS position,
+             pp.goals, pp.assists, pp.clean_sheets, pp.saves, pp.penalty_saves,
+             pp.own_goals, pp.penalty_misses, pp.regular_fouls, pp.serious_fouls,
+             pp.minutes_played, pp.total_points
+           FROM player_points pp
+           INNER JOIN players p ON p.id = pp.player_id
+           WHERE pp.match_week_id = $1 AND pp.player_id = ANY($2)
+           ORDER BY p.name"#,
+    )
+    .bind(week.id)
+    .bind(&player_ids)
+    .fetch_all(&mut **tx)
+    .await
+}
+
+/// Upsert `stats` for `week` and rescore everything that depends on them: player
+/// totals and every team's gameweek points. The week becomes provisional.
+async fn score_week_stats(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    week: &MatchWeek,
+    stats: &[PlayerStatInput],
+) -> AppResult<WeekStatsOutcome> {
+    let week_number = week.week_number;
+
+    let rules = sqlx::query_as::<_, ScoringRules>(points_sql::rules_for_week())
+        .bind(week.id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest(format!("No scoring rules apply to gameweek {week_number}"))
+        })?;
+
+    // Clean sheets and minutes follow the week's recorded matches, if it has any.
+    let appearances = sqlx::query_as::<_, Appearance>(
+        r#"SELECT ml.player_id,
+                  CASE ml.side WHEN 'home' THEN m.away_goals ELSE m.home_goals END AS conceded,
+                  m.fantasy_minutes, ml.minutes_played
+           FROM match_lineups ml
+           INNER JOIN matches m ON m.id = ml.match_id
+           WHERE m.match_week_id = $1"#,
+    )
+    .bind(week.id)
+    .fetch_all(&mut **tx)
+    .await?;
+    let stats = match matches::apply_match_facts(stats, &appearances) {
+        Ok(stats) => stats,
+        Err(unlisted) => {
+            let names = sqlx::query_scalar::<_, String>(
+                "SELECT name FROM players WHERE id = ANY($1) ORDER BY name",
+            )
+            .bind(&unlisted)
+            .fetch_all(&mut **tx)
+            .await?;
+            return Err(AppError::BadRequest(format!(
+                "Not in any match lineup for gameweek {week_number}: {}",
+                names.join(", ")
+            )));
+        }
+    };
+
+    let mut player_points = Vec::with_capacity(stats.len());
     for stat in &stats {
         let position: PlayerPosition =
             sqlx::query_scalar("SELECT position FROM players WHERE id = $1")
                 .bind(stat.player_id)
-                .fetch_one(&mut *tx)
+                .fetch_one(&mut **tx)
                 .await?;
 
-        let total = PointsEngine::calculate(
-            &position,
-            stat.goals,
-            stat.assists,
-            stat.clean_sheets,
-            stat.saves,
-            stat.penalty_saves,
-            stat.own_goals,
-            stat.penalty_misses,
-            stat.regular_fouls,
-            stat.serious_fouls,
-            stat.minutes_played,
-        );
+        let total = PointsEngine::calculate(&rules, &position, &MatchStats::from(stat));
 
         sqlx::query(
             r#"INSERT INTO player_points
@@ -408,39 +1164,23 @@ pub async fn submit_week_stats(
         .bind(stat.serious_fouls)
         .bind(stat.minutes_played)
         .bind(total)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
-    }
 
-    // Recalculate players.total_points as sum across all weeks (using primary position)
-    sqlx::query(
-        r#"UPDATE players SET total_points = sub.pts
-           FROM (
-             SELECT player_id, COALESCE(SUM(total_points), 0)::int AS pts
-             FROM player_points
-             GROUP BY player_id
-           ) sub
-           WHERE players.id = sub.player_id"#,
-    )
-    .execute(&mut *tx)
-    .await?;
-
-    apply_gameweek_price_adjustments(&mut tx, week.id).await?;
+        player_points.push((stat.player_id, total));
+    }
 
-    // Carry each user's team value forward as their next budget limit.
-    // This makes budget changes from price movements user-specific.
+    // Recalculate players.total_points as the open season's sum (using primary position)
     sqlx::query(
-        r#"UPDATE fantasy_teams ft
-           SET budget_limit = team_cost.total_cost
-           FROM (
-             SELECT tp.team_id, COALESCE(SUM(p.price), 0) AS total_cost
-             FROM team_players tp
-             JOIN players p ON p.id = tp.player_id
-             GROUP BY tp.team_id
-           ) AS team_cost
-           WHERE ft.id = team_cost.team_id"#,
+        r#"UPDATE players p SET total_points = COALESCE((
+             SELECT SUM(pp.total_points)
+             FROM player_points pp
+             INNER JOIN match_weeks mw ON mw.id = pp.match_week_id
+             INNER JOIN seasons s ON s.id = mw.season_id
+             WHERE pp.player_id = p.id AND s.closed_at IS NULL
+           ), 0)::int"#,
     )
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await?;
 
     #[derive(sqlx::FromRow)]
@@ -448,16 +1188,25 @@ pub async fn submit_week_stats(
         id: Uuid,
         lineup_id: Option<Uuid>,
         captain_id: Option<Uuid>,
+        vice_captain_id: Option<Uuid>,
+        transfer_hits_waived: bool,
     }
 
+    let (vice_captain_fallback, auto_subs_enabled) = sqlx::query_as::<_, (bool, bool)>(
+        "SELECT vice_captain_fallback, auto_subs FROM seasons WHERE id = $1",
+    )
+    .bind(week.season_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
     let total_teams = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM fantasy_teams")
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
     let teams = sqlx::query_as::<_, TeamScoreContext>(points_sql::scored_teams())
         .bind(week.id)
         .bind(week.end_date)
-        .fetch_all(&mut *tx)
+        .fetch_all(&mut **tx)
         .await?;
 
     let teams_scored = teams.len() as i64;
@@ -470,81 +1219,133 @@ pub async fn submit_week_stats(
             None => (points_sql::Source::LiveSquad, team.id),
         };
 
-        let starter_base = sqlx::query_scalar::<_, i64>(&points_sql::squad_half_total(source, false))
-            .bind(source_id)
-            .bind(week.id)
-            .fetch_one(&mut *tx)
-            .await?;
+        if let Some(lineup_id) = team.lineup_id {
+            apply_auto_subs(tx, lineup_id, week.id, auto_subs_enabled).await?;
+        }
 
-        let triple_captain_active = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM team_chips WHERE team_id = $1 AND match_week_id = $2 AND chip_type = 'triple_captain'",
-        )
-        .bind(team.id)
-        .bind(week.id)
-        .fetch_one(&mut *tx)
-        .await?
-            > 0;
+        let starter_base =
+            sqlx::query_scalar::<_, i64>(&points_sql::squad_half_total(source, false))
+                .bind(source_id)
+                .bind(week.id)
+                .fetch_one(&mut **tx)
+                .await?;
 
-        let bench_boost_active = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM team_chips WHERE team_id = $1 AND match_week_id = $2 AND chip_type = 'bench_boost'",
+        let chip = sqlx::query_as::<_, (ChipType, Option<Uuid>)>(
+            "SELECT chip_type, second_captain_id FROM team_chips WHERE team_id = $1 AND match_week_id = $2",
         )
         .bind(team.id)
         .bind(week.id)
-        .fetch_one(&mut *tx)
-        .await?
-            > 0;
+        .fetch_optional(&mut **tx)
+        .await?;
+        let chip_type = chip.map(|(chip_type, _)| chip_type);
+        let second_captain_id = chip.and_then(|(_, second_captain_id)| second_captain_id);
+
+        let captain_minutes = match team.captain_id {
+            Some(captain_id) if vice_captain_fallback && team.vice_captain_id.is_some() => {
+                sqlx::query_scalar::<_, i32>(
+                    "SELECT minutes_played FROM player_points WHERE player_id = $1 AND match_week_id = $2",
+                )
+                .bind(captain_id)
+                .bind(week.id)
+                .fetch_optional(&mut **tx)
+                .await?
+                .unwrap_or(0)
+            }
+            _ => 0,
+        };
+        let armband = PointsEngine::armband(
+            team.captain_id,
+            team.vice_captain_id,
+            captain_minutes,
+            vice_captain_fallback,
+        );
 
         // The captain is already counted once in `starter_base`, so adding his score
         // again makes 2x, and twice again makes 3x under Triple Captain.
-        let captain_bonus = if let Some(captain_id) = team.captain_id {
-            let captain_points =
+        let captain_points = match armband {
+            Some(captain_id) => {
                 sqlx::query_scalar::<_, i32>(&points_sql::single_starter_total(source))
                     .bind(source_id)
                     .bind(week.id)
                     .bind(captain_id)
-                    .fetch_optional(&mut *tx)
+                    .fetch_optional(&mut **tx)
                     .await?
-                    .unwrap_or(0);
-
-            if triple_captain_active {
-                (captain_points * 2) as i64
-            } else {
-                captain_points as i64
+                    .unwrap_or(0)
             }
+            None => 0,
+        };
+        let captain_bonus = if chip_type == Some(ChipType::TripleCaptain) {
+            (captain_points * 2) as i64
         } else {
-            0
+            captain_points as i64
+        };
+
+        // Double Captain: a second starter is also counted twice.
+        let second_captain_bonus = match second_captain_id {
+            Some(player_id)
+                if chip_type == Some(ChipType::DoubleCaptain) && armband != Some(player_id) =>
+            {
+                sqlx::query_scalar::<_, i32>(&points_sql::single_starter_total(source))
+                    .bind(source_id)
+                    .bind(week.id)
+                    .bind(player_id)
+                    .fetch_optional(&mut **tx)
+                    .await?
+                    .unwrap_or(0) as i64
+            }
+            _ => 0,
         };
 
-        let bench_bonus = if bench_boost_active {
+        let bench_bonus = if chip_type == Some(ChipType::BenchBoost) {
             sqlx::query_scalar::<_, i64>(&points_sql::squad_half_total(source, true))
                 .bind(source_id)
                 .bind(week.id)
-                .fetch_one(&mut *tx)
+                .fetch_one(&mut **tx)
                 .await?
         } else {
             0
         };
 
-        let transfers_this_week = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM transfers WHERE team_id = $1 AND match_week_id = $2",
-        )
-        .bind(team.id)
-        .bind(week.id)
-        .fetch_one(&mut *tx)
-        .await?;
+        // Cup tiebreakers: the armband's points with the multiplier, and goals by
+        // every player who counted.
+        let mut goals = sqlx::query_scalar::<_, i64>(&points_sql::squad_half_goals(source, false))
+            .bind(source_id)
+            .bind(week.id)
+            .fetch_one(&mut **tx)
+            .await?;
+        if chip_type == Some(ChipType::BenchBoost) {
+            goals += sqlx::query_scalar::<_, i64>(&points_sql::squad_half_goals(source, true))
+                .bind(source_id)
+                .bind(week.id)
+                .fetch_one(&mut **tx)
+                .await?;
+        }
+        let armband_points = captain_points as i64 + captain_bonus;
 
-        let transfer_points_hit = ((transfers_this_week as i32) - 1).max(0) * 4;
-        let gross_points = (starter_base + captain_bonus + bench_bonus) as i32;
+        // Banked transfers and the hit cost come from the season's policy. A Wildcard
+        // or Free Hit marks the snapshot, so the waiver survives any rescore.
+        let transfer_points_hit = if team.transfer_hits_waived {
+            0
+        } else {
+            transfer_policy::team_allowance(tx, team.id, week.id)
+                .await?
+                .points_hit
+        };
+        let gross_points =
+            (starter_base + captain_bonus + second_captain_bonus + bench_bonus) as i32;
         let total_points = gross_points - transfer_points_hit;
 
         sqlx::query(
             r#"INSERT INTO team_gameweek_points
-                 (team_id, match_week_id, gross_points, transfer_points_hit, total_points)
-               VALUES ($1, $2, $3, $4, $5)
+                 (team_id, match_week_id, gross_points, transfer_points_hit, total_points,
+                  captain_points, goals)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
                ON CONFLICT (team_id, match_week_id) DO UPDATE SET
                  gross_points = EXCLUDED.gross_points,
                  transfer_points_hit = EXCLUDED.transfer_points_hit,
                  total_points = EXCLUDED.total_points,
+                 captain_points = EXCLUDED.captain_points,
+                 goals = EXCLUDED.goals,
                  updated_at = NOW()"#,
         )
         .bind(team.id)
@@ -552,21 +1353,24 @@ pub async fn submit_week_stats(
         .bind(gross_points)
         .bind(transfer_points_hit)
         .bind(total_points)
-        .execute(&mut *tx)
+        .bind(armband_points as i32)
+        .bind(goals as i32)
+        .execute(&mut **tx)
         .await?;
     }
 
-    tx.commit().await?;
+    // Prices and league results wait for the week to be finalised.
+    sqlx::query("UPDATE match_weeks SET status = $2 WHERE id = $1")
+        .bind(week.id)
+        .bind(GameweekStatus::Provisional)
+        .execute(&mut **tx)
+        .await?;
 
-    Ok(Json(serde_json::json!({
-        "ok": true,
-        "players_updated": stats.len(),
-        "week": week_number,
-        "teams_scored": teams_scored,
-        // Teams that had not joined by the end of this week, so they are left out
-        // rather than scored against their current squad.
-        "teams_skipped": total_teams - teams_scored,
-    })))
+    Ok(WeekStatsOutcome {
+        player_points,
+        teams_scored,
+        total_teams,
+    })
 }
 
 /// GET /api/admin/lineup-lock
@@ -582,21 +1386,46 @@ pub async fn get_lineup_lock_control(
     .await?
     .unwrap_or(false);
 
-    let lock = compute_lock_status(&state.pool).await?;
+    lineup_lock_response(&state.pool, force_unlock).await
+}
+
+async fn lineup_lock_response(
+    pool: &sqlx::PgPool,
+    force_unlock: bool,
+) -> AppResult<Json<AdminLineupLockResponse>> {
+    let lock = compute_lock_status(pool).await?;
+    let schedule = sqlx::query_as::<_, LockSchedule>(
+        r#"SELECT timezone, lock_weekday, lock_time, unlock_weekday, unlock_time
+           FROM lineup_lock_control WHERE id = true"#,
+    )
+    .fetch_one(pool)
+    .await?;
     Ok(Json(AdminLineupLockResponse {
         force_unlock,
         effective_locked: lock.locked,
+        deadline_at: lock.deadline_at,
         unlock_at: lock.unlock_at,
+        schedule,
     }))
 }
 
 /// PUT /api/admin/lineup-lock
 ///
-/// Allows admins to manually unlock/restore the scheduled weekend lock.
+/// Allows admins to manually unlock/restore the scheduled lock.
 pub async fn set_lineup_lock_control(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
     Json(body): Json<SetLineupLockRequest>,
 ) -> AppResult<Json<AdminLineupLockResponse>> {
+    let mut tx = state.pool.begin().await?;
+
+    let previous = sqlx::query_scalar::<_, bool>(
+        "SELECT force_unlock FROM lineup_lock_control WHERE id = true",
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .unwrap_or(false);
+
     sqlx::query(
         r#"INSERT INTO lineup_lock_control (id, force_unlock)
            VALUES (true, $1)
@@ -605,13 +1434,191 @@ pub async fn set_lineup_lock_control(
              updated_at = NOW()"#,
     )
     .bind(body.force_unlock)
-    .execute(&state.pool)
+    .execute(&mut *tx)
     .await?;
 
-    let lock = compute_lock_status(&state.pool).await?;
-    Ok(Json(AdminLineupLockResponse {
-        force_unlock: body.force_unlock,
-        effective_locked: lock.locked,
-        unlock_at: lock.unlock_at,
-    }))
+    AuditEntry::new(auth.user_id, "lineup_lock.set", "lineup_lock", "global")
+        .before(serde_json::json!({ "force_unlock": previous }))
+        .after(serde_json::json!({ "force_unlock": body.force_unlock }))
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    lineup_lock_response(&state.pool, body.force_unlock).await
+}
+
+/// PUT /api/admin/lineup-lock/schedule
+///
+/// Change the weekly lock that gameweeks without their own deadline follow.
+pub async fn set_lock_schedule(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Json(body): Json<LockSchedule>,
+) -> AppResult<Json<AdminLineupLockResponse>> {
+    WeeklyLock::from_settings(&body).map_err(AppError::BadRequest)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let previous = sqlx::query_as::<_, LockSchedule>(
+        r#"SELECT timezone, lock_weekday, lock_time, unlock_weekday, unlock_time
+           FROM lineup_lock_control WHERE id = true FOR UPDATE"#,
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let force_unlock = sqlx::query_scalar::<_, bool>(
+        r#"UPDATE lineup_lock_control SET
+             timezone = $1,
+             lock_weekday = $2,
+             lock_time = $3,
+             unlock_weekday = $4,
+             unlock_time = $5,
+             updated_at = NOW()
+           WHERE id = true
+           RETURNING force_unlock"#,
+    )
+    .bind(&body.timezone)
+    .bind(body.lock_weekday)
+    .bind(body.lock_time)
+    .bind(body.unlock_weekday)
+    .bind(body.unlock_time)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEntry::new(
+        auth.user_id,
+        "lineup_lock.schedule",
+        "lineup_lock",
+        "global",
+    )
+    .before(&previous)
+    .after(&body)
+    .record(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    lineup_lock_response(&state.pool, force_unlock).await
+}
+
+/// GET /api/admin/scoring-rules
+///
+/// List every scoring rule set version, oldest first.
+pub async fn list_scoring_rules(
+    State(state): State<AppState>,
+) -> AppResult<Json<Vec<ScoringRules>>> {
+    let rules = sqlx::query_as::<_, ScoringRules>(
+        r#"SELECT sr.* FROM scoring_rules sr
+           INNER JOIN seasons s ON s.id = sr.season_id
+           ORDER BY s.start_week"#,
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(rules))
+}
+
+/// POST /api/admin/scoring-rules
+///
+/// Add the scoring rule set for a season (`season_id`, default the open one).
+/// Versions are append-only, and a season may only get one before any of its
+/// weeks has stats, so the points already stored for a week always match the
+/// rules that produced them.
+pub async fn create_scoring_rules(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Json(body): Json<CreateScoringRulesRequest>,
+) -> AppResult<Json<ScoringRules>> {
+    if body.name.trim().is_empty() {
+        return Err(AppError::BadRequest(
+            "Rule set name is required".to_string(),
+        ));
+    }
+    if body.saves_per_point < 1 || body.long_appearance_minutes < 1 {
+        return Err(AppError::BadRequest(
+            "saves_per_point and long_appearance_minutes must be at least 1".to_string(),
+        ));
+    }
+
+    let season = resolve_season(&state.pool, body.season_id).await?;
+    if season.closed_at.is_some() {
+        return Err(AppError::BadRequest(format!(
+            "{} {} is closed",
+            season.name, season.year
+        )));
+    }
+
+    let scored_week = sqlx::query_scalar::<_, Option<i32>>(
+        r#"SELECT MIN(mw.week_number)
+           FROM player_points pp
+           JOIN match_weeks mw ON mw.id = pp.match_week_id
+           WHERE mw.season_id = $1"#,
+    )
+    .bind(season.id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if let Some(week) = scored_week {
+        return Err(AppError::Conflict(format!(
+            "Gameweek {week} of {} {} already has stats. Rules can only change between seasons.",
+            season.name, season.year
+        )));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let rules = sqlx::query_as::<_, ScoringRules>(
+        r#"INSERT INTO scoring_rules (
+             name, season_id, goal_gk, goal_def, goal_mid, goal_fwd, assist,
+             clean_sheet_gk, clean_sheet_def, clean_sheet_mid, clean_sheet_fwd,
+             saves_per_point, penalty_save, long_appearance_minutes, long_appearance,
+             short_appearance, own_goal, penalty_miss, regular_foul, serious_foul)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+                   $16, $17, $18, $19, $20)
+           ON CONFLICT (season_id) DO NOTHING
+           RETURNING *"#,
+    )
+    .bind(body.name.trim())
+    .bind(season.id)
+    .bind(body.goal_gk)
+    .bind(body.goal_def)
+    .bind(body.goal_mid)
+    .bind(body.goal_fwd)
+    .bind(body.assist)
+    .bind(body.clean_sheet_gk)
+    .bind(body.clean_sheet_def)
+    .bind(body.clean_sheet_mid)
+    .bind(body.clean_sheet_fwd)
+    .bind(body.saves_per_point)
+    .bind(body.penalty_save)
+    .bind(body.long_appearance_minutes)
+    .bind(body.long_appearance)
+    .bind(body.short_appearance)
+    .bind(body.own_goal)
+    .bind(body.penalty_miss)
+    .bind(body.regular_foul)
+    .bind(body.serious_foul)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| {
+        AppError::Conflict(format!(
+            "{} {} already has a rule set",
+            season.name, season.year
+        ))
+    })?;
+
+    AuditEntry::new(
+        auth.user_id,
+        "scoring_rules.create",
+        "scoring_rules",
+        rules.id,
+    )
+    .after(&rules)
+    .record(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(rules))
 }
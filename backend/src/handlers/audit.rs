@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::auth::handler::AppState;
+use crate::error::{AppError, AppResult};
+use crate::models::{AuditEvent, AuditPage, AuditQuery};
+
+const DEFAULT_PER_PAGE: i64 = 50;
+const MAX_PER_PAGE: i64 = 200;
+
+/// Shared filter for the page and its count. Binds `$1`..`$6` in `AuditQuery` order.
+const AUDIT_FILTER: &str = r#"
+    WHERE ($1::uuid IS NULL OR ae.actor_id = $1)
+      AND ($2::text IS NULL OR ae.action = $2)
+      AND ($3::text IS NULL OR ae.target_type = $3)
+      AND ($4::text IS NULL OR ae.target_id = $4)
+      AND ($5::timestamptz IS NULL OR ae.created_at >= $5)
+      AND ($6::timestamptz IS NULL OR ae.created_at < $6)"#;
+
+/// GET /api/admin/audit
+///
+/// Page through admin audit events, newest first. Filter by `actor_id`,
+/// `action`, `target_type`, `target_id` and a `since`/`until` time range.
+pub async fn list_audit_events(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> AppResult<Json<AuditPage>> {
+    let page = query.page.unwrap_or(1);
+    if page < 1 {
+        return Err(AppError::BadRequest("page must be at least 1".to_string()));
+    }
+    let per_page = query
+        .per_page
+        .unwrap_or(DEFAULT_PER_PAGE)
+        .clamp(1, MAX_PER_PAGE);
+    let offset = (page - 1)
+        .checked_mul(per_page)
+        .ok_or_else(|| AppError::BadRequest("page is too large".to_string()))?;
+
+    let total = sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT COUNT(*) FROM audit_events ae {AUDIT_FILTER}"
+    ))
+    .bind(query.actor_id)
+    .bind(&query.action)
+    .bind(&query.target_type)
+    .bind(&query.target_id)
+    .bind(query.since)
+    .bind(query.until)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let events = sqlx::query_as::<_, AuditEvent>(&format!(
+        r#"SELECT ae.id, ae.actor_id, u.username AS actor_username, ae.action,
+                  ae.target_type, ae.target_id, ae.before, ae.after, ae.created_at
+           FROM audit_events ae
+           LEFT JOIN users u ON u.id = ae.actor_id
+           {AUDIT_FILTER}
+           ORDER BY ae.created_at DESC, ae.id DESC
+           LIMIT $7 OFFSET $8"#
+    ))
+    .bind(query.actor_id)
+    .bind(&query.action)
+    .bind(&query.target_type)
+    .bind(&query.target_id)
+    .bind(query.since)
+    .bind(query.until)
+    .bind(per_page)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(AuditPage {
+        events,
+        page,
+        per_page,
+        total,
+    }))
+}
@@ -3,71 +3,127 @@ use axum::{
     Json,
 };
 use chrono::Utc;
-use chrono_tz::America::New_York;
+use chrono_tz::Tz;
 use uuid::Uuid;
 
 use crate::auth::handler::AppState;
 use crate::auth::middleware::AuthUser;
 use crate::error::{AppError, AppResult};
 use crate::models::{
-    ActivateChipRequest, ActiveGameweek, ChipInfo, ChipRow, ChipStatusResponse, FantasyTeam,
+    ActivateChipRequest, ActiveGameweek, ChipDefinition, ChipInfo, ChipRow, ChipStatusResponse,
+    ChipType, FantasyTeam, UpdateChipDefinitionRequest,
 };
+use crate::services::audit::AuditEntry;
+use crate::services::free_hit;
 
-use super::teams::compute_lock_status;
+use super::seasons::resolve_season;
+use super::teams::{compute_lock_status, lock_schedule, snapshot_team_lineup_if_missing};
 
-fn chip_can_deactivate(chip: &ChipRow) -> bool {
-    let today_et = Utc::now().with_timezone(&New_York).date_naive();
-    today_et < chip.start_date
+/// A chip can be taken back until its gameweek's deadline, or with no deadline
+/// of its own, until the week starts in the lock schedule's timezone.
+fn chip_can_deactivate(chip: &ChipRow, timezone: Tz) -> bool {
+    let now = Utc::now();
+    match chip.deadline_at {
+        Some(deadline_at) => now < deadline_at,
+        None => now.with_timezone(&timezone).date_naive() < chip.start_date,
+    }
+}
+
+/// Whether two gameweeks fall in the same half of a season split at `second_half_start_week`.
+fn same_half(week: i32, other: i32, second_half_start_week: Option<i32>) -> bool {
+    second_half_start_week.is_none_or(|split| (week >= split) == (other >= split))
+}
+
+async fn chip_definitions(pool: &sqlx::PgPool) -> Result<Vec<ChipDefinition>, sqlx::Error> {
+    sqlx::query_as::<_, ChipDefinition>(
+        "SELECT chip_type, name, description, uses_per_season, per_half_season FROM chip_definitions ORDER BY sort_order",
+    )
+    .fetch_all(pool)
+    .await
 }
 
 async fn build_chip_status(
     pool: &sqlx::PgPool,
     team_id: Uuid,
 ) -> Result<ChipStatusResponse, AppError> {
+    let season = resolve_season(pool, None).await?;
     let chips = sqlx::query_as::<_, ChipRow>(
-        r#"SELECT tc.chip_type, mw.week_number, mw.start_date
+        r#"SELECT tc.id, tc.chip_type, tc.match_week_id, mw.week_number, mw.start_date,
+                  mw.deadline_at
            FROM team_chips tc
            INNER JOIN match_weeks mw ON mw.id = tc.match_week_id
-           WHERE tc.team_id = $1"#,
+           WHERE tc.team_id = $1 AND tc.season_id = $2
+           ORDER BY mw.week_number"#,
     )
     .bind(team_id)
+    .bind(season.id)
     .fetch_all(pool)
     .await?;
 
-    let tc_chip = chips.iter().find(|c| c.chip_type == "triple_captain");
-    let bb_chip = chips.iter().find(|c| c.chip_type == "bench_boost");
-
     let active_gw = sqlx::query_as::<_, (Uuid, i32)>(
         "SELECT id, week_number FROM match_weeks WHERE is_active = true LIMIT 1",
     )
     .fetch_optional(pool)
     .await?;
 
+    let timezone = lock_schedule(pool).await?.timezone;
+    let catalogue = chip_definitions(pool)
+        .await?
+        .into_iter()
+        .map(|def| {
+            let uses: Vec<&ChipRow> = chips
+                .iter()
+                .filter(|c| c.chip_type == def.chip_type)
+                .collect();
+            let counted = uses
+                .iter()
+                .filter(|c| {
+                    !def.per_half_season
+                        || active_gw.is_none_or(|(_, week)| {
+                            same_half(c.week_number, week, season.second_half_start_week)
+                        })
+                })
+                .count() as i32;
+            let remaining = (def.uses_per_season - counted).max(0);
+            ChipInfo {
+                chip_type: def.chip_type,
+                name: def.name,
+                description: def.description,
+                uses_per_season: def.uses_per_season,
+                per_half_season: def.per_half_season,
+                remaining,
+                available: remaining > 0,
+                used_in_week: uses.last().map(|c| c.week_number),
+                used_in_weeks: uses.iter().map(|c| c.week_number).collect(),
+                can_deactivate: uses.iter().any(|c| chip_can_deactivate(c, timezone)),
+            }
+        })
+        .collect();
+
+    let active_chip = active_gw.and_then(|(id, _)| {
+        chips
+            .iter()
+            .find(|c| c.match_week_id == id)
+            .map(|c| c.chip_type)
+    });
+
     Ok(ChipStatusResponse {
-        triple_captain: ChipInfo {
-            available: tc_chip.is_none(),
-            used_in_week: tc_chip.map(|c| c.week_number),
-            can_deactivate: tc_chip.map_or(false, chip_can_deactivate),
-        },
-        bench_boost: ChipInfo {
-            available: bb_chip.is_none(),
-            used_in_week: bb_chip.map(|c| c.week_number),
-            can_deactivate: bb_chip.map_or(false, chip_can_deactivate),
-        },
+        chips: catalogue,
+        active_chip,
         active_gameweek: active_gw.map(|(id, week_number)| ActiveGameweek { id, week_number }),
     })
 }
 
 /// GET /api/teams/:id/chips
 ///
-/// Get chip status for a team (which chips are available/used).
+/// Get chip status for a team: every chip in the catalogue with its remaining uses.
 pub async fn get_chip_status(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthUser>,
     Path(team_id): Path<Uuid>,
 ) -> AppResult<Json<ChipStatusResponse>> {
     let _team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
     )
     .bind(team_id)
     .bind(auth.user_id)
@@ -81,8 +137,10 @@ pub async fn get_chip_status(
 
 /// POST /api/teams/:id/chips
 ///
-/// Activate a chip (triple_captain or bench_boost) for the current active gameweek.
-/// Each chip can only be used once per team. Can be deactivated before the gameweek starts.
+/// Activate a chip for the current active gameweek. Each chip can be played as many
+/// times per season as `chip_definitions` allows, and only one chip per gameweek.
+/// Double Captain needs a `second_captain_id`; Free Hit saves the squad so it can be
+/// restored afterwards. Can be deactivated before the gameweek starts.
 pub async fn activate_chip(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthUser>,
@@ -91,19 +149,11 @@ pub async fn activate_chip(
 ) -> AppResult<Json<ChipStatusResponse>> {
     let lock = compute_lock_status(&state.pool).await?;
     if lock.locked {
-        return Err(AppError::BadRequest(
-            "Chips cannot be activated during the lock period (Saturday 10:00 PM ET to Sunday 12:00 PM ET)".to_string(),
-        ));
-    }
-
-    if body.chip_type != "triple_captain" && body.chip_type != "bench_boost" {
-        return Err(AppError::BadRequest(
-            "Invalid chip type. Must be 'triple_captain' or 'bench_boost'".to_string(),
-        ));
+        return Err(lock.locked_error("Chips cannot be activated"));
     }
 
-    let _team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
+    let team = sqlx::query_as::<_, FantasyTeam>(
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
     )
     .bind(team_id)
     .bind(auth.user_id)
@@ -111,8 +161,8 @@ pub async fn activate_chip(
     .await?
     .ok_or_else(|| AppError::NotFound("Team not found or access denied".to_string()))?;
 
-    let active_gw = sqlx::query_as::<_, (Uuid, i32)>(
-        "SELECT id, week_number FROM match_weeks WHERE is_active = true LIMIT 1",
+    let active_gw = sqlx::query_as::<_, (Uuid, i32, Uuid)>(
+        "SELECT id, week_number, season_id FROM match_weeks WHERE is_active = true LIMIT 1",
     )
     .fetch_optional(&state.pool)
     .await?
@@ -120,31 +170,164 @@ pub async fn activate_chip(
         AppError::BadRequest("No active gameweek. Cannot activate chip right now.".to_string())
     })?;
 
-    let already_used = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM team_chips WHERE team_id = $1 AND chip_type = $2",
+    let (uses_per_season, per_half_season) = sqlx::query_as::<_, (i32, bool)>(
+        "SELECT uses_per_season, per_half_season FROM chip_definitions WHERE chip_type = $1",
+    )
+    .bind(body.chip_type)
+    .fetch_optional(&state.pool)
+    .await?
+    .unwrap_or((0, false));
+
+    let season = resolve_season(&state.pool, Some(active_gw.2)).await?;
+    let used_weeks = sqlx::query_scalar::<_, i32>(
+        r#"SELECT mw.week_number
+           FROM team_chips tc
+           INNER JOIN match_weeks mw ON mw.id = tc.match_week_id
+           WHERE tc.team_id = $1 AND tc.chip_type = $2 AND tc.season_id = $3"#,
+    )
+    .bind(team_id)
+    .bind(body.chip_type)
+    .bind(active_gw.2)
+    .fetch_all(&state.pool)
+    .await?;
+    let already_used = used_weeks
+        .iter()
+        .filter(|week| {
+            !per_half_season || same_half(**week, active_gw.1, season.second_half_start_week)
+        })
+        .count() as i32;
+
+    if uses_per_season == 0 {
+        return Err(AppError::BadRequest(format!(
+            "The {} chip is not available this season.",
+            body.chip_type.label()
+        )));
+    }
+    if already_used >= uses_per_season {
+        return Err(AppError::Conflict(if per_half_season {
+            format!(
+                "You have already used the {} chip in this half of the season.",
+                body.chip_type.label()
+            )
+        } else if uses_per_season == 1 {
+            format!(
+                "You have already used the {} chip. It can only be activated once per season.",
+                body.chip_type.label()
+            )
+        } else {
+            format!(
+                "You have already used the {} chip {uses_per_season} times, the most allowed per season.",
+                body.chip_type.label()
+            )
+        }));
+    }
+
+    let chip_this_week = sqlx::query_scalar::<_, ChipType>(
+        "SELECT chip_type FROM team_chips WHERE team_id = $1 AND match_week_id = $2",
     )
     .bind(team_id)
-    .bind(&body.chip_type)
-    .fetch_one(&state.pool)
+    .bind(active_gw.0)
+    .fetch_optional(&state.pool)
     .await?;
 
-    if already_used > 0 {
+    let one_chip_per_week = |chip_type: ChipType| {
+        AppError::Conflict(format!(
+            "You have already played the {} chip in gameweek {}. Only one chip can be played per gameweek.",
+            chip_type.label(),
+            active_gw.1
+        ))
+    };
+    if let Some(chip_type) = chip_this_week {
+        return Err(one_chip_per_week(chip_type));
+    }
+
+    if let Some(free_hit_week) = free_hit::squad_in_play(&state.pool, team_id, active_gw.1).await? {
         return Err(AppError::Conflict(format!(
-            "You have already used the {} chip. It can only be activated once.",
-            body.chip_type.replace('_', " ")
+            "Your Free Hit squad from gameweek {} plays in gameweek {}. Only one chip can be played per gameweek.",
+            free_hit_week, active_gw.1
         )));
     }
 
-    sqlx::query("INSERT INTO team_chips (team_id, chip_type, match_week_id) VALUES ($1, $2, $3)")
-        .bind(team_id)
-        .bind(&body.chip_type)
-        .bind(active_gw.0)
-        .execute(&state.pool)
-        .await?;
+    match (body.chip_type, body.second_captain_id) {
+        (ChipType::DoubleCaptain, None) => {
+            return Err(AppError::BadRequest(
+                "Double Captain needs a second_captain_id".to_string(),
+            ));
+        }
+        (ChipType::DoubleCaptain, Some(second_captain_id)) => {
+            if team.captain_id == Some(second_captain_id) {
+                return Err(AppError::BadRequest(
+                    "The second captain must be a different player from the captain".to_string(),
+                ));
+            }
+            let is_starter = sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM team_players WHERE team_id = $1 AND player_id = $2 AND is_bench = false",
+            )
+            .bind(team_id)
+            .bind(second_captain_id)
+            .fetch_one(&state.pool)
+            .await?;
+            if is_starter == 0 {
+                return Err(AppError::BadRequest(
+                    "The second captain must be one of your starting players".to_string(),
+                ));
+            }
+        }
+        (_, Some(_)) => {
+            return Err(AppError::BadRequest(
+                "second_captain_id only applies to the double captain chip".to_string(),
+            ));
+        }
+        (_, None) => {}
+    }
+
+    if body.chip_type.waives_transfer_hits() {
+        snapshot_team_lineup_if_missing(&state.pool, team_id, active_gw.0).await?;
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let inserted = sqlx::query_scalar::<_, Uuid>(
+        r#"INSERT INTO team_chips (team_id, chip_type, match_week_id, season_id, second_captain_id)
+           VALUES ($1, $2, $3, $4, $5)
+           RETURNING id"#,
+    )
+    .bind(team_id)
+    .bind(body.chip_type)
+    .bind(active_gw.0)
+    .bind(active_gw.2)
+    .bind(body.second_captain_id)
+    .fetch_one(&mut *tx)
+    .await;
+    let team_chip_id = match inserted {
+        Ok(id) => id,
+        // Another activation for the same week committed first.
+        Err(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+            let chip_type = sqlx::query_scalar::<_, ChipType>(
+                "SELECT chip_type FROM team_chips WHERE team_id = $1 AND match_week_id = $2",
+            )
+            .bind(team_id)
+            .bind(active_gw.0)
+            .fetch_one(&state.pool)
+            .await?;
+            return Err(one_chip_per_week(chip_type));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if body.chip_type == ChipType::FreeHit {
+        free_hit::save_squad(&mut tx, team_chip_id, team_id).await?;
+    }
+
+    if body.chip_type.waives_transfer_hits() {
+        set_transfer_hits_waived(&mut tx, team_id, active_gw.0, true).await?;
+    }
+
+    tx.commit().await?;
 
     tracing::info!(
         "Chip '{}' activated for team {} in gameweek {}",
-        body.chip_type,
+        body.chip_type.label(),
         team_id,
         active_gw.1
     );
@@ -156,20 +339,15 @@ pub async fn activate_chip(
 /// DELETE /api/teams/:id/chips/:chip_type
 ///
 /// Deactivate a chip before its gameweek starts. Once the gameweek has begun
-/// the chip is locked in and cannot be cancelled.
+/// the chip is locked in and cannot be cancelled. Cancelling a Free Hit puts
+/// the saved squad back and drops the transfers made under it.
 pub async fn deactivate_chip(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthUser>,
-    Path((team_id, chip_type)): Path<(Uuid, String)>,
+    Path((team_id, chip_type)): Path<(Uuid, ChipType)>,
 ) -> AppResult<Json<ChipStatusResponse>> {
-    if chip_type != "triple_captain" && chip_type != "bench_boost" {
-        return Err(AppError::BadRequest(
-            "Invalid chip type. Must be 'triple_captain' or 'bench_boost'".to_string(),
-        ));
-    }
-
     let _team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
     )
     .bind(team_id)
     .bind(auth.user_id)
@@ -177,35 +355,62 @@ pub async fn deactivate_chip(
     .await?
     .ok_or_else(|| AppError::NotFound("Team not found or access denied".to_string()))?;
 
+    let season = resolve_season(&state.pool, None).await?;
     let chip = sqlx::query_as::<_, ChipRow>(
-        r#"SELECT tc.chip_type, mw.week_number, mw.start_date
+        r#"SELECT tc.id, tc.chip_type, tc.match_week_id, mw.week_number, mw.start_date,
+                  mw.deadline_at
            FROM team_chips tc
            INNER JOIN match_weeks mw ON mw.id = tc.match_week_id
-           WHERE tc.team_id = $1 AND tc.chip_type = $2"#,
+           WHERE tc.team_id = $1 AND tc.chip_type = $2 AND tc.season_id = $3
+           ORDER BY mw.week_number DESC
+           LIMIT 1"#,
     )
     .bind(team_id)
-    .bind(&chip_type)
+    .bind(chip_type)
+    .bind(season.id)
     .fetch_optional(&state.pool)
     .await?
     .ok_or_else(|| AppError::NotFound("Chip not found or not activated".to_string()))?;
 
-    if !chip_can_deactivate(&chip) {
+    let timezone = lock_schedule(&state.pool).await?.timezone;
+    if !chip_can_deactivate(&chip, timezone) {
         return Err(AppError::BadRequest(format!(
             "Cannot deactivate {} — gameweek {} has already started. The chip is permanently used.",
-            chip_type.replace('_', " "),
+            chip_type.label(),
             chip.week_number
         )));
     }
 
-    sqlx::query("DELETE FROM team_chips WHERE team_id = $1 AND chip_type = $2")
+    let mut tx = state.pool.begin().await?;
+
+    if chip_type == ChipType::FreeHit {
+        free_hit::restore_squad(&mut tx, chip.id, team_id).await?;
+        sqlx::query(
+            r#"DELETE FROM transfers
+               WHERE team_id = $1 AND match_week_id = $2
+                 AND created_at >= (SELECT created_at FROM team_chips WHERE id = $3)"#,
+        )
         .bind(team_id)
-        .bind(&chip_type)
-        .execute(&state.pool)
+        .bind(chip.match_week_id)
+        .bind(chip.id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if chip_type.waives_transfer_hits() {
+        set_transfer_hits_waived(&mut tx, team_id, chip.match_week_id, false).await?;
+    }
+
+    sqlx::query("DELETE FROM team_chips WHERE id = $1")
+        .bind(chip.id)
+        .execute(&mut *tx)
         .await?;
 
+    tx.commit().await?;
+
     tracing::info!(
         "Chip '{}' deactivated for team {} (was set for gameweek {})",
-        chip_type,
+        chip_type.label(),
         team_id,
         chip.week_number
     );
@@ -213,3 +418,70 @@ pub async fn deactivate_chip(
     let status = build_chip_status(&state.pool, team_id).await?;
     Ok(Json(status))
 }
+
+/// Mark a gameweek's lineup snapshot so scoring charges no transfer hits for it.
+async fn set_transfer_hits_waived(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    team_id: Uuid,
+    match_week_id: Uuid,
+    waived: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE team_gameweek_lineups SET transfer_hits_waived = $3 WHERE team_id = $1 AND match_week_id = $2",
+    )
+    .bind(team_id)
+    .bind(match_week_id)
+    .bind(waived)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// PUT /api/admin/chips/:chip_type
+///
+/// Set how many times a chip can be played per season, and whether that count
+/// applies to each half separately. 0 takes it out of play.
+pub async fn update_chip_definition(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(chip_type): Path<ChipType>,
+    Json(body): Json<UpdateChipDefinitionRequest>,
+) -> AppResult<Json<Vec<ChipDefinition>>> {
+    if body.uses_per_season < 0 {
+        return Err(AppError::BadRequest(
+            "uses_per_season cannot be negative".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let before = sqlx::query_as::<_, ChipDefinition>(
+        "SELECT chip_type, name, description, uses_per_season, per_half_season FROM chip_definitions WHERE chip_type = $1",
+    )
+    .bind(chip_type)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("No {} chip in the catalogue", chip_type.label())))?;
+
+    let after = sqlx::query_as::<_, ChipDefinition>(
+        r#"UPDATE chip_definitions
+           SET uses_per_season = $2, per_half_season = COALESCE($3, per_half_season)
+           WHERE chip_type = $1
+           RETURNING chip_type, name, description, uses_per_season, per_half_season"#,
+    )
+    .bind(chip_type)
+    .bind(body.uses_per_season)
+    .bind(body.per_half_season)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEntry::new(auth.user_id, "chip.update", "chip", chip_type.as_str())
+        .before(&before)
+        .after(&after)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(chip_definitions(&state.pool).await?))
+}
@@ -1,5 +1,7 @@
+use std::collections::HashMap;
+
 use axum::{
-    extract::{Extension, Path, State},
+    extract::{Extension, Path, Query, State},
     Json,
 };
 use rand::Rng;
@@ -8,11 +10,20 @@ use uuid::Uuid;
 use crate::auth::handler::AppState;
 use crate::auth::middleware::AuthUser;
 use crate::error::{AppError, AppResult};
+use crate::handlers::seasons::{last_played_week, resolve_season};
 use crate::handlers::teams::{compute_lock_status, fetch_team_starters};
 use crate::models::{
-    CreateLeagueRequest, JoinLeagueRequest, League, LeagueDetail, LeagueGameweekDetail,
-    LeagueGameweekStanding, LeagueMemberStanding, MemberLineupResponse, MyLeague,
+    CreateCupRequest, CreateLeagueRequest, CupBracket, CupEntrant, FixturesQuery,
+    GenerateFixturesRequest, H2hFixture, H2hStanding, JoinLeagueRequest, JoinLeagueResponse,
+    JoinStatus, LeaderboardPage, LeaderboardQuery, League, LeagueDetail, LeagueGameweekDetail,
+    LeagueGameweekStanding, LeagueJoinRequest, LeagueType, LeagueVisibility, MemberLineupResponse,
+    MyLeague, PublicLeague, RenameLeagueRequest, SeasonQuery, TransferOwnershipRequest,
+    UpdateLeagueAccessRequest,
 };
+use crate::services::audit::AuditEntry;
+use crate::services::cup::{self, WeekScore};
+use crate::services::head_to_head::{self, FixtureResult};
+use crate::services::standings;
 
 /// Generate a random 8-character alphanumeric invite code.
 fn generate_invite_code() -> String {
@@ -23,6 +34,47 @@ fn generate_invite_code() -> String {
         .collect()
 }
 
+const LEAGUE_COLUMNS: &str = "id, name, invite_code, created_by, created_at, league_type, \
+                              visibility, max_members, requires_approval";
+
+/// Load a league by id.
+async fn fetch_league(pool: &sqlx::PgPool, league_id: Uuid) -> AppResult<League> {
+    sqlx::query_as::<_, League>(&format!(
+        "SELECT {LEAGUE_COLUMNS} FROM leagues WHERE id = $1"
+    ))
+    .bind(league_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("League not found".to_string()))
+}
+
+/// Load a league and check that `user_id` owns it.
+async fn fetch_owned_league(
+    pool: &sqlx::PgPool,
+    league_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<League> {
+    let league = fetch_league(pool, league_id).await?;
+    if league.created_by != user_id {
+        return Err(AppError::BadRequest(
+            "Only the league owner can do this".to_string(),
+        ));
+    }
+    Ok(league)
+}
+
+/// Whether `user_id` belongs to the league.
+async fn is_member(pool: &sqlx::PgPool, league_id: Uuid, user_id: Uuid) -> AppResult<bool> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM league_members WHERE league_id = $1 AND user_id = $2",
+    )
+    .bind(league_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(count > 0)
+}
+
 /// POST /api/leagues
 ///
 /// Create a new league and automatically add the creator as a member.
@@ -36,19 +88,25 @@ pub async fn create_league(
             "League name cannot be empty".to_string(),
         ));
     }
+    validate_member_cap(body.max_members)?;
 
     let invite_code = generate_invite_code();
 
     let mut tx = state.pool.begin().await?;
 
-    let league = sqlx::query_as::<_, League>(
-        r#"INSERT INTO leagues (name, invite_code, created_by)
-           VALUES ($1, $2, $3)
-           RETURNING id, name, invite_code, created_by, created_at"#,
-    )
+    let league = sqlx::query_as::<_, League>(&format!(
+        r#"INSERT INTO leagues
+             (name, invite_code, created_by, league_type, visibility, max_members, requires_approval)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)
+           RETURNING {LEAGUE_COLUMNS}"#
+    ))
     .bind(&body.name)
     .bind(&invite_code)
     .bind(auth.user_id)
+    .bind(body.league_type)
+    .bind(body.visibility)
+    .bind(body.max_members)
+    .bind(body.requires_approval)
     .fetch_one(&mut *tx)
     .await?;
 
@@ -64,46 +122,551 @@ pub async fn create_league(
     Ok(Json(league))
 }
 
+/// A member cap has to leave room for someone besides the owner.
+fn validate_member_cap(max_members: Option<i32>) -> AppResult<()> {
+    match max_members {
+        Some(cap) if cap < 2 => Err(AppError::BadRequest(
+            "A league needs room for at least 2 members".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Fail if the league has no room for another member. Call with the league
+/// row locked so two joins can't both take the last place.
+async fn ensure_room(conn: &mut sqlx::PgConnection, league: &League) -> AppResult<()> {
+    let Some(cap) = league.max_members else {
+        return Ok(());
+    };
+    let members =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM league_members WHERE league_id = $1")
+            .bind(league.id)
+            .fetch_one(conn)
+            .await?;
+    if members >= cap as i64 {
+        return Err(AppError::Conflict("This league is full".to_string()));
+    }
+    Ok(())
+}
+
+/// Add `user_id` to a league, or queue them for the owner when the league
+/// wants approval.
+async fn enter_league(
+    pool: &sqlx::PgPool,
+    league_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<JoinLeagueResponse> {
+    let mut tx = pool.begin().await?;
+
+    let league = sqlx::query_as::<_, League>(&format!(
+        "SELECT {LEAGUE_COLUMNS} FROM leagues WHERE id = $1 FOR UPDATE"
+    ))
+    .bind(league_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let already_member = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM league_members WHERE league_id = $1 AND user_id = $2",
+    )
+    .bind(league_id)
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if already_member > 0 {
+        return Err(AppError::Conflict(
+            "You are already a member of this league".to_string(),
+        ));
+    }
+
+    let status = if league.requires_approval {
+        let queued = sqlx::query(
+            r#"INSERT INTO league_join_requests (league_id, user_id) VALUES ($1, $2)
+               ON CONFLICT (league_id, user_id) DO NOTHING"#,
+        )
+        .bind(league_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        if queued.rows_affected() == 0 {
+            return Err(AppError::Conflict(
+                "You have already asked to join this league".to_string(),
+            ));
+        }
+        JoinStatus::Pending
+    } else {
+        ensure_room(&mut tx, &league).await?;
+        sqlx::query("INSERT INTO league_members (league_id, user_id) VALUES ($1, $2)")
+            .bind(league_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        JoinStatus::Joined
+    };
+
+    let action = match status {
+        JoinStatus::Pending => "league.request_join",
+        JoinStatus::Joined => "league.join",
+    };
+    AuditEntry::new(user_id, action, "league", league_id)
+        .after(user_id)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(JoinLeagueResponse { league, status })
+}
+
 /// POST /api/leagues/join
 ///
-/// Join a league using an invite code.
+/// Join a league using an invite code. Leagues that want approval queue the
+/// request for the owner instead.
 pub async fn join_league(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthUser>,
     Json(body): Json<JoinLeagueRequest>,
+) -> AppResult<Json<JoinLeagueResponse>> {
+    let league_id = sqlx::query_scalar::<_, Uuid>("SELECT id FROM leagues WHERE invite_code = $1")
+        .bind(&body.invite_code)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Invalid invite code".to_string()))?;
+
+    let response = enter_league(&state.pool, league_id, auth.user_id).await?;
+    Ok(Json(response))
+}
+
+/// POST /api/leagues/:id/join
+///
+/// Join a public league without its invite code.
+pub async fn join_public_league(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+) -> AppResult<Json<JoinLeagueResponse>> {
+    let league = fetch_league(&state.pool, league_id).await?;
+    if league.visibility != LeagueVisibility::Public {
+        return Err(AppError::BadRequest(
+            "This league is invite-only".to_string(),
+        ));
+    }
+
+    let response = enter_league(&state.pool, league_id, auth.user_id).await?;
+    Ok(Json(response))
+}
+
+/// GET /api/leagues/public
+///
+/// The public league directory, largest first. Full leagues are left out.
+pub async fn list_public_leagues(
+    State(state): State<AppState>,
+) -> AppResult<Json<Vec<PublicLeague>>> {
+    let leagues = sqlx::query_as::<_, PublicLeague>(
+        r#"SELECT l.id, l.name, l.league_type, m.member_count, l.max_members,
+                  l.requires_approval, l.created_at
+           FROM leagues l
+           CROSS JOIN LATERAL (
+             SELECT COUNT(*) AS member_count FROM league_members lm WHERE lm.league_id = l.id
+           ) m
+           WHERE l.visibility = 'public'
+             AND (l.max_members IS NULL OR m.member_count < l.max_members)
+           ORDER BY m.member_count DESC, l.created_at DESC"#,
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(leagues))
+}
+
+/// PUT /api/leagues/:id/access
+///
+/// Change a league's visibility, member cap or approval setting. Owner only.
+pub async fn update_league_access(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+    Json(body): Json<UpdateLeagueAccessRequest>,
 ) -> AppResult<Json<League>> {
-    let league = sqlx::query_as::<_, League>(
-        "SELECT id, name, invite_code, created_by, created_at FROM leagues WHERE invite_code = $1",
+    // 0 clears the cap; anything else must be a usable cap.
+    let max_members = body.max_members.filter(|&cap| cap != 0);
+    validate_member_cap(max_members)?;
+
+    let before = fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    if let Some(cap) = max_members {
+        let members = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM league_members WHERE league_id = $1",
+        )
+        .bind(league_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if members > cap as i64 {
+            return Err(AppError::BadRequest(format!(
+                "The league already has {members} members"
+            )));
+        }
+    }
+
+    let league = sqlx::query_as::<_, League>(&format!(
+        r#"UPDATE leagues SET
+             visibility = COALESCE($2, visibility),
+             max_members = CASE WHEN $3::int IS NULL THEN max_members
+                                ELSE NULLIF($3, 0) END,
+             requires_approval = COALESCE($4, requires_approval)
+           WHERE id = $1
+           RETURNING {LEAGUE_COLUMNS}"#
+    ))
+    .bind(league_id)
+    .bind(body.visibility)
+    .bind(body.max_members)
+    .bind(body.requires_approval)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEntry::new(auth.user_id, "league.access", "league", league_id)
+        .before(&before)
+        .after(&league)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(league))
+}
+
+/// GET /api/leagues/:id/requests
+///
+/// People waiting to join, oldest first. Owner only.
+pub async fn list_join_requests(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+) -> AppResult<Json<Vec<LeagueJoinRequest>>> {
+    fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    let requests = sqlx::query_as::<_, LeagueJoinRequest>(
+        r#"SELECT u.id AS user_id, u.username, u.full_name, r.created_at AS requested_at
+           FROM league_join_requests r
+           INNER JOIN users u ON u.id = r.user_id
+           WHERE r.league_id = $1
+           ORDER BY r.created_at"#,
     )
-    .bind(&body.invite_code)
-    .fetch_optional(&state.pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound("Invalid invite code".to_string()))?;
+    .bind(league_id)
+    .fetch_all(&state.pool)
+    .await?;
 
-    // Check if already a member
-    let already_member = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM league_members WHERE league_id = $1 AND user_id = $2",
+    Ok(Json(requests))
+}
+
+/// POST /api/leagues/:id/requests/:user_id/approve
+///
+/// Let a pending joiner in, if there is still room. Owner only.
+pub async fn approve_join_request(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path((league_id, user_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<serde_json::Value>> {
+    fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let league = sqlx::query_as::<_, League>(&format!(
+        "SELECT {LEAGUE_COLUMNS} FROM leagues WHERE id = $1 FOR UPDATE"
+    ))
+    .bind(league_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let removed =
+        sqlx::query("DELETE FROM league_join_requests WHERE league_id = $1 AND user_id = $2")
+            .bind(league_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+    if removed.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "No pending request from this user".to_string(),
+        ));
+    }
+
+    ensure_room(&mut tx, &league).await?;
+    sqlx::query(
+        r#"INSERT INTO league_members (league_id, user_id) VALUES ($1, $2)
+           ON CONFLICT (league_id, user_id) DO NOTHING"#,
     )
-    .bind(league.id)
-    .bind(auth.user_id)
-    .fetch_one(&state.pool)
+    .bind(league_id)
+    .bind(user_id)
+    .execute(&mut *tx)
     .await?;
 
-    if already_member > 0 {
-        return Err(AppError::Conflict(
-            "You are already a member of this league".to_string(),
+    AuditEntry::new(auth.user_id, "league.approve_join", "league", league_id)
+        .after(user_id)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// DELETE /api/leagues/:id/requests/:user_id
+///
+/// Turn down a pending joiner. Owner only.
+pub async fn reject_join_request(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path((league_id, user_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<serde_json::Value>> {
+    fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let removed =
+        sqlx::query("DELETE FROM league_join_requests WHERE league_id = $1 AND user_id = $2")
+            .bind(league_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+    if removed.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "No pending request from this user".to_string(),
         ));
     }
 
-    sqlx::query("INSERT INTO league_members (league_id, user_id) VALUES ($1, $2)")
-        .bind(league.id)
-        .bind(auth.user_id)
-        .execute(&state.pool)
+    AuditEntry::new(auth.user_id, "league.reject_join", "league", league_id)
+        .before(user_id)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// PUT /api/leagues/:id
+///
+/// Rename a league. Owner only.
+pub async fn rename_league(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+    Json(body): Json<RenameLeagueRequest>,
+) -> AppResult<Json<League>> {
+    let name = body.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest(
+            "League name cannot be empty".to_string(),
+        ));
+    }
+
+    let before = fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let league = sqlx::query_as::<_, League>(&format!(
+        "UPDATE leagues SET name = $2 WHERE id = $1 RETURNING {LEAGUE_COLUMNS}"
+    ))
+    .bind(league_id)
+    .bind(name)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEntry::new(auth.user_id, "league.rename", "league", league_id)
+        .before(&before.name)
+        .after(&league.name)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(league))
+}
+
+/// DELETE /api/leagues/:id
+///
+/// Close a league for good, along with its fixtures and cups. Owner only.
+pub async fn delete_league(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+) -> AppResult<Json<serde_json::Value>> {
+    let before = fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query("DELETE FROM leagues WHERE id = $1")
+        .bind(league_id)
+        .execute(&mut *tx)
+        .await?;
+
+    AuditEntry::new(auth.user_id, "league.delete", "league", league_id)
+        .before(&before)
+        .record(&mut *tx)
         .await?;
 
+    tx.commit().await?;
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// POST /api/leagues/:id/invite-code
+///
+/// Replace the invite code, so a leaked one stops working. Owner only.
+pub async fn regenerate_invite_code(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+) -> AppResult<Json<League>> {
+    fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let league = sqlx::query_as::<_, League>(&format!(
+        "UPDATE leagues SET invite_code = $2 WHERE id = $1 RETURNING {LEAGUE_COLUMNS}"
+    ))
+    .bind(league_id)
+    .bind(generate_invite_code())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // The codes themselves stay out of the log; the old one is dead anyway.
+    AuditEntry::new(auth.user_id, "league.invite_code", "league", league_id)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
     Ok(Json(league))
 }
 
+/// PUT /api/leagues/:id/owner
+///
+/// Hand the league over to another member. Owner only.
+pub async fn transfer_ownership(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+    Json(body): Json<TransferOwnershipRequest>,
+) -> AppResult<Json<League>> {
+    fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    if body.user_id == auth.user_id {
+        return Err(AppError::BadRequest(
+            "You already own this league".to_string(),
+        ));
+    }
+    if !is_member(&state.pool, league_id, body.user_id).await? {
+        return Err(AppError::NotFound(
+            "User is not a member of this league".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let league = sqlx::query_as::<_, League>(&format!(
+        "UPDATE leagues SET created_by = $2 WHERE id = $1 RETURNING {LEAGUE_COLUMNS}"
+    ))
+    .bind(league_id)
+    .bind(body.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEntry::new(
+        auth.user_id,
+        "league.transfer_ownership",
+        "league",
+        league_id,
+    )
+    .before(auth.user_id)
+    .after(body.user_id)
+    .record(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(league))
+}
+
+/// DELETE /api/leagues/:league_id/members/:user_id
+///
+/// Remove a member from the league. Owner only, and the owner cannot remove
+/// themselves. Their head-to-head fixtures stay until the owner regenerates them.
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path((league_id, target_user_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<serde_json::Value>> {
+    fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    if target_user_id == auth.user_id {
+        return Err(AppError::BadRequest(
+            "Hand the league to another member or delete it instead".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let removed = sqlx::query("DELETE FROM league_members WHERE league_id = $1 AND user_id = $2")
+        .bind(league_id)
+        .bind(target_user_id)
+        .execute(&mut *tx)
+        .await?;
+    if removed.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "User is not a member of this league".to_string(),
+        ));
+    }
+
+    AuditEntry::new(auth.user_id, "league.remove_member", "league", league_id)
+        .before(target_user_id)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// POST /api/leagues/:id/leave
+///
+/// Leave a league. The owner has to hand it over or delete it first.
+pub async fn leave_league(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+) -> AppResult<Json<serde_json::Value>> {
+    let league = fetch_league(&state.pool, league_id).await?;
+    if league.created_by == auth.user_id {
+        return Err(AppError::BadRequest(
+            "The owner can't leave; hand the league to another member or delete it".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let removed = sqlx::query("DELETE FROM league_members WHERE league_id = $1 AND user_id = $2")
+        .bind(league_id)
+        .bind(auth.user_id)
+        .execute(&mut *tx)
+        .await?;
+    if removed.rows_affected() == 0 {
+        return Err(AppError::BadRequest(
+            "You are not a member of this league".to_string(),
+        ));
+    }
+
+    AuditEntry::new(auth.user_id, "league.leave", "league", league_id)
+        .before(auth.user_id)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
 /// GET /api/leagues/my
 ///
 /// List all leagues the authenticated user belongs to.
@@ -137,67 +700,52 @@ pub async fn get_league(
     State(state): State<AppState>,
     Path(league_id): Path<Uuid>,
 ) -> AppResult<Json<LeagueDetail>> {
-    let league = sqlx::query_as::<_, League>(
-        "SELECT id, name, invite_code, created_by, created_at FROM leagues WHERE id = $1",
-    )
-    .bind(league_id)
-    .fetch_optional(&state.pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound("League not found".to_string()))?;
+    let league = fetch_league(&state.pool, league_id).await?;
 
-    let members = sqlx::query_as::<_, LeagueMemberStanding>(
-        r#"SELECT
-             u.id AS user_id,
-             u.username,
-             u.full_name,
-             ft.name AS team_name,
-             COALESCE((
-               SELECT SUM(tgp.total_points)
-               FROM team_gameweek_points tgp
-               WHERE tgp.team_id = ft.id
-             ), 0) AS total_points
-           FROM league_members lm
-           INNER JOIN users u ON u.id = lm.user_id
-           LEFT JOIN fantasy_teams ft ON ft.user_id = u.id
-           WHERE lm.league_id = $1
-           ORDER BY total_points DESC"#,
-    )
-    .bind(league_id)
-    .fetch_all(&state.pool)
-    .await?;
+    let season = resolve_season(&state.pool, None).await?;
+    let mut conn = state.pool.acquire().await?;
+    let members = standings::league_table(&mut conn, league_id, season.id, None, None)
+        .await?
+        .standings;
 
     Ok(Json(LeagueDetail { league, members }))
 }
 
 /// GET /api/leagues/:id/leaderboard
 ///
-/// Get ranked leaderboard for a league.
+/// One page of the league's table for a season (`?season_id=`, default the open
+/// one). Follow `next_cursor` with `?cursor=` for the rest.
 pub async fn get_leaderboard(
     State(state): State<AppState>,
     Path(league_id): Path<Uuid>,
-) -> AppResult<Json<Vec<LeagueMemberStanding>>> {
-    let standings = sqlx::query_as::<_, LeagueMemberStanding>(
-        r#"SELECT
-             u.id AS user_id,
-             u.username,
-             u.full_name,
-             ft.name AS team_name,
-             COALESCE((
-               SELECT SUM(tgp.total_points)
-               FROM team_gameweek_points tgp
-               WHERE tgp.team_id = ft.id
-             ), 0) AS total_points
-           FROM league_members lm
-           INNER JOIN users u ON u.id = lm.user_id
-           LEFT JOIN fantasy_teams ft ON ft.user_id = u.id
-           WHERE lm.league_id = $1
-           ORDER BY total_points DESC"#,
-    )
-    .bind(league_id)
-    .fetch_all(&state.pool)
-    .await?;
+    Query(query): Query<LeaderboardQuery>,
+) -> AppResult<Json<LeaderboardPage>> {
+    let limit = query.limit.unwrap_or(50);
+    if !(1..=200).contains(&limit) {
+        return Err(AppError::BadRequest(
+            "limit must be between 1 and 200".to_string(),
+        ));
+    }
 
-    Ok(Json(standings))
+    fetch_league(&state.pool, league_id).await?;
+    let season = resolve_season(&state.pool, query.season_id).await?;
+    if let Some(cursor) = query.cursor {
+        let is_member = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM league_members WHERE league_id = $1 AND user_id = $2)",
+        )
+        .bind(league_id)
+        .bind(cursor)
+        .fetch_one(&state.pool)
+        .await?;
+        if !is_member {
+            return Err(AppError::BadRequest("Invalid cursor".to_string()));
+        }
+    }
+    let mut conn = state.pool.acquire().await?;
+    let page =
+        standings::league_table(&mut conn, league_id, season.id, query.cursor, Some(limit)).await?;
+
+    Ok(Json(page))
 }
 
 /// GET /api/leagues/:id/gameweek/:week
@@ -207,9 +755,18 @@ pub async fn get_league_gameweek(
     State(state): State<AppState>,
     Path((league_id, week)): Path<(Uuid, i32)>,
 ) -> AppResult<Json<LeagueGameweekDetail>> {
+    Ok(Json(league_gameweek(&state.pool, league_id, week).await?))
+}
+
+/// League member standings for one gameweek, best first.
+pub(crate) async fn league_gameweek(
+    pool: &sqlx::PgPool,
+    league_id: Uuid,
+    week: i32,
+) -> AppResult<LeagueGameweekDetail> {
     let _league = sqlx::query_scalar::<_, Uuid>("SELECT id FROM leagues WHERE id = $1")
         .bind(league_id)
-        .fetch_optional(&state.pool)
+        .fetch_optional(pool)
         .await?
         .ok_or_else(|| AppError::NotFound("League not found".to_string()))?;
 
@@ -234,14 +791,14 @@ pub async fn get_league_gameweek(
     )
     .bind(league_id)
     .bind(week)
-    .fetch_all(&state.pool)
+    .fetch_all(pool)
     .await?;
 
-    Ok(Json(LeagueGameweekDetail {
+    Ok(LeagueGameweekDetail {
         league_id,
         week_number: week,
         members,
-    }))
+    })
 }
 
 /// GET /api/leagues/:league_id/members/:user_id/lineup
@@ -257,34 +814,17 @@ pub async fn get_member_lineup(
     let lock = compute_lock_status(&state.pool).await?;
     if !lock.locked {
         return Err(AppError::BadRequest(
-            "Lineups are only visible after the gameweek starts (Saturday 10:00 PM ET to Sunday 12:00 PM ET)"
-                .to_string(),
+            "Lineups are only visible once the gameweek deadline has passed".to_string(),
         ));
     }
 
-    let requesting_is_member = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM league_members WHERE league_id = $1 AND user_id = $2",
-    )
-    .bind(league_id)
-    .bind(auth.user_id)
-    .fetch_one(&state.pool)
-    .await?;
-
-    if requesting_is_member == 0 {
+    if !is_member(&state.pool, league_id, auth.user_id).await? {
         return Err(AppError::BadRequest(
             "You are not a member of this league".to_string(),
         ));
     }
 
-    let target_is_member = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM league_members WHERE league_id = $1 AND user_id = $2",
-    )
-    .bind(league_id)
-    .bind(target_user_id)
-    .fetch_one(&state.pool)
-    .await?;
-
-    if target_is_member == 0 {
+    if !is_member(&state.pool, league_id, target_user_id).await? {
         return Err(AppError::NotFound(
             "User is not a member of this league".to_string(),
         ));
@@ -295,10 +835,11 @@ pub async fn get_member_lineup(
         id: Uuid,
         name: String,
         captain_id: Option<Uuid>,
+        vice_captain_id: Option<Uuid>,
     }
 
     let team = sqlx::query_as::<_, TeamRow>(
-        "SELECT id, name, captain_id FROM fantasy_teams WHERE user_id = $1",
+        "SELECT id, name, captain_id, vice_captain_id FROM fantasy_teams WHERE user_id = $1",
     )
     .bind(target_user_id)
     .fetch_optional(&state.pool)
@@ -317,6 +858,434 @@ pub async fn get_member_lineup(
         username,
         team_name: team.name,
         captain_id: team.captain_id,
+        vice_captain_id: team.vice_captain_id,
         starters,
     }))
 }
+
+/// Load a league and check it is head-to-head.
+async fn fetch_head_to_head_league(pool: &sqlx::PgPool, league_id: Uuid) -> AppResult<League> {
+    let league = fetch_league(pool, league_id).await?;
+    if league.league_type != LeagueType::HeadToHead {
+        return Err(AppError::BadRequest(
+            "This is not a head-to-head league".to_string(),
+        ));
+    }
+    Ok(league)
+}
+
+async fn fetch_fixtures(
+    pool: &sqlx::PgPool,
+    league_id: Uuid,
+    season_id: Uuid,
+    week: Option<i32>,
+) -> AppResult<Vec<H2hFixture>> {
+    let fixtures = sqlx::query_as::<_, H2hFixture>(
+        r#"SELECT
+             f.id,
+             f.week_number,
+             f.home_team_id,
+             home.name AS home_team_name,
+             f.away_team_id,
+             away.name AS away_team_name,
+             f.home_points,
+             f.away_points
+           FROM h2h_fixtures f
+           INNER JOIN fantasy_teams home ON home.id = f.home_team_id
+           LEFT JOIN fantasy_teams away ON away.id = f.away_team_id
+           WHERE f.league_id = $1 AND f.season_id = $2
+             AND ($3::int IS NULL OR f.week_number = $3)
+           ORDER BY f.week_number, home.name"#,
+    )
+    .bind(league_id)
+    .bind(season_id)
+    .bind(week)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(fixtures)
+}
+
+/// Copy a scored gameweek's team totals onto the head-to-head fixtures played
+/// in it. A team with no score for the week counts as 0.
+pub(crate) async fn record_h2h_results(
+    conn: &mut sqlx::PgConnection,
+    match_week_id: Uuid,
+) -> AppResult<()> {
+    sqlx::query(
+        r#"UPDATE h2h_fixtures f
+           SET home_points = COALESCE((
+                 SELECT tgp.total_points FROM team_gameweek_points tgp
+                 WHERE tgp.team_id = f.home_team_id AND tgp.match_week_id = mw.id
+               ), 0),
+               away_points = CASE WHEN f.away_team_id IS NULL THEN NULL ELSE COALESCE((
+                 SELECT tgp.total_points FROM team_gameweek_points tgp
+                 WHERE tgp.team_id = f.away_team_id AND tgp.match_week_id = mw.id
+               ), 0) END
+           FROM match_weeks mw
+           WHERE mw.id = $1
+             AND f.season_id = mw.season_id
+             AND f.week_number = mw.week_number"#,
+    )
+    .bind(match_week_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// POST /api/leagues/:id/h2h/fixtures
+///
+/// Generate a round-robin between the members' teams for the open season, one
+/// round per gameweek from `start_week`. Only the league owner can do this,
+/// and only unplayed fixtures are replaced.
+pub async fn generate_fixtures(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+    Json(body): Json<GenerateFixturesRequest>,
+) -> AppResult<Json<Vec<H2hFixture>>> {
+    fetch_head_to_head_league(&state.pool, league_id).await?;
+    fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    let season = resolve_season(&state.pool, None).await?;
+
+    let teams = sqlx::query_scalar::<_, Uuid>(
+        r#"SELECT ft.id
+           FROM league_members lm
+           INNER JOIN fantasy_teams ft ON ft.user_id = lm.user_id
+           WHERE lm.league_id = $1
+           ORDER BY lm.joined_at, ft.created_at"#,
+    )
+    .bind(league_id)
+    .fetch_all(&state.pool)
+    .await?;
+    if teams.len() < 2 {
+        return Err(AppError::BadRequest(
+            "A head-to-head league needs at least two teams".to_string(),
+        ));
+    }
+
+    let start_week = match body.start_week {
+        Some(week) => week,
+        None => last_played_week(&state.pool, season.id)
+            .await?
+            .map_or(season.start_week, |w| w + 1),
+    };
+    if start_week < season.start_week {
+        return Err(AppError::BadRequest(format!(
+            "The season starts at gameweek {}",
+            season.start_week
+        )));
+    }
+
+    let rounds = body
+        .rounds
+        .unwrap_or(head_to_head::cycle_length(teams.len()) as i32);
+    if rounds < 1 {
+        return Err(AppError::BadRequest(
+            "rounds must be at least 1".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let played = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM h2h_fixtures
+           WHERE league_id = $1 AND season_id = $2 AND week_number >= $3
+             AND home_points IS NOT NULL"#,
+    )
+    .bind(league_id)
+    .bind(season.id)
+    .bind(start_week)
+    .fetch_one(&mut *tx)
+    .await?;
+    if played > 0 {
+        return Err(AppError::Conflict(format!(
+            "Fixtures from gameweek {start_week} have already been played"
+        )));
+    }
+
+    sqlx::query(
+        "DELETE FROM h2h_fixtures WHERE league_id = $1 AND season_id = $2 AND week_number >= $3",
+    )
+    .bind(league_id)
+    .bind(season.id)
+    .bind(start_week)
+    .execute(&mut *tx)
+    .await?;
+
+    for (week_number, round) in
+        (start_week..).zip(head_to_head::round_robin(&teams, rounds as usize))
+    {
+        for pairing in round {
+            sqlx::query(
+                r#"INSERT INTO h2h_fixtures
+                     (league_id, season_id, week_number, home_team_id, away_team_id)
+                   VALUES ($1, $2, $3, $4, $5)"#,
+            )
+            .bind(league_id)
+            .bind(season.id)
+            .bind(week_number)
+            .bind(pairing.home)
+            .bind(pairing.away)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    let fixtures = fetch_fixtures(&state.pool, league_id, season.id, None).await?;
+    Ok(Json(fixtures))
+}
+
+/// GET /api/leagues/:id/h2h/fixtures
+///
+/// A head-to-head league's fixtures for a season (`?season_id=`, default the
+/// open one), optionally for one gameweek (`?week=`).
+pub async fn get_fixtures(
+    State(state): State<AppState>,
+    Path(league_id): Path<Uuid>,
+    Query(query): Query<FixturesQuery>,
+) -> AppResult<Json<Vec<H2hFixture>>> {
+    fetch_head_to_head_league(&state.pool, league_id).await?;
+    let season = resolve_season(&state.pool, query.season_id).await?;
+
+    let fixtures = fetch_fixtures(&state.pool, league_id, season.id, query.week).await?;
+    Ok(Json(fixtures))
+}
+
+/// GET /api/leagues/:id/h2h/standings
+///
+/// The head-to-head table for a season (`?season_id=`, default the open one):
+/// 3 points for a win, 1 for a draw, ties broken by points scored.
+pub async fn get_h2h_standings(
+    State(state): State<AppState>,
+    Path(league_id): Path<Uuid>,
+    Query(query): Query<SeasonQuery>,
+) -> AppResult<Json<Vec<H2hStanding>>> {
+    fetch_head_to_head_league(&state.pool, league_id).await?;
+    let season = resolve_season(&state.pool, query.season_id).await?;
+
+    #[derive(sqlx::FromRow)]
+    struct TeamRow {
+        id: Uuid,
+        name: String,
+    }
+
+    // Current members' teams, plus anyone who has since left but already played.
+    let teams = sqlx::query_as::<_, TeamRow>(
+        r#"SELECT ft.id, ft.name
+           FROM fantasy_teams ft
+           WHERE ft.user_id IN (SELECT user_id FROM league_members WHERE league_id = $1)
+              OR ft.id IN (
+                SELECT home_team_id FROM h2h_fixtures WHERE league_id = $1 AND season_id = $2
+                UNION
+                SELECT away_team_id FROM h2h_fixtures WHERE league_id = $1 AND season_id = $2
+              )"#,
+    )
+    .bind(league_id)
+    .bind(season.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let results = sqlx::query_as::<_, (Uuid, Uuid, i32, i32)>(
+        r#"SELECT home_team_id, away_team_id, home_points, away_points
+           FROM h2h_fixtures
+           WHERE league_id = $1 AND season_id = $2
+             AND away_team_id IS NOT NULL
+             AND home_points IS NOT NULL AND away_points IS NOT NULL"#,
+    )
+    .bind(league_id)
+    .bind(season.id)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .map(|(home, away, home_points, away_points)| FixtureResult {
+        home,
+        away,
+        home_points,
+        away_points,
+    })
+    .collect::<Vec<_>>();
+
+    let team_ids: Vec<Uuid> = teams.iter().map(|t| t.id).collect();
+    let mut names: HashMap<Uuid, String> = teams.into_iter().map(|t| (t.id, t.name)).collect();
+
+    let standings = head_to_head::table(&team_ids, &results)
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| H2hStanding {
+            rank: i as i32 + 1,
+            team_name: names.remove(&row.team_id).unwrap_or_default(),
+            team_id: row.team_id,
+            played: row.played,
+            won: row.won,
+            drawn: row.drawn,
+            lost: row.lost,
+            points_for: row.points_for,
+            points_against: row.points_against,
+            points: row.points,
+        })
+        .collect();
+
+    Ok(Json(standings))
+}
+
+/// POST /api/leagues/:id/cup
+///
+/// Attach a knockout cup to the league for the open season, entering every
+/// member's team. Only the league owner can do this.
+pub async fn create_cup(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(league_id): Path<Uuid>,
+    Json(body): Json<CreateCupRequest>,
+) -> AppResult<Json<CupBracket>> {
+    fetch_owned_league(&state.pool, league_id, auth.user_id).await?;
+
+    let season = resolve_season(&state.pool, None).await?;
+    if body.start_week < season.start_week {
+        return Err(AppError::BadRequest(format!(
+            "The season starts at gameweek {}",
+            season.start_week
+        )));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let cup_id = sqlx::query_scalar::<_, Uuid>(
+        r#"INSERT INTO league_cups (league_id, season_id, start_week)
+           VALUES ($1, $2, $3)
+           ON CONFLICT (league_id, season_id) DO NOTHING
+           RETURNING id"#,
+    )
+    .bind(league_id)
+    .bind(season.id)
+    .bind(body.start_week)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Conflict("This league already has a cup this season".to_string()))?;
+
+    let entered = sqlx::query(
+        r#"INSERT INTO league_cup_entrants (cup_id, team_id)
+           SELECT $1, ft.id
+           FROM league_members lm
+           INNER JOIN fantasy_teams ft ON ft.user_id = lm.user_id
+           WHERE lm.league_id = $2"#,
+    )
+    .bind(cup_id)
+    .bind(league_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if entered < 2 {
+        return Err(AppError::BadRequest(
+            "A cup needs at least two teams".to_string(),
+        ));
+    }
+
+    tx.commit().await?;
+
+    let bracket = load_cup(&state.pool, league_id, season.id).await?;
+    Ok(Json(bracket))
+}
+
+/// GET /api/leagues/:id/cup
+///
+/// The league's cup bracket for a season (`?season_id=`, default the open one).
+pub async fn get_cup(
+    State(state): State<AppState>,
+    Path(league_id): Path<Uuid>,
+    Query(query): Query<SeasonQuery>,
+) -> AppResult<Json<CupBracket>> {
+    let season = resolve_season(&state.pool, query.season_id).await?;
+    let bracket = load_cup(&state.pool, league_id, season.id).await?;
+    Ok(Json(bracket))
+}
+
+/// Build a cup's bracket from its entrants and their gameweek scores.
+async fn load_cup(pool: &sqlx::PgPool, league_id: Uuid, season_id: Uuid) -> AppResult<CupBracket> {
+    let (cup_id, start_week) = sqlx::query_as::<_, (Uuid, i32)>(
+        "SELECT id, start_week FROM league_cups WHERE league_id = $1 AND season_id = $2",
+    )
+    .bind(league_id)
+    .bind(season_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("This league has no cup this season".to_string()))?;
+
+    // Seeded by season points before the first round; ties go to the older team.
+    let entrants = sqlx::query_as::<_, CupEntrant>(
+        r#"SELECT
+             ft.id AS team_id,
+             ft.name AS team_name,
+             (ROW_NUMBER() OVER (ORDER BY pts.total DESC, ft.created_at, ft.id))::int AS seed,
+             pts.total AS seeding_points
+           FROM league_cup_entrants ce
+           INNER JOIN fantasy_teams ft ON ft.id = ce.team_id
+           CROSS JOIN LATERAL (
+             SELECT COALESCE(SUM(tgp.total_points), 0)::bigint AS total
+             FROM team_gameweek_points tgp
+             INNER JOIN match_weeks mw ON mw.id = tgp.match_week_id
+             WHERE tgp.team_id = ft.id AND mw.season_id = $2 AND mw.week_number < $3
+           ) pts
+           WHERE ce.cup_id = $1
+           ORDER BY seed"#,
+    )
+    .bind(cup_id)
+    .bind(season_id)
+    .bind(start_week)
+    .fetch_all(pool)
+    .await?;
+
+    let team_ids: Vec<Uuid> = entrants.iter().map(|e| e.team_id).collect();
+    let last_week = start_week + cup::round_count(team_ids.len()) as i32 - 1;
+
+    // Every finalised gameweek in the cup's span, with the entrants' scores.
+    let rows = sqlx::query_as::<_, (i32, Option<Uuid>, Option<i32>, Option<i32>, Option<i32>)>(
+        r#"SELECT mw.week_number, tgp.team_id, tgp.total_points, tgp.captain_points, tgp.goals
+           FROM match_weeks mw
+           LEFT JOIN team_gameweek_points tgp
+             ON tgp.match_week_id = mw.id AND tgp.team_id = ANY($4)
+           WHERE mw.season_id = $1 AND mw.week_number BETWEEN $2 AND $3
+             AND mw.status = 'finalised'"#,
+    )
+    .bind(season_id)
+    .bind(start_week)
+    .bind(last_week)
+    .bind(&team_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut scores: HashMap<i32, HashMap<Uuid, WeekScore>> = HashMap::new();
+    for (week_number, team_id, points, captain_points, goals) in rows {
+        let week = scores.entry(week_number).or_default();
+        if let Some(team_id) = team_id {
+            week.insert(
+                team_id,
+                WeekScore {
+                    points: points.unwrap_or(0),
+                    captain_points: captain_points.unwrap_or(0),
+                    goals: goals.unwrap_or(0),
+                },
+            );
+        }
+    }
+
+    let rounds = cup::bracket(&team_ids, start_week, &scores);
+    let winner_team_id = rounds
+        .last()
+        .and_then(|r| r.matches.first())
+        .and_then(|m| m.winner_team_id);
+
+    Ok(CupBracket {
+        league_id,
+        season_id,
+        start_week,
+        entrants,
+        rounds,
+        winner_team_id,
+    })
+}
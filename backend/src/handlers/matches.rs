@@ -0,0 +1,501 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::handler::AppState;
+use crate::auth::middleware::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    Match, MatchDetail, MatchEvent, MatchEventDetail, MatchEventRequest, MatchLineupEntry,
+    MatchLineupPlayer, MatchRequest, MatchWeek, MatchesQuery,
+};
+use crate::services::audit::AuditEntry;
+use crate::services::gameweek::GameweekAction;
+use crate::services::matches::{self, DEFAULT_FANTASY_MINUTES};
+
+use super::admin::{lock_week_for, open_week};
+use super::teams::lock_schedule;
+
+const MATCH_COLUMNS: &str = "m.id, m.match_week_id, mw.week_number, m.home_name, m.away_name, \
+                             m.kickoff_at, m.fantasy_minutes, m.home_goals, m.away_goals, \
+                             m.created_at, m.updated_at";
+
+async fn fetch_match<'e, E>(executor: E, match_id: Uuid) -> AppResult<Match>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    sqlx::query_as::<_, Match>(&format!(
+        "SELECT {MATCH_COLUMNS} FROM matches m \
+         INNER JOIN match_weeks mw ON mw.id = m.match_week_id WHERE m.id = $1"
+    ))
+    .bind(match_id)
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Match not found".to_string()))
+}
+
+async fn with_lineups<'e, E>(executor: E, m: Match) -> AppResult<MatchDetail>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let lineups = sqlx::query_as::<_, MatchLineupPlayer>(
+        r#"SELECT ml.player_id, p.name AS player_name, ml.side, ml.minutes_played
+           FROM match_lineups ml
+           INNER JOIN players p ON p.id = ml.player_id
+           WHERE ml.match_id = $1
+           ORDER BY ml.side, p.name"#,
+    )
+    .bind(m.id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(MatchDetail {
+        fantasy_time_end: m.fantasy_time_end(),
+        r#match: m,
+        lineups,
+    })
+}
+
+/// Check a match request against the gameweek it is for. Kickoff dates are
+/// read in the lock schedule's timezone.
+async fn validate_request(pool: &sqlx::PgPool, body: &MatchRequest) -> AppResult<MatchWeek> {
+    let week = open_week(pool, body.week_number).await?;
+
+    let timezone = lock_schedule(pool).await?.timezone;
+    let kickoff_day = body.kickoff_at.with_timezone(&timezone).date_naive();
+    if kickoff_day < week.start_date || kickoff_day > week.end_date {
+        return Err(AppError::BadRequest(format!(
+            "Kickoff must fall within gameweek {} ({} to {})",
+            week.week_number, week.start_date, week.end_date
+        )));
+    }
+    if body.fantasy_minutes.is_some_and(|m| m <= 0) {
+        return Err(AppError::BadRequest(
+            "fantasy_minutes must be positive".to_string(),
+        ));
+    }
+    if body.home_goals < 0 || body.away_goals < 0 {
+        return Err(AppError::BadRequest("Goals cannot be negative".to_string()));
+    }
+    matches::validate_lineups(&body.lineups).map_err(AppError::BadRequest)?;
+
+    let player_ids: Vec<Uuid> = body.lineups.iter().map(|e| e.player_id).collect();
+    let known = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM players WHERE id = ANY($1)")
+        .bind(&player_ids)
+        .fetch_one(pool)
+        .await?;
+    if known != player_ids.len() as i64 {
+        return Err(AppError::BadRequest(
+            "Lineups include unknown players".to_string(),
+        ));
+    }
+
+    Ok(week)
+}
+
+async fn insert_lineups(
+    conn: &mut sqlx::PgConnection,
+    match_id: Uuid,
+    body: &MatchRequest,
+) -> AppResult<()> {
+    for entry in &body.lineups {
+        sqlx::query(
+            "INSERT INTO match_lineups (match_id, player_id, side, minutes_played) \
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(match_id)
+        .bind(entry.player_id)
+        .bind(entry.side)
+        .bind(entry.minutes_played)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn lineup_entries(
+    conn: &mut sqlx::PgConnection,
+    match_id: Uuid,
+) -> AppResult<Vec<MatchLineupEntry>> {
+    let rows = sqlx::query_as::<_, (Uuid, crate::models::MatchSide, Option<i32>)>(
+        "SELECT player_id, side, minutes_played FROM match_lineups WHERE match_id = $1",
+    )
+    .bind(match_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(player_id, side, minutes_played)| MatchLineupEntry {
+            player_id,
+            side,
+            minutes_played,
+        })
+        .collect())
+}
+
+async fn match_events(conn: &mut sqlx::PgConnection, match_id: Uuid) -> AppResult<Vec<MatchEvent>> {
+    Ok(sqlx::query_as::<_, MatchEvent>(
+        r#"SELECT id, match_id, minute, event_type, player_id, assist_player_id,
+                  foul_severity, created_at
+           FROM match_events WHERE match_id = $1
+           ORDER BY minute, created_at"#,
+    )
+    .bind(match_id)
+    .fetch_all(&mut *conn)
+    .await?)
+}
+
+/// Once a match has events, its score is whatever they add up to.
+async fn sync_score(conn: &mut sqlx::PgConnection, m: &Match) -> AppResult<()> {
+    let events = match_events(conn, m.id).await?;
+    if events.is_empty() {
+        return Ok(());
+    }
+    let lineups = lineup_entries(conn, m.id).await?;
+    let (home, away) = matches::score_from_events(&events, &lineups, m.fantasy_minutes);
+    sqlx::query(
+        "UPDATE matches SET home_goals = $2, away_goals = $3, updated_at = NOW() WHERE id = $1",
+    )
+    .bind(m.id)
+    .bind(home)
+    .bind(away)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// GET /api/matches?week=
+///
+/// Matches with their lineups, in kickoff order, optionally for one gameweek.
+pub async fn list_matches(
+    State(state): State<AppState>,
+    Query(query): Query<MatchesQuery>,
+) -> AppResult<Json<Vec<MatchDetail>>> {
+    let rows = sqlx::query_as::<_, Match>(&format!(
+        r#"SELECT {MATCH_COLUMNS}
+           FROM matches m
+           INNER JOIN match_weeks mw ON mw.id = m.match_week_id
+           WHERE $1::int IS NULL OR mw.week_number = $1
+           ORDER BY m.kickoff_at"#
+    ))
+    .bind(query.week)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut out = Vec::with_capacity(rows.len());
+    for m in rows {
+        out.push(with_lineups(&state.pool, m).await?);
+    }
+    Ok(Json(out))
+}
+
+/// GET /api/matches/:id
+pub async fn get_match(
+    State(state): State<AppState>,
+    Path(match_id): Path<Uuid>,
+) -> AppResult<Json<MatchDetail>> {
+    let m = fetch_match(&state.pool, match_id).await?;
+    Ok(Json(with_lineups(&state.pool, m).await?))
+}
+
+/// POST /api/admin/matches
+///
+/// Record a match: both sides, the kickoff, the score at the end of fantasy time
+/// and who played for whom. Resubmit the week's stats to score it.
+pub async fn create_match(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Json(body): Json<MatchRequest>,
+) -> AppResult<Json<MatchDetail>> {
+    let week = validate_request(&state.pool, &body).await?;
+
+    let mut tx = state.pool.begin().await?;
+    lock_week_for(&mut tx, week.week_number, GameweekAction::Score).await?;
+
+    let match_id = sqlx::query_scalar::<_, Uuid>(
+        r#"INSERT INTO matches
+             (match_week_id, home_name, away_name, kickoff_at, fantasy_minutes, home_goals, away_goals)
+           VALUES ($1, COALESCE($2, 'Home'), COALESCE($3, 'Away'), $4, $5, $6, $7)
+           RETURNING id"#,
+    )
+    .bind(week.id)
+    .bind(&body.home_name)
+    .bind(&body.away_name)
+    .bind(body.kickoff_at)
+    .bind(body.fantasy_minutes.unwrap_or(DEFAULT_FANTASY_MINUTES))
+    .bind(body.home_goals)
+    .bind(body.away_goals)
+    .fetch_one(&mut *tx)
+    .await?;
+    insert_lineups(&mut tx, match_id, &body).await?;
+
+    let created = fetch_match(&mut *tx, match_id).await?;
+    let detail = with_lineups(&mut *tx, created).await?;
+
+    AuditEntry::new(auth.user_id, "match.create", "match", match_id)
+        .after(&detail)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(detail))
+}
+
+/// PUT /api/admin/matches/:id
+///
+/// Replace a match's details and lineups. Resubmit the week's stats to rescore.
+/// A match with logged events keeps the score they add up to.
+pub async fn update_match(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(match_id): Path<Uuid>,
+    Json(body): Json<MatchRequest>,
+) -> AppResult<Json<MatchDetail>> {
+    let existing = fetch_match(&state.pool, match_id).await?;
+    let old_week = existing.week_number;
+    // Neither the old week nor the new one may belong to a closed season.
+    open_week(&state.pool, old_week).await?;
+    let week = validate_request(&state.pool, &body).await?;
+    let before = with_lineups(&state.pool, existing).await?;
+
+    let mut tx = state.pool.begin().await?;
+    // Lower week first, so two moves between the same weeks cannot deadlock.
+    let (first, second) = (
+        old_week.min(week.week_number),
+        old_week.max(week.week_number),
+    );
+    lock_week_for(&mut tx, first, GameweekAction::Score).await?;
+    if second != first {
+        lock_week_for(&mut tx, second, GameweekAction::Score).await?;
+    }
+
+    sqlx::query(
+        r#"UPDATE matches SET
+             match_week_id = $2,
+             home_name = COALESCE($3, home_name),
+             away_name = COALESCE($4, away_name),
+             kickoff_at = $5,
+             fantasy_minutes = $6,
+             home_goals = $7,
+             away_goals = $8,
+             updated_at = NOW()
+           WHERE id = $1"#,
+    )
+    .bind(match_id)
+    .bind(week.id)
+    .bind(&body.home_name)
+    .bind(&body.away_name)
+    .bind(body.kickoff_at)
+    .bind(body.fantasy_minutes.unwrap_or(DEFAULT_FANTASY_MINUTES))
+    .bind(body.home_goals)
+    .bind(body.away_goals)
+    .execute(&mut *tx)
+    .await?;
+
+    // Logged events must still point at someone in the lineups.
+    let mut referenced: Vec<Uuid> = Vec::new();
+    for e in match_events(&mut tx, match_id).await? {
+        referenced.push(e.player_id);
+        referenced.extend(e.assist_player_id);
+    }
+    let dropped = referenced
+        .iter()
+        .any(|id| !body.lineups.iter().any(|e| e.player_id == *id));
+    if dropped {
+        return Err(AppError::BadRequest(
+            "Delete the events of players removed from the lineups first".to_string(),
+        ));
+    }
+
+    sqlx::query("DELETE FROM match_lineups WHERE match_id = $1")
+        .bind(match_id)
+        .execute(&mut *tx)
+        .await?;
+    insert_lineups(&mut tx, match_id, &body).await?;
+
+    let updated = fetch_match(&mut *tx, match_id).await?;
+    sync_score(&mut tx, &updated).await?;
+    let updated = fetch_match(&mut *tx, match_id).await?;
+    let detail = with_lineups(&mut *tx, updated).await?;
+
+    AuditEntry::new(auth.user_id, "match.update", "match", match_id)
+        .before(&before)
+        .after(&detail)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(detail))
+}
+
+/// DELETE /api/admin/matches/:id
+pub async fn delete_match(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(match_id): Path<Uuid>,
+) -> AppResult<Json<serde_json::Value>> {
+    let existing = fetch_match(&state.pool, match_id).await?;
+    let week_number = existing.week_number;
+    open_week(&state.pool, week_number).await?;
+    let before = with_lineups(&state.pool, existing).await?;
+
+    let mut tx = state.pool.begin().await?;
+    lock_week_for(&mut tx, week_number, GameweekAction::Score).await?;
+
+    sqlx::query("DELETE FROM matches WHERE id = $1")
+        .bind(match_id)
+        .execute(&mut *tx)
+        .await?;
+
+    AuditEntry::new(auth.user_id, "match.delete", "match", match_id)
+        .before(&before)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// GET /api/matches/:id/events
+///
+/// The match timeline in minute order, including events after fantasy time.
+pub async fn list_match_events(
+    State(state): State<AppState>,
+    Path(match_id): Path<Uuid>,
+) -> AppResult<Json<Vec<MatchEventDetail>>> {
+    fetch_match(&state.pool, match_id).await?;
+
+    let events = sqlx::query_as::<_, MatchEventDetail>(
+        r#"SELECT me.id, me.minute, me.event_type, me.player_id, p.name AS player_name,
+                  ml.side, me.assist_player_id, ap.name AS assist_player_name,
+                  me.foul_severity, me.minute <= m.fantasy_minutes AS counts
+           FROM match_events me
+           INNER JOIN matches m ON m.id = me.match_id
+           INNER JOIN players p ON p.id = me.player_id
+           INNER JOIN match_lineups ml ON ml.match_id = me.match_id AND ml.player_id = me.player_id
+           LEFT JOIN players ap ON ap.id = me.assist_player_id
+           WHERE me.match_id = $1
+           ORDER BY me.minute, me.created_at"#,
+    )
+    .bind(match_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(events))
+}
+
+/// POST /api/admin/matches/:id/events
+///
+/// Log an event against a match. The match score is recomputed from its events,
+/// and the week's stats can then be rebuilt from them.
+pub async fn create_match_event(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(match_id): Path<Uuid>,
+    Json(body): Json<MatchEventRequest>,
+) -> AppResult<Json<MatchEvent>> {
+    let m = fetch_match(&state.pool, match_id).await?;
+    open_week(&state.pool, m.week_number).await?;
+
+    let mut tx = state.pool.begin().await?;
+    lock_week_for(&mut tx, m.week_number, GameweekAction::Score).await?;
+
+    let lineups = lineup_entries(&mut tx, match_id).await?;
+    matches::validate_event(&body, &lineups).map_err(AppError::BadRequest)?;
+
+    let event = sqlx::query_as::<_, MatchEvent>(
+        r#"INSERT INTO match_events
+             (match_id, minute, event_type, player_id, assist_player_id, foul_severity)
+           VALUES ($1, $2, $3, $4, $5, $6)
+           RETURNING id, match_id, minute, event_type, player_id, assist_player_id,
+                     foul_severity, created_at"#,
+    )
+    .bind(match_id)
+    .bind(body.minute)
+    .bind(body.event_type)
+    .bind(body.player_id)
+    .bind(body.assist_player_id)
+    .bind(body.foul_severity)
+    .fetch_one(&mut *tx)
+    .await?;
+    sync_score(&mut tx, &m).await?;
+
+    AuditEntry::new(auth.user_id, "match.event.create", "match", match_id)
+        .after(&event)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(event))
+}
+
+/// DELETE /api/admin/matches/:id/events/:event_id
+pub async fn delete_match_event(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path((match_id, event_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<serde_json::Value>> {
+    let m = fetch_match(&state.pool, match_id).await?;
+    open_week(&state.pool, m.week_number).await?;
+
+    let mut tx = state.pool.begin().await?;
+    lock_week_for(&mut tx, m.week_number, GameweekAction::Score).await?;
+
+    let event = sqlx::query_as::<_, MatchEvent>(
+        r#"DELETE FROM match_events WHERE id = $1 AND match_id = $2
+           RETURNING id, match_id, minute, event_type, player_id, assist_player_id,
+                     foul_severity, created_at"#,
+    )
+    .bind(event_id)
+    .bind(match_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Match event not found".to_string()))?;
+
+    // With the last event gone the score stays where the events left it.
+    sync_score(&mut tx, &m).await?;
+
+    AuditEntry::new(auth.user_id, "match.event.delete", "match", match_id)
+        .before(&event)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// The week's stats as its matches' lineups and events add them up.
+pub(crate) async fn week_stats_from_events(
+    conn: &mut sqlx::PgConnection,
+    match_week_id: Uuid,
+) -> AppResult<Vec<crate::models::PlayerStatInput>> {
+    let ids = sqlx::query_as::<_, (Uuid, i32)>(
+        "SELECT id, fantasy_minutes FROM matches WHERE match_week_id = $1 ORDER BY kickoff_at",
+    )
+    .bind(match_week_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut loaded = Vec::with_capacity(ids.len());
+    for (match_id, fantasy_minutes) in ids {
+        let lineups = lineup_entries(conn, match_id).await?;
+        let events = match_events(conn, match_id).await?;
+        loaded.push((fantasy_minutes, lineups, events));
+    }
+    let records: Vec<matches::MatchRecord> = loaded
+        .iter()
+        .map(|(fantasy_minutes, lineups, events)| matches::MatchRecord {
+            fantasy_minutes: *fantasy_minutes,
+            lineups,
+            events,
+        })
+        .collect();
+    Ok(matches::stats_from_events(&records))
+}
@@ -1,7 +1,10 @@
 pub mod accounting;
 pub mod admin;
+pub mod audit;
 pub mod chips;
 pub mod leagues;
+pub mod matches;
 pub mod players;
 pub mod points;
+pub mod seasons;
 pub mod teams;
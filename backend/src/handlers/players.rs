@@ -6,7 +6,12 @@ use uuid::Uuid;
 
 use crate::auth::handler::AppState;
 use crate::error::{AppError, AppResult};
-use crate::models::{Player, PlayerLeaderboard, PlayerQuery};
+use crate::handlers::seasons::resolve_season;
+use crate::models::{
+    Player, PlayerLeaderboard, PlayerPriceHistory, PlayerQuery, PriceChangesQuery, PricePoint,
+    WeekPriceChange, WeekPriceChangesResponse,
+};
+use crate::services::price_model;
 
 /// GET /api/players
 ///
@@ -68,11 +73,14 @@ pub async fn list_players(
 
 /// GET /api/players/leaderboard
 ///
-/// Player leaderboard with aggregated stats per position and chosen-by percentage.
+/// Player leaderboard with aggregated stats per position and chosen-by percentage,
+/// for one season (`?season_id=`, default the open one).
 pub async fn leaderboard(
     State(state): State<AppState>,
     Query(query): Query<PlayerQuery>,
 ) -> AppResult<Json<Vec<PlayerLeaderboard>>> {
+    let season = resolve_season(&state.pool, query.season_id).await?;
+
     let rows = match &query.position {
         Some(pos) => {
             sqlx::query_as::<_, PlayerLeaderboard>(
@@ -87,7 +95,7 @@ pub async fn leaderboard(
                      COALESCE(SUM(pp.assists), 0) AS assists,
                      COALESCE(SUM(pp.clean_sheets), 0) AS clean_sheets,
                      COALESCE(SUM(pp.saves), 0) AS saves,
-                     p.total_points,
+                     COALESCE(SUM(pp.total_points), 0)::int AS total_points,
                      COALESCE(
                        (SELECT COUNT(DISTINCT tp.team_id) FROM team_players tp WHERE tp.player_id = p.id)::float
                        / GREATEST((SELECT COUNT(*) FROM fantasy_teams), 1)::float * 100.0,
@@ -95,11 +103,13 @@ pub async fn leaderboard(
                      ) AS chosen_by_percent
                    FROM players p
                    LEFT JOIN player_points pp ON pp.player_id = p.id
+                     AND pp.match_week_id IN (SELECT id FROM match_weeks WHERE season_id = $2)
                    WHERE p.position::text = $1 OR p.secondary_position::text = $1
-                   GROUP BY p.id, p.name, p.position, p.team_name, p.photo_url, p.is_top_player, p.total_points
-                   ORDER BY chosen_by_percent DESC, p.total_points DESC"#,
+                   GROUP BY p.id, p.name, p.position, p.team_name, p.photo_url, p.is_top_player
+                   ORDER BY chosen_by_percent DESC, total_points DESC"#,
             )
             .bind(pos)
+            .bind(season.id)
             .fetch_all(&state.pool)
             .await?
         }
@@ -116,7 +126,7 @@ pub async fn leaderboard(
                      COALESCE(SUM(pp.assists), 0) AS assists,
                      COALESCE(SUM(pp.clean_sheets), 0) AS clean_sheets,
                      COALESCE(SUM(pp.saves), 0) AS saves,
-                     p.total_points,
+                     COALESCE(SUM(pp.total_points), 0)::int AS total_points,
                      COALESCE(
                        (SELECT COUNT(DISTINCT tp.team_id) FROM team_players tp WHERE tp.player_id = p.id)::float
                        / GREATEST((SELECT COUNT(*) FROM fantasy_teams), 1)::float * 100.0,
@@ -124,9 +134,11 @@ pub async fn leaderboard(
                      ) AS chosen_by_percent
                    FROM players p
                    LEFT JOIN player_points pp ON pp.player_id = p.id
-                   GROUP BY p.id, p.name, p.position, p.team_name, p.photo_url, p.is_top_player, p.total_points
-                   ORDER BY chosen_by_percent DESC, p.total_points DESC"#,
+                     AND pp.match_week_id IN (SELECT id FROM match_weeks WHERE season_id = $1)
+                   GROUP BY p.id, p.name, p.position, p.team_name, p.photo_url, p.is_top_player
+                   ORDER BY chosen_by_percent DESC, total_points DESC"#,
             )
+            .bind(season.id)
             .fetch_all(&state.pool)
             .await?
         }
@@ -153,3 +165,109 @@ pub async fn get_player(
 
     Ok(Json(player))
 }
+
+/// GET /api/players/:id/price-history
+///
+/// A player's price after every scored gameweek and season rollover, rebuilt
+/// from the recorded deltas. Weeks where the price held still appear with a zero
+/// delta, so the series can be charted directly.
+pub async fn price_history(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+) -> AppResult<Json<PlayerPriceHistory>> {
+    let player = sqlx::query_as::<_, Player>(
+        r#"SELECT id, name, position, secondary_position, is_top_player, team_name, photo_url, price, total_points, created_at
+           FROM players WHERE id = $1"#,
+    )
+    .bind(player_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Player not found".to_string()))?;
+
+    // Prices only move when a week is finalised, so only finalised weeks count.
+    // Within a week the gameweek adjustment comes before the rollover ('gameweek'
+    // sorts first).
+    let mut history = sqlx::query_as::<_, PricePoint>(
+        r#"SELECT week_number, reason, delta FROM (
+             SELECT mw.week_number, 'gameweek' AS reason,
+                    COALESCE(gpa.delta, 0) AS delta
+             FROM match_weeks mw
+             LEFT JOIN gameweek_price_adjustments gpa
+               ON gpa.match_week_id = mw.id AND gpa.player_id = $1
+             WHERE mw.status = 'finalised'
+             UNION ALL
+             SELECT s.end_week, 'season_rollover', psr.price_after - psr.price_before
+             FROM player_season_results psr
+             INNER JOIN seasons s ON s.id = psr.season_id
+             WHERE psr.player_id = $1 AND s.end_week IS NOT NULL
+           ) AS changes
+           ORDER BY week_number, reason"#,
+    )
+    .bind(player_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let starting_price = price_model::rebuild_prices(player.price, &mut history);
+
+    Ok(Json(PlayerPriceHistory {
+        player_id: player.id,
+        player_name: player.name,
+        starting_price,
+        current_price: player.price,
+        history,
+    }))
+}
+
+/// GET /api/players/price-changes?week=N
+///
+/// Players whose price rose or fell after gameweek N, biggest moves first.
+pub async fn price_changes(
+    State(state): State<AppState>,
+    Query(query): Query<PriceChangesQuery>,
+) -> AppResult<Json<WeekPriceChangesResponse>> {
+    sqlx::query_scalar::<_, i32>("SELECT week_number FROM match_weeks WHERE week_number = $1")
+        .bind(query.week)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Gameweek {} not found", query.week)))?;
+
+    // The price after week N is today's price less every later change.
+    let changes = sqlx::query_as::<_, WeekPriceChange>(
+        r#"SELECT player_id, player_name, team_name, delta,
+                  price_after - delta AS price_before, price_after
+           FROM (
+             SELECT p.id AS player_id, p.name AS player_name, p.team_name, gpa.delta,
+                    p.price
+                      - COALESCE((
+                          SELECT SUM(later.delta)
+                          FROM gameweek_price_adjustments later
+                          INNER JOIN match_weeks lmw ON lmw.id = later.match_week_id
+                          WHERE later.player_id = p.id AND lmw.week_number > $1
+                        ), 0)
+                      - COALESCE((
+                          SELECT SUM(psr.price_after - psr.price_before)
+                          FROM player_season_results psr
+                          INNER JOIN seasons s ON s.id = psr.season_id
+                          WHERE psr.player_id = p.id AND s.end_week >= $1
+                        ), 0) AS price_after
+             FROM gameweek_price_adjustments gpa
+             INNER JOIN match_weeks mw ON mw.id = gpa.match_week_id
+             INNER JOIN players p ON p.id = gpa.player_id
+             WHERE mw.week_number = $1
+           ) AS moved
+           ORDER BY ABS(delta) DESC, player_name"#,
+    )
+    .bind(query.week)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let (risers, fallers) = changes
+        .into_iter()
+        .partition(|c| c.delta.is_sign_positive());
+
+    Ok(Json(WeekPriceChangesResponse {
+        week_number: query.week,
+        risers,
+        fallers,
+    }))
+}
@@ -1,12 +1,23 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
+use futures_util::stream::{self, Stream, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 
 use crate::auth::handler::AppState;
-use crate::error::AppResult;
-use crate::models::PlayerPointsDisplay;
+use crate::error::{AppError, AppResult};
+use crate::handlers::leagues::league_gameweek;
+use crate::handlers::seasons::resolve_season;
+use crate::models::{
+    GameweekStatus, LivePointsQuery, LiveTeamPoints, LiveWeekPoints, PlayerPointsDisplay,
+    SeasonQuery,
+};
 
 /// GET /api/points/week/:week
 ///
@@ -15,7 +26,14 @@ pub async fn get_week_points(
     State(state): State<AppState>,
     Path(week_number): Path<i32>,
 ) -> AppResult<Json<Vec<PlayerPointsDisplay>>> {
-    let points = sqlx::query_as::<_, PlayerPointsDisplay>(
+    Ok(Json(week_player_points(&state.pool, week_number).await?))
+}
+
+async fn week_player_points(
+    pool: &sqlx::PgPool,
+    week_number: i32,
+) -> AppResult<Vec<PlayerPointsDisplay>> {
+    Ok(sqlx::query_as::<_, PlayerPointsDisplay>(
         r#"SELECT
              pp.player_id,
              p.name AS player_name,
@@ -35,19 +53,136 @@ pub async fn get_week_points(
            ORDER BY pp.total_points DESC"#,
     )
     .bind(week_number)
-    .fetch_all(&state.pool)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Player and team points for a gameweek as they stand now.
+async fn week_snapshot(pool: &sqlx::PgPool, week_number: i32) -> AppResult<LiveWeekPoints> {
+    let status = sqlx::query_scalar::<_, GameweekStatus>(
+        "SELECT status FROM match_weeks WHERE week_number = $1",
+    )
+    .bind(week_number)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Gameweek {week_number} not found")))?;
+    let players = week_player_points(pool, week_number).await?;
+    let teams = sqlx::query_as::<_, LiveTeamPoints>(
+        r#"SELECT ft.id AS team_id, ft.name AS team_name, ft.user_id, tgp.total_points
+           FROM team_gameweek_points tgp
+           INNER JOIN fantasy_teams ft ON ft.id = tgp.team_id
+           INNER JOIN match_weeks mw ON mw.id = tgp.match_week_id
+           WHERE mw.week_number = $1
+           ORDER BY tgp.total_points DESC, ft.name"#,
+    )
+    .bind(week_number)
+    .fetch_all(pool)
     .await?;
 
-    Ok(Json(points))
+    Ok(LiveWeekPoints {
+        week_number,
+        status,
+        players,
+        teams,
+    })
+}
+
+/// Push a freshly scored gameweek to live subscribers. Call after the scoring
+/// transaction commits; a failure here only costs subscribers an update.
+pub(crate) async fn publish_week(state: &AppState, week_number: i32) {
+    if state.live.receiver_count() == 0 {
+        return;
+    }
+    match week_snapshot(&state.pool, week_number).await {
+        // Sending only fails when everyone has just disconnected.
+        Ok(snapshot) => {
+            let _ = state.live.send(Arc::new(snapshot));
+        }
+        Err(e) => tracing::warn!("Failed to publish live points for week {week_number}: {e}"),
+    }
+}
+
+/// GET /api/points/live/:week?league_id=
+///
+/// Server-sent events for a gameweek. A `points` event carries player points
+/// and team totals, first as they stand on connecting and again whenever the
+/// week's stats are submitted. With `league_id`, each is followed by a `league`
+/// event with that league's gameweek standings. Points stay provisional until
+/// the week is finalised, which sends one more update.
+pub async fn live_week_points(
+    State(state): State<AppState>,
+    Path(week_number): Path<i32>,
+    Query(query): Query<LivePointsQuery>,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    // Subscribe before loading the snapshot so no update slips between them.
+    let updates = state.live.subscribe();
+    if let Some(league_id) = query.league_id {
+        league_gameweek(&state.pool, league_id, week_number).await?;
+    }
+    let first = Arc::new(week_snapshot(&state.pool, week_number).await?);
+
+    let pool = state.pool.clone();
+    let events = stream::unfold((updates, Some(first)), move |(mut updates, pending)| {
+        let pool = pool.clone();
+        async move {
+            let update = match pending {
+                Some(update) => update,
+                None => loop {
+                    match updates.recv().await {
+                        Ok(update) if update.week_number == week_number => break update,
+                        Ok(_) => continue,
+                        // Missed some updates; the database has the latest.
+                        Err(RecvError::Lagged(_)) => {
+                            match week_snapshot(&pool, week_number).await {
+                                Ok(snapshot) => break Arc::new(snapshot),
+                                Err(e) => {
+                                    tracing::warn!(
+                                        "Failed to reload live points for week {week_number}: {e}"
+                                    );
+                                    continue;
+                                }
+                            }
+                        }
+                        Err(RecvError::Closed) => return None,
+                    }
+                },
+            };
+
+            let mut events = Vec::with_capacity(2);
+            if let Ok(event) = Event::default().event("points").json_data(&*update) {
+                events.push(Ok(event));
+            }
+            if let Some(league_id) = query.league_id {
+                match league_gameweek(&pool, league_id, week_number).await {
+                    Ok(league) => {
+                        if let Ok(event) = Event::default().event("league").json_data(&league) {
+                            events.push(Ok(event));
+                        }
+                    }
+                    Err(e) => tracing::warn!(
+                        "Failed to load league {league_id} standings for week {week_number}: {e}"
+                    ),
+                }
+            }
+            Some((stream::iter(events), (updates, None)))
+        }
+    })
+    .flatten();
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
 }
 
 /// GET /api/points/player/:id
 ///
-/// Get a player's point history across all match weeks.
+/// Get a player's point history across a season's match weeks (`?season_id=`,
+/// default the open one).
 pub async fn get_player_points(
     State(state): State<AppState>,
     Path(player_id): Path<Uuid>,
+    Query(query): Query<SeasonQuery>,
 ) -> AppResult<Json<Vec<PlayerPointsDisplay>>> {
+    let season = resolve_season(&state.pool, query.season_id).await?;
+
     let points = sqlx::query_as::<_, PlayerPointsDisplay>(
         r#"SELECT
              pp.player_id,
@@ -64,10 +199,11 @@ pub async fn get_player_points(
            FROM player_points pp
            INNER JOIN players p ON p.id = pp.player_id
            INNER JOIN match_weeks mw ON mw.id = pp.match_week_id
-           WHERE pp.player_id = $1
+           WHERE pp.player_id = $1 AND mw.season_id = $2
            ORDER BY mw.week_number ASC"#,
     )
     .bind(player_id)
+    .bind(season.id)
     .fetch_all(&state.pool)
     .await?;
 
@@ -0,0 +1,444 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::auth::handler::AppState;
+use crate::auth::middleware::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::handlers::admin::price_floor;
+use crate::models::{
+    CloseSeasonRequest, CloseSeasonResponse, PlayerSeasonResult, Season, SeasonStanding,
+    UpdateSeasonSettingsRequest, SEASON_NAMES,
+};
+use crate::services::audit::AuditEntry;
+use crate::services::price_model;
+
+const SEASON_COLUMNS: &str =
+    "id, name, year, start_week, end_week, closed_at, created_at, vice_captain_fallback, auto_subs, \
+     second_half_start_week, free_transfers_per_week, max_banked_transfers, transfer_hit_cost, \
+     price_model";
+
+/// Each team's points across a season's gameweeks, ranked. Binds `$1` = season id.
+const SEASON_STANDINGS_SQL: &str = r#"
+    SELECT ft.id AS team_id,
+           ft.user_id,
+           ft.name AS team_name,
+           s.pts AS total_points,
+           RANK() OVER (ORDER BY s.pts DESC)::int AS rank
+    FROM fantasy_teams ft
+    CROSS JOIN LATERAL (
+      SELECT COALESCE(SUM(tgp.total_points), 0)::int AS pts
+      FROM team_gameweek_points tgp
+      INNER JOIN match_weeks mw ON mw.id = tgp.match_week_id
+      WHERE tgp.team_id = ft.id AND mw.season_id = $1
+    ) s"#;
+
+/// The highest gameweek of a season that has been activated or scored, if any.
+///
+/// Weeks created in advance stay `upcoming` until then, so they don't count.
+pub(crate) async fn last_played_week<'e, E: sqlx::PgExecutor<'e>>(
+    executor: E,
+    season_id: Uuid,
+) -> Result<Option<i32>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<i32>>(
+        "SELECT MAX(week_number) FROM match_weeks WHERE season_id = $1 AND status <> 'upcoming'",
+    )
+    .bind(season_id)
+    .fetch_one(executor)
+    .await
+}
+
+/// Look up a season by id, or the open season when `season_id` is `None`.
+pub async fn resolve_season(pool: &sqlx::PgPool, season_id: Option<Uuid>) -> AppResult<Season> {
+    let season = match season_id {
+        Some(id) => {
+            sqlx::query_as::<_, Season>(&format!(
+                "SELECT {SEASON_COLUMNS} FROM seasons WHERE id = $1"
+            ))
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+        }
+        None => {
+            sqlx::query_as::<_, Season>(&format!(
+                "SELECT {SEASON_COLUMNS} FROM seasons WHERE closed_at IS NULL"
+            ))
+            .fetch_optional(pool)
+            .await?
+        }
+    };
+
+    season.ok_or_else(|| AppError::NotFound("Season not found".to_string()))
+}
+
+/// The season after `name` in playing order, and the year it falls in.
+fn following_season(name: &str, year: i32) -> (String, i32) {
+    let idx = SEASON_NAMES.iter().position(|n| *n == name).unwrap_or(0);
+    let next = (idx + 1) % SEASON_NAMES.len();
+    let next_year = if next == 0 { year + 1 } else { year };
+    (SEASON_NAMES[next].to_string(), next_year)
+}
+
+/// GET /api/seasons
+///
+/// List all seasons, most recent first.
+pub async fn list_seasons(State(state): State<AppState>) -> AppResult<Json<Vec<Season>>> {
+    let seasons = sqlx::query_as::<_, Season>(&format!(
+        "SELECT {SEASON_COLUMNS} FROM seasons ORDER BY start_week DESC"
+    ))
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(seasons))
+}
+
+/// GET /api/seasons/current
+///
+/// Get the open season.
+pub async fn get_current_season(State(state): State<AppState>) -> AppResult<Json<Season>> {
+    Ok(Json(resolve_season(&state.pool, None).await?))
+}
+
+/// GET /api/seasons/:id/standings
+///
+/// Final standings for a closed season, or live standings for the open one.
+pub async fn get_season_standings(
+    State(state): State<AppState>,
+    Path(season_id): Path<Uuid>,
+) -> AppResult<Json<Vec<SeasonStanding>>> {
+    let season = resolve_season(&state.pool, Some(season_id)).await?;
+
+    let standings = if season.closed_at.is_some() {
+        sqlx::query_as::<_, SeasonStanding>(
+            r#"SELECT team_id, user_id, team_name, total_points, rank
+               FROM season_standings
+               WHERE season_id = $1
+               ORDER BY rank, team_name"#,
+        )
+        .bind(season.id)
+        .fetch_all(&state.pool)
+        .await?
+    } else {
+        sqlx::query_as::<_, SeasonStanding>(&format!(
+            "{SEASON_STANDINGS_SQL} ORDER BY rank, team_name"
+        ))
+        .bind(season.id)
+        .fetch_all(&state.pool)
+        .await?
+    };
+
+    Ok(Json(standings))
+}
+
+/// PUT /api/admin/season/settings
+///
+/// Change the open season's rules. A change applies to gameweeks scored from
+/// then on; resubmit a week's stats to rescore it under the new rules.
+pub async fn update_season_settings(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Json(body): Json<UpdateSeasonSettingsRequest>,
+) -> AppResult<Json<Season>> {
+    let mut tx = state.pool.begin().await?;
+
+    let season = sqlx::query_as::<_, Season>(&format!(
+        "SELECT {SEASON_COLUMNS} FROM seasons WHERE closed_at IS NULL FOR UPDATE"
+    ))
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("There is no open season".to_string()))?;
+
+    if body
+        .second_half_start_week
+        .is_some_and(|week| week <= season.start_week)
+    {
+        return Err(AppError::BadRequest(format!(
+            "The second half must start after gameweek {}, where {} {} begins",
+            season.start_week, season.name, season.year
+        )));
+    }
+
+    let free_transfers_per_week = body
+        .free_transfers_per_week
+        .unwrap_or(season.free_transfers_per_week);
+    let max_banked_transfers = body
+        .max_banked_transfers
+        .unwrap_or(season.max_banked_transfers);
+    if free_transfers_per_week < 0 || body.transfer_hit_cost.is_some_and(|cost| cost < 0) {
+        return Err(AppError::BadRequest(
+            "Free transfers and the hit cost cannot be negative".to_string(),
+        ));
+    }
+    if max_banked_transfers < free_transfers_per_week {
+        return Err(AppError::BadRequest(format!(
+            "max_banked_transfers ({max_banked_transfers}) cannot be below free_transfers_per_week ({free_transfers_per_week})"
+        )));
+    }
+
+    let updated = sqlx::query_as::<_, Season>(&format!(
+        r#"UPDATE seasons
+           SET vice_captain_fallback = COALESCE($2, vice_captain_fallback),
+               auto_subs = COALESCE($3, auto_subs),
+               second_half_start_week = COALESCE($4, second_half_start_week),
+               free_transfers_per_week = $5,
+               max_banked_transfers = $6,
+               transfer_hit_cost = COALESCE($7, transfer_hit_cost),
+               price_model = COALESCE($8, price_model)
+           WHERE id = $1
+           RETURNING {SEASON_COLUMNS}"#
+    ))
+    .bind(season.id)
+    .bind(body.vice_captain_fallback)
+    .bind(body.auto_subs)
+    .bind(body.second_half_start_week)
+    .bind(free_transfers_per_week)
+    .bind(max_banked_transfers)
+    .bind(body.transfer_hit_cost)
+    .bind(body.price_model)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEntry::new(auth.user_id, "season.settings", "season", season.id)
+        .before(&season)
+        .after(&updated)
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/admin/season/close
+///
+/// Close the open season and start the next one. Archives final standings,
+/// reprices every player by their season points quartile, resets
+/// `players.total_points` to 0 and re-buys each squad at the new prices, keeping
+/// the team's bank. Chips come back automatically because their usage is per season, and
+/// the next season starts with the closing season's rules, minus the half-season
+/// split, whose week numbers only make sense for the closing season. Every
+/// gameweek with stats must be finalised first.
+pub async fn close_season(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Json(body): Json<CloseSeasonRequest>,
+) -> AppResult<Json<CloseSeasonResponse>> {
+    let mut tx = state.pool.begin().await?;
+
+    let season = sqlx::query_as::<_, Season>(&format!(
+        "SELECT {SEASON_COLUMNS} FROM seasons WHERE closed_at IS NULL FOR UPDATE"
+    ))
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("There is no open season to close".to_string()))?;
+
+    let (default_name, default_year) = following_season(&season.name, season.year);
+    let next_name = body.next_name.unwrap_or(default_name);
+    let next_year = body.next_year.unwrap_or(default_year);
+    if !SEASON_NAMES.contains(&next_name.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "Season name must be one of {}",
+            SEASON_NAMES.join(", ")
+        )));
+    }
+
+    let end_week = last_played_week(&mut *tx, season.id)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "{} {} has not played any gameweeks yet, so there is nothing to close",
+                season.name, season.year
+            ))
+        })?;
+
+    let provisional = sqlx::query_scalar::<_, i32>(
+        r#"SELECT week_number FROM match_weeks
+           WHERE season_id = $1 AND status = 'provisional'
+           ORDER BY week_number"#,
+    )
+    .bind(season.id)
+    .fetch_all(&mut *tx)
+    .await?;
+    if !provisional.is_empty() {
+        let weeks: Vec<String> = provisional.iter().map(i32::to_string).collect();
+        return Err(AppError::Conflict(format!(
+            "Finalise gameweek(s) {} before closing the season",
+            weeks.join(", ")
+        )));
+    }
+
+    sqlx::query(&format!(
+        r#"INSERT INTO season_standings (season_id, team_id, user_id, team_name, total_points, rank)
+           SELECT $1, team_id, user_id, team_name, total_points, rank
+           FROM ({SEASON_STANDINGS_SQL}) AS standings"#
+    ))
+    .bind(season.id)
+    .execute(&mut *tx)
+    .await?;
+
+    #[derive(sqlx::FromRow)]
+    struct SeasonPoints {
+        id: Uuid,
+        price: Decimal,
+        season_points: i32,
+    }
+
+    let players = sqlx::query_as::<_, SeasonPoints>(
+        r#"SELECT p.id, p.price,
+                  COALESCE((
+                    SELECT SUM(pp.total_points)
+                    FROM player_points pp
+                    INNER JOIN match_weeks mw ON mw.id = pp.match_week_id
+                    WHERE pp.player_id = p.id AND mw.season_id = $1
+                  ), 0)::int AS season_points
+           FROM players p
+           ORDER BY season_points DESC, p.name ASC"#,
+    )
+    .bind(season.id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let ranked: Vec<(Decimal, i32)> = players.iter().map(|p| (p.price, p.season_points)).collect();
+    let new_prices = price_model::season_prices(&ranked, price_floor());
+    for (player, new_price) in players.iter().zip(new_prices) {
+        sqlx::query("UPDATE players SET price = $1, total_points = 0 WHERE id = $2")
+            .bind(new_price)
+            .bind(player.id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"INSERT INTO player_season_results
+                 (season_id, player_id, total_points, price_before, price_after)
+               VALUES ($1, $2, $3, $4, $5)"#,
+        )
+        .bind(season.id)
+        .bind(player.id)
+        .bind(player.season_points)
+        .bind(player.price)
+        .bind(new_price)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Squads count as bought afresh at the new prices, keeping each team's bank, so
+    // repricing neither grants nor takes away budget from anyone.
+    sqlx::query(
+        r#"UPDATE fantasy_teams ft
+           SET budget_limit = ft.budget_limit - team_cost.purchase_total + team_cost.price_total
+           FROM (
+             SELECT tp.team_id,
+                    SUM(tp.purchase_price) AS purchase_total,
+                    SUM(p.price) AS price_total
+             FROM team_players tp
+             JOIN players p ON p.id = tp.player_id
+             GROUP BY tp.team_id
+           ) AS team_cost
+           WHERE ft.id = team_cost.team_id"#,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE team_players tp SET purchase_price = p.price FROM players p WHERE p.id = tp.player_id",
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE match_weeks SET is_active = false WHERE season_id = $1")
+        .bind(season.id)
+        .execute(&mut *tx)
+        .await?;
+
+    let closed = sqlx::query_as::<_, Season>(&format!(
+        "UPDATE seasons SET closed_at = NOW(), end_week = $2 WHERE id = $1 RETURNING {SEASON_COLUMNS}"
+    ))
+    .bind(season.id)
+    .bind(end_week)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let next = sqlx::query_as::<_, Season>(&format!(
+        r#"INSERT INTO seasons
+             (name, year, start_week, vice_captain_fallback, auto_subs,
+              free_transfers_per_week, max_banked_transfers, transfer_hit_cost, price_model)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+           RETURNING {SEASON_COLUMNS}"#
+    ))
+    .bind(&next_name)
+    .bind(next_year)
+    .bind(end_week + 1)
+    .bind(season.vice_captain_fallback)
+    .bind(season.auto_subs)
+    .bind(season.free_transfers_per_week)
+    .bind(season.max_banked_transfers)
+    .bind(season.transfer_hit_cost)
+    .bind(season.price_model)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::Conflict(format!("{next_name} {next_year} already exists"))
+        }
+        other => other.into(),
+    })?
+    .ok_or_else(|| AppError::Internal("Failed to open the next season".to_string()))?;
+
+    // Weeks created ahead of time but never played belong to the new season,
+    // which starts straight after the last played week.
+    sqlx::query("UPDATE match_weeks SET season_id = $1 WHERE season_id = $2 AND week_number > $3")
+        .bind(next.id)
+        .bind(season.id)
+        .bind(end_week)
+        .execute(&mut *tx)
+        .await?;
+
+    let standings = sqlx::query_as::<_, SeasonStanding>(
+        r#"SELECT team_id, user_id, team_name, total_points, rank
+           FROM season_standings
+           WHERE season_id = $1
+           ORDER BY rank, team_name"#,
+    )
+    .bind(closed.id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let price_changes = sqlx::query_as::<_, PlayerSeasonResult>(
+        r#"SELECT psr.player_id, p.name AS player_name, psr.total_points,
+                  psr.price_before, psr.price_after
+           FROM player_season_results psr
+           INNER JOIN players p ON p.id = psr.player_id
+           WHERE psr.season_id = $1
+           ORDER BY psr.total_points DESC, p.name ASC"#,
+    )
+    .bind(closed.id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    AuditEntry::new(auth.user_id, "season.close", "season", closed.id)
+        .before(&season)
+        .after(serde_json::json!({ "closed": &closed, "next": &next }))
+        .record(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        "Closed {} {} after gameweek {}; opened {} {}",
+        closed.name,
+        closed.year,
+        end_week,
+        next.name,
+        next.year
+    );
+
+    Ok(Json(CloseSeasonResponse {
+        closed,
+        next,
+        standings,
+        price_changes,
+    }))
+}
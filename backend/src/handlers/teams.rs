@@ -2,55 +2,62 @@ use axum::{
     extract::{Extension, Path, State},
     Json,
 };
-use chrono::{Datelike, Timelike, Utc};
-use chrono_tz::America::New_York;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::Serialize;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::auth::handler::AppState;
 use crate::auth::middleware::AuthUser;
 use crate::error::{AppError, AppResult};
+use crate::handlers::points::publish_week;
+use crate::handlers::seasons::resolve_season;
 use crate::models::{
-    CreateTeamRequest, FantasyTeam, FantasyTeamWithPlayers, Player, PlayerPosition,
-    SetPlayersRequest, StarterPlayer, TransferRecord, TransferRequest, TransferStatusResponse,
+    AutoSubstitution, BatchTransferRequest, BatchTransferResponse, ChipType, CreateTeamRequest,
+    FantasyTeam, FantasyTeamWithPlayers, GameweekStatus, LockSchedule, Player, PlayerPosition,
+    SetPlayersRequest, StarterPlayer, TransferHistoryEntry, TransferRecord, TransferRequest,
+    TransferStatusResponse,
 };
-use crate::services::points_sql;
+use crate::services::deadlines::{self, LockWindow, WeeklyLock};
+use crate::services::squad_rules::{self, SquadSlot, Swap};
+use crate::services::{points_sql, transfer_policy};
 
 #[derive(Debug, Serialize)]
 pub struct LockStatusResponse {
     pub locked: bool,
+    /// While locked, when the lock began; otherwise the next deadline.
+    pub deadline_at: Option<String>,
     pub unlock_at: Option<String>,
     pub manually_unlocked: bool,
     pub active_gameweek: Option<i32>,
+    /// The timezone the times above are given in.
+    pub timezone: String,
+    /// `unlock_at` for people, e.g. "Sun 19 Oct 12:00 PM EDT".
+    #[serde(skip)]
+    unlock_label: Option<String>,
 }
 
-fn scheduled_lock_status() -> (bool, Option<String>) {
-    let now_et = Utc::now().with_timezone(&New_York);
-    let weekday = now_et.weekday();
-
-    // Locked from Saturday 10:00 PM ET through Sunday before 12:00 PM ET.
-    let locked = (matches!(weekday, chrono::Weekday::Sat) && now_et.hour() >= 22)
-        || (matches!(weekday, chrono::Weekday::Sun) && now_et.hour() < 12);
-
-    let unlock_at = if locked {
-        let unlock_date = if matches!(weekday, chrono::Weekday::Sat) {
-            (now_et + chrono::Duration::days(1)).date_naive()
-        } else {
-            now_et.date_naive()
-        };
-        unlock_date
-            .and_hms_opt(12, 0, 0)
-            .map(|dt| {
-                dt.and_local_timezone(New_York)
-                    .single()
-                    .map(|t| t.to_rfc3339())
-            })
-            .flatten()
-    } else {
-        None
-    };
+impl LockStatusResponse {
+    /// The error for an action refused while lineups are locked; `what` reads
+    /// like "Transfers are locked".
+    pub fn locked_error(&self, what: &str) -> AppError {
+        match &self.unlock_label {
+            Some(at) => AppError::BadRequest(format!("{what} until {at}")),
+            None => AppError::BadRequest(format!("{what} for this gameweek")),
+        }
+    }
+}
 
-    (locked, unlock_at)
+/// The weekly lock schedule admins have set.
+pub(crate) async fn lock_schedule(pool: &sqlx::PgPool) -> AppResult<WeeklyLock> {
+    let settings = sqlx::query_as::<_, LockSchedule>(
+        r#"SELECT timezone, lock_weekday, lock_time, unlock_weekday, unlock_time
+           FROM lineup_lock_control WHERE id = true"#,
+    )
+    .fetch_one(pool)
+    .await?;
+    WeeklyLock::from_settings(&settings).map_err(AppError::Internal)
 }
 
 pub async fn compute_lock_status(pool: &sqlx::PgPool) -> AppResult<LockStatusResponse> {
@@ -61,21 +68,37 @@ pub async fn compute_lock_status(pool: &sqlx::PgPool) -> AppResult<LockStatusRes
     .await?
     .unwrap_or(false);
 
-    let active_gameweek = sqlx::query_scalar::<_, i32>(
-        "SELECT week_number FROM match_weeks WHERE is_active = true LIMIT 1",
+    let active = sqlx::query_as::<_, (i32, Option<DateTime<Utc>>, Option<DateTime<Utc>>)>(
+        "SELECT week_number, deadline_at, unlock_at FROM match_weeks WHERE is_active = true LIMIT 1",
     )
     .fetch_optional(pool)
     .await?;
-
-    let (scheduled_locked, scheduled_unlock_at) = scheduled_lock_status();
-    let locked = scheduled_locked && !manually_unlocked;
-    let unlock_at = if locked { scheduled_unlock_at } else { None };
+    let schedule = lock_schedule(pool).await?;
+
+    let now = Utc::now();
+    let own = active.and_then(|(_, deadline_at, unlock_at)| {
+        Some(LockWindow {
+            deadline_at: deadline_at?,
+            unlock_at: unlock_at?,
+        })
+    });
+    let window = deadlines::current_window(now, own, &schedule);
+    let locked = window.contains(now) && !manually_unlocked;
+    let local = |at: DateTime<Utc>| at.with_timezone(&schedule.timezone);
 
     Ok(LockStatusResponse {
         locked,
-        unlock_at,
+        // A gameweek's own window can be over with no later one known yet.
+        deadline_at: (now < window.unlock_at).then(|| local(window.deadline_at).to_rfc3339()),
+        unlock_at: locked.then(|| local(window.unlock_at).to_rfc3339()),
+        unlock_label: locked.then(|| {
+            local(window.unlock_at)
+                .format("%a %-d %b %-I:%M %p %Z")
+                .to_string()
+        }),
         manually_unlocked,
-        active_gameweek,
+        active_gameweek: active.map(|(week_number, _, _)| week_number),
+        timezone: schedule.timezone.name().to_string(),
     })
 }
 
@@ -116,7 +139,7 @@ pub async fn create_team(
     let team = sqlx::query_as::<_, FantasyTeam>(
         r#"INSERT INTO fantasy_teams (user_id, name)
            VALUES ($1, $2)
-           RETURNING id, user_id, name, captain_id, budget_limit, created_at"#,
+           RETURNING id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at"#,
     )
     .bind(auth.user_id)
     .bind(&body.name)
@@ -126,13 +149,15 @@ pub async fn create_team(
     Ok(Json(team))
 }
 
-/// Fetch a team's 6 starters with the points they earned for that team.
+/// Fetch a team's 6 starters with the points they earned for that team this season.
 pub async fn fetch_team_starters(
     pool: &sqlx::PgPool,
     team_id: Uuid,
 ) -> Result<Vec<StarterPlayer>, AppError> {
+    let season = resolve_season(pool, None).await?;
     let rows = sqlx::query_as::<_, StarterRow>(&points_sql::squad_season_points(false))
         .bind(team_id)
+        .bind(season.id)
         .fetch_all(pool)
         .await?;
 
@@ -141,12 +166,11 @@ pub async fn fetch_team_starters(
 
 /// Fetch a team's 3 bench players. Points reflect the role their manager assigned,
 /// and only ever counted towards a total in a Bench Boost week.
-pub async fn fetch_team_bench(
-    pool: &sqlx::PgPool,
-    team_id: Uuid,
-) -> Result<Vec<Player>, AppError> {
+pub async fn fetch_team_bench(pool: &sqlx::PgPool, team_id: Uuid) -> Result<Vec<Player>, AppError> {
+    let season = resolve_season(pool, None).await?;
     let rows = sqlx::query_as::<_, StarterRow>(&points_sql::squad_season_points(true))
         .bind(team_id)
+        .bind(season.id)
         .fetch_all(pool)
         .await?;
 
@@ -167,7 +191,7 @@ struct StarterRow {
     is_top_player: bool,
     team_name: String,
     photo_url: Option<String>,
-    price: rust_decimal::Decimal,
+    price: Decimal,
     total_points: i32,
     created_at: chrono::DateTime<chrono::Utc>,
     // Assigned position from team_players
@@ -207,49 +231,73 @@ async fn build_team_response(
 
     let total_points = team_total_points(pool, team.id).await?;
 
+    let squad_prices = sqlx::query_as::<_, (Decimal, Decimal)>(
+        r#"SELECT tp.purchase_price, p.price
+           FROM team_players tp
+           JOIN players p ON p.id = tp.player_id
+           WHERE tp.team_id = $1"#,
+    )
+    .bind(team.id)
+    .fetch_all(pool)
+    .await?;
+    let purchase_total: Decimal = squad_prices.iter().map(|(purchase, _)| *purchase).sum();
+    let selling_value: Decimal = squad_prices
+        .iter()
+        .map(|(purchase, price)| squad_rules::selling_price(*purchase, *price))
+        .sum();
+
     Ok(FantasyTeamWithPlayers {
         id: team.id,
         user_id: team.user_id,
         name: team.name.clone(),
         captain_id: team.captain_id,
+        vice_captain_id: team.vice_captain_id,
         budget_limit: team.budget_limit,
         created_at: team.created_at,
+        bank: team.budget_limit - purchase_total,
+        selling_value,
         players: starters,
         bench,
         total_points,
     })
 }
 
+/// A team's points in the open season.
 async fn team_total_points(pool: &sqlx::PgPool, team_id: Uuid) -> Result<i32, AppError> {
+    let season = resolve_season(pool, None).await?;
     let total = sqlx::query_scalar::<_, i64>(
-        "SELECT COALESCE(SUM(total_points), 0) FROM team_gameweek_points WHERE team_id = $1",
+        r#"SELECT COALESCE(SUM(tgp.total_points), 0)
+           FROM team_gameweek_points tgp
+           INNER JOIN match_weeks mw ON mw.id = tgp.match_week_id
+           WHERE tgp.team_id = $1 AND mw.season_id = $2"#,
     )
     .bind(team_id)
+    .bind(season.id)
     .fetch_one(pool)
     .await?;
 
     Ok(total as i32)
 }
 
-async fn snapshot_team_lineup_if_missing(
+pub async fn snapshot_team_lineup_if_missing(
     pool: &sqlx::PgPool,
     team_id: Uuid,
     match_week_id: Uuid,
 ) -> Result<(), AppError> {
     let mut tx = pool.begin().await?;
 
-    let captain_id = sqlx::query_scalar::<_, Option<Uuid>>(
-        "SELECT captain_id FROM fantasy_teams WHERE id = $1",
+    let (captain_id, vice_captain_id) = sqlx::query_as::<_, (Option<Uuid>, Option<Uuid>)>(
+        "SELECT captain_id, vice_captain_id FROM fantasy_teams WHERE id = $1",
     )
     .bind(team_id)
     .fetch_optional(&mut *tx)
     .await?
-    .flatten();
+    .unwrap_or((None, None));
 
     // Keep first snapshot immutable for a team+week.
     let lineup_id = sqlx::query_scalar::<_, Uuid>(
-        r#"INSERT INTO team_gameweek_lineups (team_id, match_week_id, captain_id)
-           VALUES ($1, $2, $3)
+        r#"INSERT INTO team_gameweek_lineups (team_id, match_week_id, captain_id, vice_captain_id)
+           VALUES ($1, $2, $3, $4)
            ON CONFLICT (team_id, match_week_id) DO UPDATE
              SET captain_id = team_gameweek_lineups.captain_id
            RETURNING id"#,
@@ -257,13 +305,14 @@ async fn snapshot_team_lineup_if_missing(
     .bind(team_id)
     .bind(match_week_id)
     .bind(captain_id)
+    .bind(vice_captain_id)
     .fetch_one(&mut *tx)
     .await?;
 
     sqlx::query(
         r#"INSERT INTO team_gameweek_lineup_players
-             (team_gameweek_lineup_id, player_id, is_bench, assigned_position)
-           SELECT $1, tp.player_id, tp.is_bench, tp.assigned_position
+             (team_gameweek_lineup_id, player_id, is_bench, assigned_position, bench_order)
+           SELECT $1, tp.player_id, tp.is_bench, tp.assigned_position, tp.bench_order
            FROM team_players tp
            WHERE tp.team_id = $2
            ON CONFLICT (team_gameweek_lineup_id, player_id) DO NOTHING"#,
@@ -285,7 +334,7 @@ pub async fn get_my_team(
     Extension(auth): Extension<AuthUser>,
 ) -> AppResult<Json<FantasyTeamWithPlayers>> {
     let team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE user_id = $1",
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE user_id = $1",
     )
     .bind(auth.user_id)
     .fetch_optional(&state.pool)
@@ -307,7 +356,8 @@ pub async fn get_my_team(
 ///   - Each player's assigned_position must match their position or secondary_position
 ///   - Bench: exactly 1 GK + 2 outfield (DEF/MID/FWD)
 ///   - Captain must be one of the 6 starters
-///   - Captain's name must NOT match the user's full_name (case-insensitive)
+///   - Vice-captain, if given, must be a different starter
+///   - Captain's name must NOT match the user's full_name (case-insensitive), nor the vice-captain's
 pub async fn set_team_players(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthUser>,
@@ -316,9 +366,7 @@ pub async fn set_team_players(
 ) -> AppResult<Json<FantasyTeamWithPlayers>> {
     let lock = compute_lock_status(&state.pool).await?;
     if lock.locked {
-        return Err(AppError::BadRequest(
-            "Lineup changes are locked from Saturday 10:00 PM ET to Sunday 12:00 PM ET".to_string(),
-        ));
+        return Err(lock.locked_error("Lineup changes are locked"));
     }
 
     // Validate exactly 6 starters
@@ -343,6 +391,19 @@ pub async fn set_team_players(
         ));
     }
 
+    if let Some(vice_captain_id) = body.vice_captain_id {
+        if vice_captain_id == body.captain_id {
+            return Err(AppError::BadRequest(
+                "Vice-captain must be a different player from the captain".to_string(),
+            ));
+        }
+        if !starter_ids.contains(&vice_captain_id) {
+            return Err(AppError::BadRequest(
+                "Vice-captain must be one of the 6 starting players".to_string(),
+            ));
+        }
+    }
+
     // Combine all player IDs and check for duplicates
     let mut all_ids = starter_ids.clone();
     all_ids.extend(&body.bench_player_ids);
@@ -390,7 +451,7 @@ pub async fn set_team_players(
 
     // Verify team ownership
     let team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
     )
     .bind(team_id)
     .bind(auth.user_id)
@@ -400,11 +461,10 @@ pub async fn set_team_players(
 
     // When a gameweek is active, only allow rearranging existing squad (no new players).
     // To bring in new players, use the transfer endpoint.
-    let active_week_id = sqlx::query_scalar::<_, Uuid>(
-        "SELECT id FROM match_weeks WHERE is_active = true LIMIT 1",
-    )
-    .fetch_optional(&state.pool)
-    .await?;
+    let active_week_id =
+        sqlx::query_scalar::<_, Uuid>("SELECT id FROM match_weeks WHERE is_active = true LIMIT 1")
+            .fetch_optional(&state.pool)
+            .await?;
 
     if let Some(week_id) = active_week_id {
         snapshot_team_lineup_if_missing(&state.pool, team_id, week_id).await?;
@@ -467,7 +527,7 @@ pub async fn set_team_players(
         let matches_secondary = player
             .secondary_position
             .as_ref()
-            .map_or(false, |sp| *sp == assignment.assigned_position);
+            .is_some_and(|sp| *sp == assignment.assigned_position);
 
         if !matches_primary && !matches_secondary {
             return Err(AppError::BadRequest(format!(
@@ -506,6 +566,22 @@ pub async fn set_team_players(
         )));
     }
 
+    if let Some(vice_captain) = body
+        .vice_captain_id
+        .and_then(|id| starter_players.iter().find(|p| p.id == id))
+    {
+        if vice_captain
+            .name
+            .trim()
+            .eq_ignore_ascii_case(user_full_name.trim())
+        {
+            return Err(AppError::BadRequest(format!(
+                "You cannot vice-captain {} because they share your name. Choose a different vice-captain.",
+                vice_captain.name
+            )));
+        }
+    }
+
     // Enforce max 2 top players across entire squad (starters + bench)
     let top_player_count = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM players WHERE id = ANY($1) AND is_top_player = true",
@@ -520,18 +596,50 @@ pub async fn set_team_players(
         ));
     }
 
-    // Enforce $70 budget cap across all 9 players
-    let total_cost = sqlx::query_scalar::<_, rust_decimal::Decimal>(
-        "SELECT COALESCE(SUM(price), 0) FROM players WHERE id = ANY($1)",
+    // Enforce the budget across all 9 players. Players kept from the current squad
+    // cost what the team paid for them, new ones today's price, and each player
+    // dropped is sold at their selling price.
+    #[derive(sqlx::FromRow)]
+    struct OwnedPlayer {
+        player_id: Uuid,
+        purchase_price: Decimal,
+        price: Decimal,
+    }
+
+    let owned = sqlx::query_as::<_, OwnedPlayer>(
+        r#"SELECT tp.player_id, tp.purchase_price, p.price
+           FROM team_players tp
+           JOIN players p ON p.id = tp.player_id
+           WHERE tp.team_id = $1"#,
     )
-    .bind(&all_ids)
-    .fetch_one(&state.pool)
+    .bind(team_id)
+    .fetch_all(&state.pool)
     .await?;
 
-    if total_cost > team.budget_limit {
+    let purchase_prices: HashMap<Uuid, Decimal> = owned
+        .iter()
+        .filter(|o| all_ids.contains(&o.player_id))
+        .map(|o| (o.player_id, o.purchase_price))
+        .collect();
+    let budget = team.budget_limit
+        + owned
+            .iter()
+            .filter(|o| !all_ids.contains(&o.player_id))
+            .map(|o| squad_rules::sale_profit(o.purchase_price, o.price))
+            .sum::<Decimal>();
+
+    let total_cost: Decimal =
+        sqlx::query_as::<_, (Uuid, Decimal)>("SELECT id, price FROM players WHERE id = ANY($1)")
+            .bind(&all_ids)
+            .fetch_all(&state.pool)
+            .await?
+            .into_iter()
+            .map(|(id, price)| purchase_prices.get(&id).copied().unwrap_or(price))
+            .sum();
+
+    if total_cost > budget {
         return Err(AppError::BadRequest(format!(
-            "Team cost ${total_cost} exceeds your ${} budget. Remove expensive players to fit the budget.",
-            team.budget_limit
+            "Team cost ${total_cost} exceeds your ${budget} budget. Remove expensive players to fit the budget."
         )));
     }
 
@@ -557,41 +665,49 @@ pub async fn set_team_players(
         .execute(&mut *tx)
         .await?;
 
-    // Insert starters with assigned positions
+    // Insert starters with assigned positions. Kept players keep their purchase
+    // price; new ones are bought at today's price.
     for assignment in &body.starters {
         sqlx::query(
-            "INSERT INTO team_players (team_id, player_id, is_bench, assigned_position) VALUES ($1, $2, false, $3)",
+            "INSERT INTO team_players (team_id, player_id, is_bench, assigned_position, purchase_price) VALUES ($1, $2, false, $3, $4)",
         )
         .bind(team_id)
         .bind(assignment.player_id)
         .bind(&assignment.assigned_position)
+        .bind(purchase_prices.get(&assignment.player_id).copied())
         .execute(&mut *tx)
         .await?;
     }
 
-    // Insert bench players (no assigned_position)
-    for player_id in &body.bench_player_ids {
+    // Insert bench players (no assigned_position), in the order given
+    for (order, player_id) in (1i16..).zip(&body.bench_player_ids) {
         sqlx::query(
-            "INSERT INTO team_players (team_id, player_id, is_bench) VALUES ($1, $2, true)",
+            "INSERT INTO team_players (team_id, player_id, is_bench, bench_order, purchase_price) VALUES ($1, $2, true, $3, $4)",
         )
         .bind(team_id)
         .bind(player_id)
+        .bind(order)
+        .bind(purchase_prices.get(player_id).copied())
         .execute(&mut *tx)
         .await?;
     }
 
-    // Update captain
-    sqlx::query("UPDATE fantasy_teams SET captain_id = $1 WHERE id = $2")
-        .bind(body.captain_id)
-        .bind(team_id)
-        .execute(&mut *tx)
-        .await?;
+    // Update captain, vice-captain and the budget after any sales
+    sqlx::query(
+        "UPDATE fantasy_teams SET captain_id = $1, vice_captain_id = $2, budget_limit = $3 WHERE id = $4",
+    )
+    .bind(body.captain_id)
+    .bind(body.vice_captain_id)
+    .bind(budget)
+    .bind(team_id)
+    .execute(&mut *tx)
+    .await?;
 
     tx.commit().await?;
 
     // Return updated team (re-fetch to get updated captain_id)
     let updated_team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1",
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1",
     )
     .bind(team_id)
     .fetch_one(&state.pool)
@@ -603,13 +719,14 @@ pub async fn set_team_players(
 
 /// GET /api/teams/:id/points
 ///
-/// Get a team's total points breakdown (only starters count for points).
+/// Get a team's total points breakdown (only starters count for points),
+/// plus any automatic substitutions made this season.
 pub async fn get_team_points(
     State(state): State<AppState>,
     Path(team_id): Path<Uuid>,
 ) -> AppResult<Json<serde_json::Value>> {
     let team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1",
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1",
     )
     .bind(team_id)
     .fetch_optional(&state.pool)
@@ -621,12 +738,35 @@ pub async fn get_team_points(
 
     let total = team_total_points(&state.pool, team_id).await?;
 
+    let season = resolve_season(&state.pool, None).await?;
+    let auto_subs = sqlx::query_as::<_, AutoSubstitution>(
+        r#"SELECT mw.week_number,
+                  tglp.player_id AS player_in_id,
+                  pin.name AS player_in_name,
+                  tglp.auto_sub_for AS player_out_id,
+                  pout.name AS player_out_name,
+                  tglp.assigned_position AS position
+           FROM team_gameweek_lineups tgl
+           INNER JOIN match_weeks mw ON mw.id = tgl.match_week_id
+           INNER JOIN team_gameweek_lineup_players tglp ON tglp.team_gameweek_lineup_id = tgl.id
+           INNER JOIN players pin ON pin.id = tglp.player_id
+           INNER JOIN players pout ON pout.id = tglp.auto_sub_for
+           WHERE tgl.team_id = $1 AND mw.season_id = $2 AND tglp.auto_sub_for IS NOT NULL
+           ORDER BY mw.week_number, pin.name"#,
+    )
+    .bind(team_id)
+    .bind(season.id)
+    .fetch_all(&state.pool)
+    .await?;
+
     Ok(Json(serde_json::json!({
         "team_id": team_id,
         "captain_id": team.captain_id,
+        "vice_captain_id": team.vice_captain_id,
         "total_points": total,
         "players": starters,
         "bench": bench,
+        "auto_subs": auto_subs,
     })))
 }
 
@@ -639,7 +779,7 @@ pub async fn get_transfer_status(
     Path(team_id): Path<Uuid>,
 ) -> AppResult<Json<TransferStatusResponse>> {
     let _team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
     )
     .bind(team_id)
     .bind(auth.user_id)
@@ -659,12 +799,20 @@ pub async fn get_transfer_status(
     .fetch_optional(&state.pool)
     .await?;
 
+    let mut conn = state.pool.acquire().await?;
+
     let Some(week) = active_week else {
+        let policy = transfer_policy::current_policy(&mut conn).await?;
         return Ok(Json(TransferStatusResponse {
             transfer_available: false,
             active_gameweek: None,
             transfers_used: 0,
-            free_transfers: 1,
+            free_transfers: policy
+                .free_transfers_per_week
+                .min(policy.max_banked_transfers),
+            max_banked_transfers: policy.max_banked_transfers,
+            transfer_hit_cost: policy.transfer_hit_cost,
+            unlimited_transfers: false,
             extra_transfers: 0,
             points_hit: 0,
             transferred_out: None,
@@ -672,16 +820,12 @@ pub async fn get_transfer_status(
         }));
     };
 
-    let transfers_used = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM transfers WHERE team_id = $1 AND match_week_id = $2",
-    )
-    .bind(team_id)
-    .bind(week.id)
-    .fetch_one(&state.pool)
-    .await?;
+    let allowance = transfer_policy::team_allowance(&mut conn, team_id, week.id).await?;
+    drop(conn);
 
     let latest_transfer = sqlx::query_as::<_, TransferRecord>(
-        "SELECT id, team_id, match_week_id, player_out_id, player_in_id, created_at
+        "SELECT id, team_id, match_week_id, player_out_id, player_in_id,
+                player_out_price, player_in_price, player_out_purchase_price, created_at
          FROM transfers
          WHERE team_id = $1 AND match_week_id = $2
          ORDER BY created_at DESC
@@ -706,16 +850,29 @@ pub async fn get_transfer_status(
         (None, None)
     };
 
-    let transfers_used_i32 = transfers_used as i32;
-    let free_transfers = 1;
-    let extra_transfers = (transfers_used_i32 - free_transfers).max(0);
-    let points_hit = extra_transfers * 4;
+    let unlimited_transfers = sqlx::query_scalar::<_, ChipType>(
+        "SELECT chip_type FROM team_chips WHERE team_id = $1 AND match_week_id = $2",
+    )
+    .bind(team_id)
+    .bind(week.id)
+    .fetch_optional(&state.pool)
+    .await?
+    .is_some_and(ChipType::waives_transfer_hits);
+
+    let (extra_transfers, points_hit) = if unlimited_transfers {
+        (0, 0)
+    } else {
+        (allowance.extra_transfers, allowance.points_hit)
+    };
 
     Ok(Json(TransferStatusResponse {
         transfer_available: true,
         active_gameweek: Some(week.week_number),
-        transfers_used: transfers_used_i32,
-        free_transfers,
+        transfers_used: allowance.transfers_used,
+        free_transfers: allowance.free_transfers,
+        max_banked_transfers: allowance.policy.max_banked_transfers,
+        transfer_hit_cost: allowance.policy.transfer_hit_cost,
+        unlimited_transfers,
         extra_transfers,
         points_hit,
         transferred_out,
@@ -726,7 +883,8 @@ pub async fn get_transfer_status(
 /// POST /api/teams/:id/transfer
 ///
 /// Transfer 1 player: swap player_out (must be in squad) for player_in (new player).
-/// First transfer each active gameweek is free; each additional transfer costs -4 points.
+/// Transfers within the team's banked free allowance are free; each one beyond it costs
+/// the season's hit cost, unless a Wildcard or Free Hit is active that week.
 pub async fn transfer_player(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthUser>,
@@ -735,13 +893,11 @@ pub async fn transfer_player(
 ) -> AppResult<Json<FantasyTeamWithPlayers>> {
     let lock = compute_lock_status(&state.pool).await?;
     if lock.locked {
-        return Err(AppError::BadRequest(
-            "Transfers are locked from Saturday 10:00 PM ET to Sunday 12:00 PM ET".to_string(),
-        ));
+        return Err(lock.locked_error("Transfers are locked"));
     }
 
     let team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2",
     )
     .bind(team_id)
     .bind(auth.user_id)
@@ -779,10 +935,12 @@ pub async fn transfer_player(
     struct TeamPlayerSlot {
         is_bench: bool,
         assigned_position: Option<PlayerPosition>,
+        bench_order: Option<i16>,
+        purchase_price: Decimal,
     }
 
     let outgoing_slot = sqlx::query_as::<_, TeamPlayerSlot>(
-        "SELECT is_bench, assigned_position FROM team_players WHERE team_id = $1 AND player_id = $2",
+        "SELECT is_bench, assigned_position, bench_order, purchase_price FROM team_players WHERE team_id = $1 AND player_id = $2",
     )
     .bind(team_id)
     .bind(body.player_out_id)
@@ -842,7 +1000,7 @@ pub async fn transfer_player(
         let matches_secondary = incoming
             .secondary_position
             .as_ref()
-            .map_or(false, |sp| *sp == *pos);
+            .is_some_and(|sp| *sp == *pos);
         if !matches_primary && !matches_secondary {
             return Err(AppError::BadRequest(format!(
                 "{} cannot play as {:?}. Valid positions: {:?}{}",
@@ -881,17 +1039,22 @@ pub async fn transfer_player(
         ));
     }
 
-    let total_cost = sqlx::query_scalar::<_, rust_decimal::Decimal>(
-        "SELECT COALESCE(SUM(price), 0) FROM players WHERE id = ANY($1)",
+    // The rest of the squad costs what the team paid for it; the outgoing player is
+    // sold at their selling price, so any profit or loss on them moves the budget.
+    let budget =
+        team.budget_limit + squad_rules::sale_profit(outgoing_slot.purchase_price, outgoing.price);
+    let kept_cost = sqlx::query_scalar::<_, Decimal>(
+        "SELECT COALESCE(SUM(purchase_price), 0) FROM team_players WHERE team_id = $1 AND player_id != $2",
     )
-    .bind(&all_ids)
+    .bind(team_id)
+    .bind(body.player_out_id)
     .fetch_one(&state.pool)
     .await?;
+    let total_cost = kept_cost + incoming.price;
 
-    if total_cost > team.budget_limit {
+    if total_cost > budget {
         return Err(AppError::BadRequest(format!(
-            "Transfer would push team cost to ${total_cost}, exceeding your ${} budget",
-            team.budget_limit
+            "Transfer would push team cost to ${total_cost}, exceeding your ${budget} budget"
         )));
     }
 
@@ -929,6 +1092,12 @@ pub async fn transfer_player(
         ));
     }
 
+    if team.vice_captain_id == Some(body.player_out_id) {
+        return Err(AppError::BadRequest(
+            "Cannot transfer out your vice-captain. Change your vice-captain first.".to_string(),
+        ));
+    }
+
     let mut tx = state.pool.begin().await?;
 
     sqlx::query("DELETE FROM team_players WHERE team_id = $1 AND player_id = $2")
@@ -939,10 +1108,11 @@ pub async fn transfer_player(
 
     if outgoing_slot.is_bench {
         sqlx::query(
-            "INSERT INTO team_players (team_id, player_id, is_bench) VALUES ($1, $2, true)",
+            "INSERT INTO team_players (team_id, player_id, is_bench, bench_order) VALUES ($1, $2, true, $3)",
         )
         .bind(team_id)
         .bind(body.player_in_id)
+        .bind(outgoing_slot.bench_order)
         .execute(&mut *tx)
         .await?;
     } else {
@@ -956,20 +1126,477 @@ pub async fn transfer_player(
         .await?;
     }
 
+    sqlx::query("UPDATE fantasy_teams SET budget_limit = $1 WHERE id = $2")
+        .bind(budget)
+        .bind(team_id)
+        .execute(&mut *tx)
+        .await?;
+
     sqlx::query(
-        "INSERT INTO transfers (team_id, match_week_id, player_out_id, player_in_id) VALUES ($1, $2, $3, $4)",
+        r#"INSERT INTO transfers
+             (team_id, match_week_id, player_out_id, player_in_id, player_out_price, player_in_price,
+              player_out_purchase_price)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
     )
     .bind(team_id)
     .bind(active_week.id)
     .bind(body.player_out_id)
     .bind(body.player_in_id)
+    .bind(outgoing.price)
+    .bind(incoming.price)
+    .bind(outgoing_slot.purchase_price)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let updated_team = sqlx::query_as::<_, FantasyTeam>(
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1",
+    )
+    .bind(team_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let response = build_team_response(&state.pool, &updated_team).await?;
+    Ok(Json(response))
+}
+
+/// POST /api/teams/:id/transfers
+///
+/// Make several transfers at once. Only the final squad is validated, against the
+/// same rules as `set_team_players`, and the whole batch is applied in one
+/// transaction. Hits are worked out once for the gameweek from the season's
+/// transfer policy, as for single transfers.
+pub async fn batch_transfer(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(team_id): Path<Uuid>,
+    Json(body): Json<BatchTransferRequest>,
+) -> AppResult<Json<BatchTransferResponse>> {
+    let lock = compute_lock_status(&state.pool).await?;
+    if lock.locked {
+        return Err(lock.locked_error("Transfers are locked"));
+    }
+
+    let active_week_id =
+        sqlx::query_scalar::<_, Uuid>("SELECT id FROM match_weeks WHERE is_active = true LIMIT 1")
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| {
+                AppError::BadRequest(
+                    "No active gameweek. Transfers are only available during a gameweek."
+                        .to_string(),
+                )
+            })?;
+
+    // Ownership check before the snapshot, so other teams' lineups are untouched.
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM fantasy_teams WHERE id = $1 AND user_id = $2")
+        .bind(team_id)
+        .bind(auth.user_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Team not found or access denied".to_string()))?;
+
+    snapshot_team_lineup_if_missing(&state.pool, team_id, active_week_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    // Lock the team row so concurrent transfers validate against the same squad.
+    let team = sqlx::query_as::<_, FantasyTeam>(
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 FOR UPDATE",
+    )
+    .bind(team_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let squad = sqlx::query_as::<_, SquadSlot>(
+        "SELECT player_id, is_bench, assigned_position, bench_order, purchase_price FROM team_players WHERE team_id = $1",
+    )
+    .bind(team_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let swaps: Vec<Swap> = body
+        .transfers
+        .iter()
+        .map(|t| Swap {
+            player_out_id: t.player_out_id,
+            player_in_id: t.player_in_id,
+            assigned_position: t.assigned_position.clone(),
+        })
+        .collect();
+    let positions: Vec<(Uuid, PlayerPosition)> = body
+        .assigned_positions
+        .iter()
+        .map(|a| (a.player_id, a.assigned_position.clone()))
+        .collect();
+    let final_squad =
+        squad_rules::apply_swaps(&squad, &swaps, &positions).map_err(AppError::BadRequest)?;
+
+    // Everyone in the squad before or after, so the outgoing players can be priced.
+    let player_ids: Vec<Uuid> = squad
+        .iter()
+        .chain(&final_squad)
+        .map(|s| s.player_id)
+        .collect();
+    let players: HashMap<Uuid, Player> = sqlx::query_as::<_, Player>(
+        r#"SELECT id, name, position, secondary_position, is_top_player,
+                  team_name, photo_url, price, total_points, created_at
+           FROM players WHERE id = ANY($1)"#,
+    )
+    .bind(&player_ids)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|p| (p.id, p))
+    .collect();
+
+    // Each outgoing player is sold at their selling price.
+    let sold: Vec<&SquadSlot> = squad
+        .iter()
+        .filter(|slot| swaps.iter().any(|s| s.player_out_id == slot.player_id))
+        .collect();
+    let mut budget = team.budget_limit;
+    for slot in &sold {
+        let (Some(purchase_price), Some(player)) =
+            (slot.purchase_price, players.get(&slot.player_id))
+        else {
+            continue;
+        };
+        budget += squad_rules::sale_profit(purchase_price, player.price);
+    }
+
+    squad_rules::validate_squad(&final_squad, &players, budget).map_err(AppError::BadRequest)?;
+
+    let transferred_out =
+        |id: Option<Uuid>| id.is_some_and(|id| swaps.iter().any(|s| s.player_out_id == id));
+    if body.captain_id.is_none() && transferred_out(team.captain_id) {
+        return Err(AppError::BadRequest(
+            "You are transferring out your captain. Choose a new captain.".to_string(),
+        ));
+    }
+    if body.vice_captain_id.is_none() && transferred_out(team.vice_captain_id) {
+        return Err(AppError::BadRequest(
+            "You are transferring out your vice-captain. Choose a new vice-captain.".to_string(),
+        ));
+    }
+    let captain_id = body.captain_id.or(team.captain_id);
+    let vice_captain_id = body.vice_captain_id.or(team.vice_captain_id);
+
+    let is_starter = |id: Uuid| final_squad.iter().any(|s| s.player_id == id && !s.is_bench);
+    if captain_id.is_some_and(|id| !is_starter(id)) {
+        return Err(AppError::BadRequest(
+            "Captain must be one of the 6 starting players".to_string(),
+        ));
+    }
+    if let Some(vice_captain_id) = vice_captain_id {
+        if Some(vice_captain_id) == captain_id {
+            return Err(AppError::BadRequest(
+                "Vice-captain must be a different player from the captain".to_string(),
+            ));
+        }
+        if !is_starter(vice_captain_id) {
+            return Err(AppError::BadRequest(
+                "Vice-captain must be one of the 6 starting players".to_string(),
+            ));
+        }
+    }
+
+    let user_full_name =
+        sqlx::query_scalar::<_, String>("SELECT full_name FROM users WHERE id = $1")
+            .bind(auth.user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+    for (id, role) in [(captain_id, "captain"), (vice_captain_id, "vice-captain")] {
+        if let Some(player) = id.and_then(|id| players.get(&id)) {
+            if player
+                .name
+                .trim()
+                .eq_ignore_ascii_case(user_full_name.trim())
+            {
+                return Err(AppError::BadRequest(format!(
+                    "You cannot {role} {} because they share your name. Choose a different {role}.",
+                    player.name
+                )));
+            }
+        }
+    }
+
+    sqlx::query("DELETE FROM team_players WHERE team_id = $1")
+        .bind(team_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for slot in &final_squad {
+        sqlx::query(
+            r#"INSERT INTO team_players
+                 (team_id, player_id, is_bench, assigned_position, bench_order, purchase_price)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+        )
+        .bind(team_id)
+        .bind(slot.player_id)
+        .bind(slot.is_bench)
+        .bind(&slot.assigned_position)
+        .bind(slot.bench_order)
+        .bind(slot.purchase_price)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "UPDATE fantasy_teams SET captain_id = $1, vice_captain_id = $2, budget_limit = $3 WHERE id = $4",
+    )
+    .bind(captain_id)
+    .bind(vice_captain_id)
+    .bind(budget)
+    .bind(team_id)
+    .execute(&mut *tx)
+    .await?;
+
+    for swap in &swaps {
+        let purchase_price = sold
+            .iter()
+            .find(|slot| slot.player_id == swap.player_out_id)
+            .and_then(|slot| slot.purchase_price);
+        sqlx::query(
+            r#"INSERT INTO transfers
+                 (team_id, match_week_id, player_out_id, player_in_id, player_out_price, player_in_price,
+                  player_out_purchase_price)
+               VALUES ($1, $2, $3, $4,
+                       (SELECT price FROM players WHERE id = $3),
+                       (SELECT price FROM players WHERE id = $4),
+                       $5)"#,
+        )
+        .bind(team_id)
+        .bind(active_week_id)
+        .bind(swap.player_out_id)
+        .bind(swap.player_in_id)
+        .bind(purchase_price)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let allowance = transfer_policy::team_allowance(&mut tx, team_id, active_week_id).await?;
+
+    tx.commit().await?;
+
+    let updated_team = sqlx::query_as::<_, FantasyTeam>(
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1",
+    )
+    .bind(team_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(BatchTransferResponse {
+        team: build_team_response(&state.pool, &updated_team).await?,
+        transfers_made: swaps.len() as i32,
+        transfers_used: allowance.transfers_used,
+        free_transfers: allowance.free_transfers,
+        extra_transfers: allowance.extra_transfers,
+        points_hit: allowance.points_hit,
+    }))
+}
+
+/// GET /api/teams/:id/transfers
+///
+/// A team's transfers, newest first, with player names and prices at the time.
+pub async fn list_transfers(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(team_id): Path<Uuid>,
+) -> AppResult<Json<Vec<TransferHistoryEntry>>> {
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM fantasy_teams WHERE id = $1 AND user_id = $2")
+        .bind(team_id)
+        .bind(auth.user_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Team not found or access denied".to_string()))?;
+
+    let transfers = sqlx::query_as::<_, TransferHistoryEntry>(
+        r#"SELECT t.id, t.match_week_id, mw.week_number,
+                  t.player_out_id, po.name AS player_out_name, t.player_out_price,
+                  t.player_in_id, pi.name AS player_in_name, t.player_in_price,
+                  t.created_at
+           FROM transfers t
+           INNER JOIN match_weeks mw ON mw.id = t.match_week_id
+           INNER JOIN players po ON po.id = t.player_out_id
+           INNER JOIN players pi ON pi.id = t.player_in_id
+           WHERE t.team_id = $1
+           ORDER BY t.created_at DESC"#,
+    )
+    .bind(team_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(transfers))
+}
+
+/// POST /api/teams/:id/transfers/undo
+///
+/// Reverse the team's most recent transfer in the active gameweek while lineups
+/// are unlocked. The outgoing player returns to the incoming player's slot at their
+/// original purchase price, and the incoming player is refunded in full, so price
+/// changes since the transfer do not count against the budget. Deleting the
+/// transfer frees the allowance it used, and any hit already scored for the week
+/// is recalculated. Not allowed once the week is finalised.
+pub async fn undo_last_transfer(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthUser>,
+    Path(team_id): Path<Uuid>,
+) -> AppResult<Json<FantasyTeamWithPlayers>> {
+    let lock = compute_lock_status(&state.pool).await?;
+    if lock.locked {
+        return Err(lock.locked_error("Transfers are locked"));
+    }
+
+    let (active_week_id, week_number, status) = sqlx::query_as::<_, (Uuid, i32, GameweekStatus)>(
+        "SELECT id, week_number, status FROM match_weeks WHERE is_active = true LIMIT 1",
+    )
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("No active gameweek".to_string()))?;
+    // Undoing changes the week's transfer hits, which are settled once it is final.
+    if status == GameweekStatus::Finalised {
+        return Err(AppError::Conflict(format!(
+            "Gameweek {week_number} is finalised, so its transfers can no longer be undone"
+        )));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let team = sqlx::query_as::<_, FantasyTeam>(
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1 AND user_id = $2 FOR UPDATE",
+    )
+    .bind(team_id)
+    .bind(auth.user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Team not found or access denied".to_string()))?;
+
+    let transfer = sqlx::query_as::<_, TransferRecord>(
+        "SELECT id, team_id, match_week_id, player_out_id, player_in_id,
+                player_out_price, player_in_price, player_out_purchase_price, created_at
+         FROM transfers
+         WHERE team_id = $1 AND match_week_id = $2
+         ORDER BY created_at DESC
+         LIMIT 1",
+    )
+    .bind(team_id)
+    .bind(active_week_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("No transfers to undo this gameweek".to_string()))?;
+
+    if [team.captain_id, team.vice_captain_id].contains(&Some(transfer.player_in_id)) {
+        return Err(AppError::BadRequest(
+            "The player you transferred in is your captain or vice-captain. Change the armband first."
+                .to_string(),
+        ));
+    }
+
+    let squad = sqlx::query_as::<_, SquadSlot>(
+        "SELECT player_id, is_bench, assigned_position, bench_order, purchase_price FROM team_players WHERE team_id = $1",
+    )
+    .bind(team_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let players: HashMap<Uuid, Player> = sqlx::query_as::<_, Player>(
+        r#"SELECT id, name, position, secondary_position, is_top_player,
+                  team_name, photo_url, price, total_points, created_at
+           FROM players WHERE id = ANY($1) OR id = $2"#,
+    )
+    .bind(squad.iter().map(|s| s.player_id).collect::<Vec<_>>())
+    .bind(transfer.player_out_id)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|p| (p.id, p))
+    .collect();
+
+    let restored = players
+        .get(&transfer.player_out_id)
+        .ok_or_else(|| AppError::BadRequest("Player to restore not found".to_string()))?;
+
+    // Keep the slot's role if the restored player can play it, else their own position.
+    let swap = Swap {
+        player_out_id: transfer.player_in_id,
+        player_in_id: transfer.player_out_id,
+        assigned_position: squad
+            .iter()
+            .find(|s| s.player_id == transfer.player_in_id)
+            .and_then(|s| s.assigned_position.clone())
+            .filter(|role| {
+                restored.position == *role || restored.secondary_position.as_ref() == Some(role)
+            })
+            .or_else(|| Some(restored.position.clone())),
+    };
+    let restored_squad = squad_rules::apply_swaps(&squad, std::slice::from_ref(&swap), &[])
+        .map_err(|e| AppError::BadRequest(format!("Cannot undo this transfer: {e}")))?;
+    squad_rules::validate_squad(&restored_squad, &players, Decimal::MAX)
+        .map_err(|e| AppError::BadRequest(format!("Cannot undo this transfer: {e}")))?;
+
+    let slot = restored_squad
+        .iter()
+        .find(|s| s.player_id == transfer.player_out_id)
+        .ok_or_else(|| AppError::Internal("Restored player missing from squad".to_string()))?;
+
+    // The incoming player is refunded at what was paid for them, and the restored
+    // player goes back at their original purchase price, reversing the sale.
+    sqlx::query(
+        r#"UPDATE team_players
+           SET player_id = $3, assigned_position = $4,
+               purchase_price = COALESCE($5, (SELECT price FROM players WHERE id = $3))
+           WHERE team_id = $1 AND player_id = $2"#,
+    )
+    .bind(team_id)
+    .bind(transfer.player_in_id)
+    .bind(transfer.player_out_id)
+    .bind(&slot.assigned_position)
+    .bind(
+        transfer
+            .player_out_purchase_price
+            .or(transfer.player_out_price),
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if let (Some(purchase_price), Some(sold_at)) = (
+        transfer.player_out_purchase_price,
+        transfer.player_out_price,
+    ) {
+        sqlx::query("UPDATE fantasy_teams SET budget_limit = budget_limit - $1 WHERE id = $2")
+            .bind(squad_rules::sale_profit(purchase_price, sold_at))
+            .bind(team_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    sqlx::query("DELETE FROM transfers WHERE id = $1")
+        .bind(transfer.id)
+        .execute(&mut *tx)
+        .await?;
+
+    let allowance = transfer_policy::team_allowance(&mut tx, team_id, active_week_id).await?;
+    sqlx::query(
+        r#"UPDATE team_gameweek_points
+           SET transfer_points_hit = $3,
+               total_points = gross_points - $3,
+               updated_at = NOW()
+           WHERE team_id = $1 AND match_week_id = $2"#,
+    )
+    .bind(team_id)
+    .bind(active_week_id)
+    .bind(allowance.points_hit)
     .execute(&mut *tx)
     .await?;
 
     tx.commit().await?;
+    // The refunded hit changes this week's live totals.
+    publish_week(&state, week_number).await;
 
     let updated_team = sqlx::query_as::<_, FantasyTeam>(
-        "SELECT id, user_id, name, captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1",
+        "SELECT id, user_id, name, captain_id, vice_captain_id, budget_limit, created_at FROM fantasy_teams WHERE id = $1",
     )
     .bind(team_id)
     .fetch_one(&state.pool)
@@ -56,9 +56,12 @@ async fn main() {
         tracing::warn!("Failed to seed match weeks: {e}");
     }
 
+    // Subscribers that fall this far behind reload the week from the database.
+    let (live, _) = tokio::sync::broadcast::channel(16);
     let state = AppState {
         pool,
         jwt_secret: config.jwt_secret.clone(),
+        live,
     };
 
     // CORS layer
@@ -77,12 +80,30 @@ async fn main() {
     let player_routes = Router::new()
         .route("/", get(handlers::players::list_players))
         .route("/leaderboard", get(handlers::players::leaderboard))
-        .route("/:id", get(handlers::players::get_player));
+        .route("/price-changes", get(handlers::players::price_changes))
+        .route("/:id", get(handlers::players::get_player))
+        .route("/:id/price-history", get(handlers::players::price_history));
+
+    // Match routes (public)
+    let match_routes = Router::new()
+        .route("/", get(handlers::matches::list_matches))
+        .route("/:id", get(handlers::matches::get_match))
+        .route("/:id/events", get(handlers::matches::list_match_events));
 
     // Points routes (public)
     let points_routes = Router::new()
         .route("/week/:week", get(handlers::points::get_week_points))
-        .route("/player/:id", get(handlers::points::get_player_points));
+        .route("/player/:id", get(handlers::points::get_player_points))
+        .route("/live/:week", get(handlers::points::live_week_points));
+
+    // Season routes (public)
+    let season_routes = Router::new()
+        .route("/", get(handlers::seasons::list_seasons))
+        .route("/current", get(handlers::seasons::get_current_season))
+        .route(
+            "/:id/standings",
+            get(handlers::seasons::get_season_standings),
+        );
 
     // Team routes (mixed: lock-status is public, rest protected)
     let team_public_routes = Router::new().route("/lock-status", get(handlers::teams::lock_status));
@@ -96,6 +117,14 @@ async fn main() {
             "/:id/transfer",
             get(handlers::teams::get_transfer_status).post(handlers::teams::transfer_player),
         )
+        .route(
+            "/:id/transfers",
+            get(handlers::teams::list_transfers).post(handlers::teams::batch_transfer),
+        )
+        .route(
+            "/:id/transfers/undo",
+            post(handlers::teams::undo_last_transfer),
+        )
         .route(
             "/:id/chips",
             get(handlers::chips::get_chip_status).post(handlers::chips::activate_chip),
@@ -113,17 +142,54 @@ async fn main() {
 
     // League routes (mixed: some public, some protected)
     let league_public_routes = Router::new()
+        .route("/public", get(handlers::leagues::list_public_leagues))
         .route("/:id", get(handlers::leagues::get_league))
         .route("/:id/leaderboard", get(handlers::leagues::get_leaderboard))
         .route(
             "/:id/gameweek/:week",
             get(handlers::leagues::get_league_gameweek),
-        );
+        )
+        .route("/:id/h2h/fixtures", get(handlers::leagues::get_fixtures))
+        .route(
+            "/:id/h2h/standings",
+            get(handlers::leagues::get_h2h_standings),
+        )
+        .route("/:id/cup", get(handlers::leagues::get_cup));
 
     let league_protected_routes = Router::new()
         .route("/", post(handlers::leagues::create_league))
         .route("/join", post(handlers::leagues::join_league))
         .route("/my", get(handlers::leagues::get_my_leagues))
+        .route(
+            "/:id",
+            put(handlers::leagues::rename_league).delete(handlers::leagues::delete_league),
+        )
+        .route("/:id/leave", post(handlers::leagues::leave_league))
+        .route("/:id/join", post(handlers::leagues::join_public_league))
+        .route("/:id/access", put(handlers::leagues::update_league_access))
+        .route("/:id/requests", get(handlers::leagues::list_join_requests))
+        .route(
+            "/:id/requests/:user_id",
+            delete(handlers::leagues::reject_join_request),
+        )
+        .route(
+            "/:id/requests/:user_id/approve",
+            post(handlers::leagues::approve_join_request),
+        )
+        .route(
+            "/:id/invite-code",
+            post(handlers::leagues::regenerate_invite_code),
+        )
+        .route("/:id/owner", put(handlers::leagues::transfer_ownership))
+        .route(
+            "/:league_id/members/:user_id",
+            delete(handlers::leagues::remove_member),
+        )
+        .route(
+            "/:id/h2h/fixtures",
+            post(handlers::leagues::generate_fixtures),
+        )
+        .route("/:id/cup", post(handlers::leagues::create_cup))
         .route(
             "/:league_id/members/:user_id/lineup",
             get(handlers::leagues::get_member_lineup),
@@ -140,7 +206,10 @@ async fn main() {
         .route("/sessions", post(handlers::accounting::create_session))
         .route("/sessions", get(handlers::accounting::list_sessions))
         .route("/sessions/:id", get(handlers::accounting::get_session))
-        .route("/sessions/:id", delete(handlers::accounting::delete_session))
+        .route(
+            "/sessions/:id",
+            delete(handlers::accounting::delete_session),
+        )
         .route(
             "/sessions/:id/players",
             post(handlers::accounting::add_player),
@@ -185,11 +254,66 @@ async fn main() {
             "/gameweek/:week/stats",
             post(handlers::admin::submit_week_stats),
         )
+        .route(
+            "/gameweek/:week/stats/import",
+            post(handlers::admin::import_week_stats),
+        )
+        .route(
+            "/gameweek/:week/deadline",
+            put(handlers::admin::set_gameweek_deadline),
+        )
+        .route(
+            "/gameweek/:week/finalise",
+            post(handlers::admin::finalise_gameweek),
+        )
+        .route(
+            "/gameweek/:week/reopen",
+            post(handlers::admin::reopen_gameweek),
+        )
+        .route(
+            "/gameweek/:week/stats/from-events",
+            post(handlers::admin::score_week_from_events),
+        )
+        .route(
+            "/gameweek/:week/stats/preview",
+            post(handlers::admin::preview_week_stats),
+        )
         .route(
             "/lineup-lock",
             get(handlers::admin::get_lineup_lock_control)
                 .put(handlers::admin::set_lineup_lock_control),
         )
+        .route(
+            "/lineup-lock/schedule",
+            put(handlers::admin::set_lock_schedule),
+        )
+        .route("/season/close", post(handlers::seasons::close_season))
+        .route(
+            "/season/settings",
+            put(handlers::seasons::update_season_settings),
+        )
+        .route("/audit", get(handlers::audit::list_audit_events))
+        .route(
+            "/chips/:chip_type",
+            put(handlers::chips::update_chip_definition),
+        )
+        .route(
+            "/scoring-rules",
+            get(handlers::admin::list_scoring_rules).post(handlers::admin::create_scoring_rules),
+        )
+        .route("/matches", post(handlers::matches::create_match))
+        .route(
+            "/matches/:id",
+            put(handlers::matches::update_match).delete(handlers::matches::delete_match),
+        )
+        .route(
+            "/matches/:id/events",
+            post(handlers::matches::create_match_event),
+        )
+        .route(
+            "/matches/:id/events/:event_id",
+            delete(handlers::matches::delete_match_event),
+        )
         .layer(middleware::from_fn(auth::admin::admin_middleware))
         .layer(middleware::from_fn(auth::middleware::auth_middleware))
         .layer(Extension(state.pool.clone()))
@@ -200,7 +324,9 @@ async fn main() {
         .route("/healthz", get(health_check))
         .nest("/api/auth", auth_routes)
         .nest("/api/players", player_routes)
+        .nest("/api/matches", match_routes)
         .nest("/api/points", points_routes)
+        .nest("/api/seasons", season_routes)
         .nest("/api/teams", team_routes)
         .nest("/api/leagues", league_routes)
         .nest("/api/accounting", accounting_routes)
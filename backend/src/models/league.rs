@@ -4,24 +4,82 @@ use uuid::Uuid;
 
 use super::StarterPlayer;
 
+/// How a league ranks its members, stored as text in `leagues.league_type`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LeagueType {
+    /// Ranked by total points.
+    #[default]
+    Classic,
+    /// Members play one another each gameweek; 3 points for a win, 1 for a draw.
+    HeadToHead,
+}
+
+/// Whether a league is listed in the public directory.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LeagueVisibility {
+    /// Listed, and joinable without the invite code.
+    Public,
+    /// Joinable only with the invite code.
+    #[default]
+    Private,
+}
+
 /// Database row for a league.
 #[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct League {
     pub id: Uuid,
     pub name: String,
     pub invite_code: String,
+    /// The league's owner: its creator until ownership is handed over.
     pub created_by: Uuid,
     pub created_at: DateTime<Utc>,
+    pub league_type: LeagueType,
+    pub visibility: LeagueVisibility,
+    /// No limit when `None`.
+    pub max_members: Option<i32>,
+    /// Whether joiners wait in a queue until the owner accepts them.
+    pub requires_approval: bool,
 }
 
-/// A league member with user info and points.
-#[derive(Debug, Serialize, sqlx::FromRow)]
+/// A league member with user info, points and place in the table.
+#[derive(Debug, Serialize)]
 pub struct LeagueMemberStanding {
+    pub rank: i32,
+    /// Rank before the latest scored gameweek; `None` until two have been scored.
+    pub previous_rank: Option<i32>,
+    /// Places gained since the previous gameweek; negative for places lost.
+    pub rank_change: Option<i32>,
     pub user_id: Uuid,
     pub username: String,
     pub full_name: String,
     pub team_name: Option<String>,
     pub total_points: Option<i64>,
+    pub transfer_hits: i64,
+    pub best_gameweek_points: i64,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Query for a page of a league's table.
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardQuery {
+    pub season_id: Option<Uuid>,
+    /// `next_cursor` from the previous page.
+    pub cursor: Option<Uuid>,
+    /// Rows per page; defaults to 50, at most 200.
+    pub limit: Option<i64>,
+}
+
+/// One page of a league's table.
+#[derive(Debug, Serialize)]
+pub struct LeaderboardPage {
+    pub standings: Vec<LeagueMemberStanding>,
+    /// Pass back as `cursor` for the next page; `None` on the last one.
+    pub next_cursor: Option<Uuid>,
+    pub total_members: i64,
 }
 
 /// Response for viewing a league member's starting lineup.
@@ -31,6 +89,7 @@ pub struct MemberLineupResponse {
     pub username: String,
     pub team_name: String,
     pub captain_id: Option<Uuid>,
+    pub vice_captain_id: Option<Uuid>,
     pub starters: Vec<StarterPlayer>,
 }
 
@@ -55,6 +114,70 @@ pub struct MyLeague {
 #[derive(Debug, Deserialize)]
 pub struct CreateLeagueRequest {
     pub name: String,
+    #[serde(default)]
+    pub league_type: LeagueType,
+    #[serde(default)]
+    pub visibility: LeagueVisibility,
+    pub max_members: Option<i32>,
+    #[serde(default)]
+    pub requires_approval: bool,
+}
+
+/// Request to change who can join a league. Omitted fields are left as they
+/// are; a `max_members` of 0 removes the cap.
+#[derive(Debug, Deserialize)]
+pub struct UpdateLeagueAccessRequest {
+    pub visibility: Option<LeagueVisibility>,
+    pub max_members: Option<i32>,
+    pub requires_approval: Option<bool>,
+}
+
+/// Whether a join went through or is waiting for the owner.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinStatus {
+    Joined,
+    Pending,
+}
+
+/// Result of asking to join a league.
+#[derive(Debug, Serialize)]
+pub struct JoinLeagueResponse {
+    pub league: League,
+    pub status: JoinStatus,
+}
+
+/// Someone waiting to be let into a league.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct LeagueJoinRequest {
+    pub user_id: Uuid,
+    pub username: String,
+    pub full_name: String,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// A league in the public directory.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PublicLeague {
+    pub id: Uuid,
+    pub name: String,
+    pub league_type: LeagueType,
+    pub member_count: i64,
+    pub max_members: Option<i32>,
+    pub requires_approval: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to rename a league.
+#[derive(Debug, Deserialize)]
+pub struct RenameLeagueRequest {
+    pub name: String,
+}
+
+/// Request to hand a league over to another member.
+#[derive(Debug, Deserialize)]
+pub struct TransferOwnershipRequest {
+    pub user_id: Uuid,
 }
 
 /// Request to join a league.
@@ -81,3 +204,114 @@ pub struct LeagueGameweekDetail {
     pub week_number: i32,
     pub members: Vec<LeagueGameweekStanding>,
 }
+
+/// Request to generate a head-to-head league's fixtures.
+///
+/// Unplayed fixtures from `start_week` on are replaced.
+#[derive(Debug, Deserialize)]
+pub struct GenerateFixturesRequest {
+    /// Defaults to the gameweek after the last one activated or scored.
+    pub start_week: Option<i32>,
+    /// Defaults to one full round-robin.
+    pub rounds: Option<i32>,
+}
+
+/// Query for a league's head-to-head fixtures.
+#[derive(Debug, Deserialize)]
+pub struct FixturesQuery {
+    pub week: Option<i32>,
+    pub season_id: Option<Uuid>,
+}
+
+/// A head-to-head pairing. `away_team_id` is `None` for a bye; the points are
+/// `None` until the gameweek is scored.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct H2hFixture {
+    pub id: Uuid,
+    pub week_number: i32,
+    pub home_team_id: Uuid,
+    pub home_team_name: String,
+    pub away_team_id: Option<Uuid>,
+    pub away_team_name: Option<String>,
+    pub home_points: Option<i32>,
+    pub away_points: Option<i32>,
+}
+
+/// A team's line in a head-to-head table.
+#[derive(Debug, Serialize)]
+pub struct H2hStanding {
+    pub rank: i32,
+    pub team_id: Uuid,
+    pub team_name: String,
+    pub played: i32,
+    pub won: i32,
+    pub drawn: i32,
+    pub lost: i32,
+    pub points_for: i64,
+    pub points_against: i64,
+    pub points: i32,
+}
+
+/// Request to attach a knockout cup to a league for the open season.
+#[derive(Debug, Deserialize)]
+pub struct CreateCupRequest {
+    /// Gameweek of the first round; seeds come from total points before it.
+    pub start_week: i32,
+}
+
+/// What settled a cup tie.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CupDecider {
+    Points,
+    CaptainPoints,
+    Goals,
+    /// Level on everything, so the higher seed went through.
+    Seed,
+    /// No opponent in the first round.
+    Bye,
+}
+
+/// One tie in a cup round. The higher seed is listed as home. Team slots are
+/// `None` while waiting on an earlier round, or for a bye's missing opponent.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CupMatch {
+    pub home_team_id: Option<Uuid>,
+    pub home_seed: Option<i32>,
+    pub away_team_id: Option<Uuid>,
+    pub away_seed: Option<i32>,
+    pub home_points: Option<i32>,
+    pub away_points: Option<i32>,
+    pub winner_team_id: Option<Uuid>,
+    pub decided_by: Option<CupDecider>,
+}
+
+/// A cup round, played in a single gameweek.
+#[derive(Debug, Clone, Serialize)]
+pub struct CupRound {
+    pub round: i32,
+    pub week_number: i32,
+    /// In bracket order: the winners of matches 2k and 2k+1 meet next round.
+    pub matches: Vec<CupMatch>,
+}
+
+/// A team entered in a cup.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CupEntrant {
+    pub team_id: Uuid,
+    pub team_name: String,
+    pub seed: i32,
+    /// Season points before the cup's first round, which decide the seeding.
+    pub seeding_points: i64,
+}
+
+/// A league's knockout cup, ready to be drawn as a bracket.
+#[derive(Debug, Serialize)]
+pub struct CupBracket {
+    pub league_id: Uuid,
+    pub season_id: Uuid,
+    pub start_week: i32,
+    pub entrants: Vec<CupEntrant>,
+    pub rounds: Vec<CupRound>,
+    pub winner_team_id: Option<Uuid>,
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Which side of a match a player was on, stored as text in `match_lineups.side`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MatchSide {
+    Home,
+    Away,
+}
+
+/// Database row for a pickup match.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Match {
+    pub id: Uuid,
+    pub match_week_id: Uuid,
+    pub week_number: i32,
+    pub home_name: String,
+    pub away_name: String,
+    pub kickoff_at: DateTime<Utc>,
+    /// Length of fantasy time from kickoff; nothing after it counts.
+    pub fantasy_minutes: i32,
+    /// Goals inside fantasy time.
+    pub home_goals: i32,
+    pub away_goals: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Match {
+    /// When fantasy time runs out.
+    pub fn fantasy_time_end(&self) -> DateTime<Utc> {
+        self.kickoff_at + Duration::minutes(self.fantasy_minutes as i64)
+    }
+}
+
+/// A player's place in a match lineup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchLineupEntry {
+    pub player_id: Uuid,
+    pub side: MatchSide,
+    /// Left out when the player was on for the whole of fantasy time.
+    #[serde(default)]
+    pub minutes_played: Option<i32>,
+}
+
+/// A lineup entry with the player's name, for display.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct MatchLineupPlayer {
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub side: MatchSide,
+    pub minutes_played: Option<i32>,
+}
+
+/// A match with both lineups.
+#[derive(Debug, Serialize)]
+pub struct MatchDetail {
+    #[serde(flatten)]
+    pub r#match: Match,
+    pub fantasy_time_end: DateTime<Utc>,
+    pub lineups: Vec<MatchLineupPlayer>,
+}
+
+/// Request to create a match, or to replace one on update.
+#[derive(Debug, Deserialize)]
+pub struct MatchRequest {
+    pub week_number: i32,
+    pub home_name: Option<String>,
+    pub away_name: Option<String>,
+    pub kickoff_at: DateTime<Utc>,
+    /// Defaults to the hour the rules allow.
+    pub fantasy_minutes: Option<i32>,
+    #[serde(default)]
+    pub home_goals: i32,
+    #[serde(default)]
+    pub away_goals: i32,
+    pub lineups: Vec<MatchLineupEntry>,
+}
+
+/// Query for listing matches.
+#[derive(Debug, Deserialize)]
+pub struct MatchesQuery {
+    pub week: Option<i32>,
+}
+
+/// What happened, stored as text in `match_events.event_type`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MatchEventType {
+    Goal,
+    OwnGoal,
+    Save,
+    PenaltySave,
+    PenaltyMiss,
+    Foul,
+}
+
+/// How bad a foul was, stored as text in `match_events.foul_severity`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum FoulSeverity {
+    /// Dragging, handball.
+    Regular,
+    /// Causes a serious injury.
+    Serious,
+}
+
+/// Database row for a logged match event.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct MatchEvent {
+    pub id: Uuid,
+    pub match_id: Uuid,
+    pub minute: i32,
+    pub event_type: MatchEventType,
+    pub player_id: Uuid,
+    pub assist_player_id: Option<Uuid>,
+    pub foul_severity: Option<FoulSeverity>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An event in a match timeline, with names and whether it falls in fantasy time.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct MatchEventDetail {
+    pub id: Uuid,
+    pub minute: i32,
+    pub event_type: MatchEventType,
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub side: MatchSide,
+    pub assist_player_id: Option<Uuid>,
+    pub assist_player_name: Option<String>,
+    pub foul_severity: Option<FoulSeverity>,
+    /// False for events after fantasy time, which are kept but score nothing.
+    pub counts: bool,
+}
+
+/// Request to log a match event.
+#[derive(Debug, Deserialize)]
+pub struct MatchEventRequest {
+    pub minute: i32,
+    pub event_type: MatchEventType,
+    pub player_id: Uuid,
+    pub assist_player_id: Option<Uuid>,
+    pub foul_severity: Option<FoulSeverity>,
+}
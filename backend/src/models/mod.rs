@@ -1,11 +1,17 @@
+pub mod audit;
 pub mod league;
+pub mod matches;
 pub mod player;
 pub mod points;
+pub mod season;
 pub mod team;
 pub mod user;
 
+pub use audit::*;
 pub use league::*;
+pub use matches::*;
 pub use player::*;
 pub use points::*;
+pub use season::*;
 pub use team::*;
 pub use user::*;
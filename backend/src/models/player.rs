@@ -58,4 +58,63 @@ pub struct PlayerLeaderboard {
 pub struct PlayerQuery {
     pub position: Option<String>,
     pub search: Option<String>,
+    pub season_id: Option<Uuid>,
+}
+
+/// Why a player's price moved.
+#[derive(Debug, Clone, Copy, Serialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PriceChangeReason {
+    /// The weekly adjustment after a gameweek's stats.
+    Gameweek,
+    /// Repricing when the season closed after this week.
+    SeasonRollover,
+}
+
+/// A player's price after one gameweek or season rollover.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PricePoint {
+    pub week_number: i32,
+    pub reason: PriceChangeReason,
+    pub delta: Decimal,
+    /// Filled in when the history is rebuilt from the current price.
+    #[sqlx(default)]
+    pub price: Decimal,
+}
+
+/// Chart data for one player's price over time.
+#[derive(Debug, Serialize)]
+pub struct PlayerPriceHistory {
+    pub player_id: Uuid,
+    pub player_name: String,
+    /// The price before the first recorded change.
+    pub starting_price: Decimal,
+    pub current_price: Decimal,
+    pub history: Vec<PricePoint>,
+}
+
+/// Query parameters for a gameweek's price changes.
+#[derive(Debug, Deserialize)]
+pub struct PriceChangesQuery {
+    pub week: i32,
+}
+
+/// A player whose price moved in a gameweek.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct WeekPriceChange {
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub team_name: String,
+    pub delta: Decimal,
+    pub price_before: Decimal,
+    pub price_after: Decimal,
+}
+
+/// Risers and fallers for one gameweek.
+#[derive(Debug, Serialize)]
+pub struct WeekPriceChangesResponse {
+    pub week_number: i32,
+    pub risers: Vec<WeekPriceChange>,
+    pub fallers: Vec<WeekPriceChange>,
 }
@@ -1,7 +1,21 @@
 use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Where a gameweek is in its life, stored as text in `match_weeks.status`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum GameweekStatus {
+    Upcoming,
+    Live,
+    /// Stats are in; points can still change.
+    Provisional,
+    /// Stats are confirmed and prices and league results committed.
+    Finalised,
+}
+
 /// Database row for a match week.
 #[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct MatchWeek {
@@ -10,27 +24,27 @@ pub struct MatchWeek {
     pub start_date: NaiveDate,
     pub end_date: NaiveDate,
     pub is_active: bool,
+    pub season_id: Uuid,
+    pub status: GameweekStatus,
+    pub finalised_at: Option<DateTime<Utc>>,
+    /// The week's own lineup lock; `None` follows the weekly schedule.
+    pub deadline_at: Option<DateTime<Utc>>,
+    pub unlock_at: Option<DateTime<Utc>>,
 }
 
-/// Database row for player points in a match week.
-#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
-pub struct PlayerPoints {
-    pub id: Uuid,
-    pub player_id: Uuid,
-    pub match_week_id: Uuid,
-    pub goals: i32,
-    pub assists: i32,
-    pub clean_sheets: i32,
-    pub saves: i32,
-    pub tackles: i32,
-    pub total_points: i32,
-    pub own_goals: i32,
-    pub penalty_misses: i32,
-    pub penalty_saves: i32,
-    pub regular_fouls: i32,
-    pub serious_fouls: i32,
-    pub minutes_played: i32,
-    pub created_at: DateTime<Utc>,
+/// Request to set a gameweek's own lineup lock, or with neither time to go
+/// back to the weekly schedule.
+#[derive(Debug, Deserialize)]
+pub struct GameweekDeadlineRequest {
+    pub deadline_at: Option<DateTime<Utc>>,
+    pub unlock_at: Option<DateTime<Utc>>,
+}
+
+/// Request to reopen a finalised gameweek.
+#[derive(Debug, Deserialize)]
+pub struct ReopenGameweekRequest {
+    /// Why the confirmed stats need changing; kept in the audit log.
+    pub reason: String,
 }
 
 /// Player points with player name for display.
@@ -49,6 +63,32 @@ pub struct PlayerPointsDisplay {
     pub week_number: i32,
 }
 
+/// A fantasy team's total for a gameweek.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct LiveTeamPoints {
+    pub team_id: Uuid,
+    pub team_name: String,
+    pub user_id: Uuid,
+    pub total_points: i32,
+}
+
+/// Everything a gameweek's scoring changed, as pushed to live subscribers.
+#[derive(Debug, Serialize)]
+pub struct LiveWeekPoints {
+    pub week_number: i32,
+    /// Points are provisional until the week is finalised.
+    pub status: GameweekStatus,
+    pub players: Vec<PlayerPointsDisplay>,
+    pub teams: Vec<LiveTeamPoints>,
+}
+
+/// Query for the live points stream.
+#[derive(Debug, Deserialize)]
+pub struct LivePointsQuery {
+    /// Also push this league's gameweek standings with each update.
+    pub league_id: Option<Uuid>,
+}
+
 /// Admin view of player stats for a gameweek.
 #[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct AdminPlayerStats {
@@ -69,7 +109,7 @@ pub struct AdminPlayerStats {
 }
 
 /// Request body for submitting a single player's stats.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct PlayerStatInput {
     pub player_id: Uuid,
     pub goals: i32,
@@ -90,4 +130,146 @@ pub struct CreateGameweekRequest {
     pub week_number: i32,
     pub start_date: NaiveDate,
     pub end_date: NaiveDate,
+    /// Optional lineup lock for weeks played off the weekly schedule.
+    pub deadline_at: Option<DateTime<Utc>>,
+    pub unlock_at: Option<DateTime<Utc>>,
+}
+
+/// Database row for a versioned scoring rule set.
+///
+/// Applies to its season, and to later seasons until one gets its own rule set.
+/// Deductions are stored signed, so `own_goal` is negative.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ScoringRules {
+    pub id: Uuid,
+    pub name: String,
+    pub season_id: Uuid,
+    pub goal_gk: i32,
+    pub goal_def: i32,
+    pub goal_mid: i32,
+    pub goal_fwd: i32,
+    pub assist: i32,
+    pub clean_sheet_gk: i32,
+    pub clean_sheet_def: i32,
+    pub clean_sheet_mid: i32,
+    pub clean_sheet_fwd: i32,
+    pub saves_per_point: i32,
+    pub penalty_save: i32,
+    pub long_appearance_minutes: i32,
+    pub long_appearance: i32,
+    pub short_appearance: i32,
+    pub own_goal: i32,
+    pub penalty_miss: i32,
+    pub regular_foul: i32,
+    pub serious_foul: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request body for adding a new scoring rule set version.
+#[derive(Debug, Deserialize)]
+pub struct CreateScoringRulesRequest {
+    pub name: String,
+    /// Defaults to the open season.
+    pub season_id: Option<Uuid>,
+    pub goal_gk: i32,
+    pub goal_def: i32,
+    pub goal_mid: i32,
+    pub goal_fwd: i32,
+    pub assist: i32,
+    pub clean_sheet_gk: i32,
+    pub clean_sheet_def: i32,
+    pub clean_sheet_mid: i32,
+    pub clean_sheet_fwd: i32,
+    pub saves_per_point: i32,
+    pub penalty_save: i32,
+    pub long_appearance_minutes: i32,
+    pub long_appearance: i32,
+    pub short_appearance: i32,
+    pub own_goal: i32,
+    pub penalty_miss: i32,
+    pub regular_foul: i32,
+    pub serious_foul: i32,
+}
+
+/// Query string for the CSV stats import.
+#[derive(Debug, Deserialize)]
+pub struct StatsImportQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A CSV line that could not be imported.
+#[derive(Debug, Serialize)]
+pub struct StatsImportError {
+    pub line: u64,
+    pub message: String,
+}
+
+/// A CSV line that was matched to a player, with the points it scores.
+#[derive(Debug, Serialize)]
+pub struct StatsImportRow {
+    pub line: u64,
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub total_points: i32,
+}
+
+/// Result of a CSV stats import. Nothing is written unless `committed` is true,
+/// which requires an error-free file and `dry_run` off.
+#[derive(Debug, Serialize)]
+pub struct StatsImportResponse {
+    pub week: i32,
+    pub dry_run: bool,
+    pub committed: bool,
+    pub rows: Vec<StatsImportRow>,
+    pub errors: Vec<StatsImportError>,
+    pub teams_scored: i64,
+}
+
+/// A submitted player's points for the week, before and after the preview.
+#[derive(Debug, Serialize)]
+pub struct PlayerPointsChange {
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub points_before: Option<i32>,
+    pub points_after: i32,
+}
+
+/// A price the submission would move.
+#[derive(Debug, Serialize)]
+pub struct PlayerPriceChange {
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub price_before: Decimal,
+    pub price_after: Decimal,
+}
+
+/// A fantasy team whose gameweek total would change.
+#[derive(Debug, Serialize)]
+pub struct TeamPointsChange {
+    pub team_id: Uuid,
+    pub team_name: String,
+    pub points_before: Option<i32>,
+    pub points_after: Option<i32>,
+}
+
+/// A team whose season rank in a league would move.
+#[derive(Debug, Serialize)]
+pub struct LeagueRankChange {
+    pub league_id: Uuid,
+    pub league_name: String,
+    pub team_id: Uuid,
+    pub team_name: String,
+    pub rank_before: i64,
+    pub rank_after: i64,
+}
+
+/// Everything a stats submission would change, computed and then rolled back.
+#[derive(Debug, Serialize)]
+pub struct StatsPreviewResponse {
+    pub week: i32,
+    pub players: Vec<PlayerPointsChange>,
+    pub price_changes: Vec<PlayerPriceChange>,
+    pub teams: Vec<TeamPointsChange>,
+    pub league_ranks: Vec<LeagueRankChange>,
 }
@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The four seasons a league year is split into, in playing order.
+pub const SEASON_NAMES: [&str; 4] = ["Summer", "Winter", "Spring", "Fall"];
+
+/// The price model a season uses, stored as text in `seasons.price_model`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PriceModelKind {
+    /// The week's top and bottom three scorers rise and fall.
+    Points,
+    /// The three most transferred-in players rise and the three most
+    /// transferred-out fall.
+    NetTransfers,
+}
+
+/// Database row for a season.
+///
+/// The open season has no `closed_at`; `end_week` is set when it closes.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Season {
+    pub id: Uuid,
+    pub name: String,
+    pub year: i32,
+    pub start_week: i32,
+    pub end_week: Option<i32>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    /// Whether the vice-captain inherits the multiplier when the captain plays no minutes.
+    pub vice_captain_fallback: bool,
+    /// Whether starters who play no minutes are replaced from the bench.
+    pub auto_subs: bool,
+    /// First gameweek of the second half, where half-season chips come back.
+    pub second_half_start_week: Option<i32>,
+    /// Free transfers added each gameweek; unused ones roll over up to `max_banked_transfers`.
+    pub free_transfers_per_week: i32,
+    pub max_banked_transfers: i32,
+    /// Points deducted for each transfer beyond the free ones.
+    pub transfer_hit_cost: i32,
+    /// How player prices move after each gameweek.
+    pub price_model: PriceModelKind,
+}
+
+/// A team's final position in a closed season.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct SeasonStanding {
+    pub team_id: Uuid,
+    pub user_id: Uuid,
+    pub team_name: String,
+    pub total_points: i32,
+    pub rank: i32,
+}
+
+/// A player's season total and the price change it earned at rollover.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PlayerSeasonResult {
+    pub player_id: Uuid,
+    pub player_name: String,
+    pub total_points: i32,
+    pub price_before: Decimal,
+    pub price_after: Decimal,
+}
+
+/// Query parameter selecting a season; defaults to the open one.
+#[derive(Debug, Deserialize)]
+pub struct SeasonQuery {
+    pub season_id: Option<Uuid>,
+}
+
+/// Request to close the open season. The next season defaults to the one that
+/// follows in `SEASON_NAMES`, rolling the year over after Fall.
+#[derive(Debug, Deserialize)]
+pub struct CloseSeasonRequest {
+    pub next_name: Option<String>,
+    pub next_year: Option<i32>,
+}
+
+/// Request to change the open season's rules. Omitted fields are left as they are.
+#[derive(Debug, Deserialize)]
+pub struct UpdateSeasonSettingsRequest {
+    pub vice_captain_fallback: Option<bool>,
+    pub auto_subs: Option<bool>,
+    pub second_half_start_week: Option<i32>,
+    pub free_transfers_per_week: Option<i32>,
+    pub max_banked_transfers: Option<i32>,
+    pub transfer_hit_cost: Option<i32>,
+    pub price_model: Option<PriceModelKind>,
+}
+
+/// Result of closing a season.
+#[derive(Debug, Serialize)]
+pub struct CloseSeasonResponse {
+    pub closed: Season,
+    pub next: Season,
+    pub standings: Vec<SeasonStanding>,
+    pub price_changes: Vec<PlayerSeasonResult>,
+}
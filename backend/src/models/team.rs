@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -13,6 +13,9 @@ pub struct FantasyTeam {
     pub user_id: Uuid,
     pub name: String,
     pub captain_id: Option<Uuid>,
+    pub vice_captain_id: Option<Uuid>,
+    /// The team's bank plus what it paid for its squad. Moves only when a sale
+    /// makes a profit or loss.
     pub budget_limit: Decimal,
     pub created_at: DateTime<Utc>,
 }
@@ -32,8 +35,13 @@ pub struct FantasyTeamWithPlayers {
     pub user_id: Uuid,
     pub name: String,
     pub captain_id: Option<Uuid>,
+    pub vice_captain_id: Option<Uuid>,
     pub budget_limit: Decimal,
     pub created_at: DateTime<Utc>,
+    /// Money not spent on the squad.
+    pub bank: Decimal,
+    /// What the squad would sell for today.
+    pub selling_value: Decimal,
     pub players: Vec<StarterPlayer>,
     pub bench: Vec<Player>,
     pub total_points: i32,
@@ -56,15 +64,82 @@ pub struct StarterAssignment {
 #[derive(Debug, Deserialize)]
 pub struct SetPlayersRequest {
     pub starters: Vec<StarterAssignment>,
+    /// In substitution priority order, first sub first.
     pub bench_player_ids: Vec<Uuid>,
     pub captain_id: Uuid,
+    /// Optional; must be a different starter from the captain.
+    #[serde(default)]
+    pub vice_captain_id: Option<Uuid>,
 }
 
-/// Info about a single chip (Triple Captain or Bench Boost).
+/// A chip in the catalogue, stored as text in `team_chips.chip_type`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ChipType {
+    TripleCaptain,
+    BenchBoost,
+    DoubleCaptain,
+    Wildcard,
+    FreeHit,
+}
+
+impl ChipType {
+    /// The name stored in `team_chips.chip_type`, e.g. "triple_captain".
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChipType::TripleCaptain => "triple_captain",
+            ChipType::BenchBoost => "bench_boost",
+            ChipType::DoubleCaptain => "double_captain",
+            ChipType::Wildcard => "wildcard",
+            ChipType::FreeHit => "free_hit",
+        }
+    }
+
+    /// Lower-case name for messages, e.g. "triple captain".
+    pub fn label(self) -> String {
+        self.as_str().replace('_', " ")
+    }
+
+    /// Whether transfers made in the chip's gameweek cost no points.
+    pub fn waives_transfer_hits(self) -> bool {
+        matches!(self, ChipType::Wildcard | ChipType::FreeHit)
+    }
+}
+
+/// A row of `chip_definitions`.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ChipDefinition {
+    pub chip_type: ChipType,
+    pub name: String,
+    pub description: String,
+    pub uses_per_season: i32,
+    /// `uses_per_season` applies to each half of the season separately.
+    pub per_half_season: bool,
+}
+
+/// Request to change how often a chip can be played per season (0 disables it).
+#[derive(Debug, Deserialize)]
+pub struct UpdateChipDefinitionRequest {
+    pub uses_per_season: i32,
+    #[serde(default)]
+    pub per_half_season: Option<bool>,
+}
+
+/// A team's standing with one chip this season.
 #[derive(Debug, Serialize)]
 pub struct ChipInfo {
+    pub chip_type: ChipType,
+    pub name: String,
+    pub description: String,
+    pub uses_per_season: i32,
+    pub per_half_season: bool,
+    /// Uses left this season, or in the current half for half-season chips.
+    pub remaining: i32,
     pub available: bool,
+    /// The most recent gameweek the chip was played in.
     pub used_in_week: Option<i32>,
+    pub used_in_weeks: Vec<i32>,
     pub can_deactivate: bool,
 }
 
@@ -78,23 +153,31 @@ pub struct ActiveGameweek {
 /// Combined chip status for a team.
 #[derive(Debug, Serialize)]
 pub struct ChipStatusResponse {
-    pub triple_captain: ChipInfo,
-    pub bench_boost: ChipInfo,
+    pub chips: Vec<ChipInfo>,
+    /// The chip played in the active gameweek, if any (one per gameweek).
+    pub active_chip: Option<ChipType>,
     pub active_gameweek: Option<ActiveGameweek>,
 }
 
 /// Request to activate a chip.
 #[derive(Debug, Deserialize)]
 pub struct ActivateChipRequest {
-    pub chip_type: String,
+    pub chip_type: ChipType,
+    /// Required for Double Captain: a starter other than the captain.
+    #[serde(default)]
+    pub second_captain_id: Option<Uuid>,
 }
 
 /// Row returned when querying chip usage.
 #[derive(Debug, sqlx::FromRow)]
 pub struct ChipRow {
-    pub chip_type: String,
+    pub id: Uuid,
+    pub chip_type: ChipType,
+    pub match_week_id: Uuid,
     pub week_number: i32,
     pub start_date: chrono::NaiveDate,
+    /// The week's own lineup lock, if it has one.
+    pub deadline_at: Option<DateTime<Utc>>,
 }
 
 /// Request to transfer a player (swap 1 out for 1 in).
@@ -105,19 +188,65 @@ pub struct TransferRequest {
     pub assigned_position: Option<PlayerPosition>,
 }
 
+/// Request to make several transfers at once (`POST /api/teams/:id/transfers`).
+///
+/// Only the final squad is validated, so a batch may pass through squads that
+/// would break the budget or top-player limit on their own.
+#[derive(Debug, Deserialize)]
+pub struct BatchTransferRequest {
+    pub transfers: Vec<TransferRequest>,
+    /// New roles for any starters in the final squad, transferred or not.
+    #[serde(default)]
+    pub assigned_positions: Vec<StarterAssignment>,
+    /// Required if the captain is transferred out; otherwise keeps the current one.
+    #[serde(default)]
+    pub captain_id: Option<Uuid>,
+    /// Required if the vice-captain is transferred out; otherwise keeps the current one.
+    #[serde(default)]
+    pub vice_captain_id: Option<Uuid>,
+}
+
+/// The team after a batch of transfers, with the gameweek's transfer totals.
+#[derive(Debug, Serialize)]
+pub struct BatchTransferResponse {
+    pub team: FantasyTeamWithPlayers,
+    pub transfers_made: i32,
+    pub transfers_used: i32,
+    pub free_transfers: i32,
+    pub extra_transfers: i32,
+    /// Points the gameweek's transfers will cost, including this batch.
+    pub points_hit: i32,
+}
+
 /// Transfer status for the current gameweek.
 #[derive(Debug, Serialize)]
 pub struct TransferStatusResponse {
     pub transfer_available: bool,
     pub active_gameweek: Option<i32>,
     pub transfers_used: i32,
+    /// Free transfers available this gameweek, including any banked from earlier weeks.
     pub free_transfers: i32,
+    pub max_banked_transfers: i32,
+    pub transfer_hit_cost: i32,
+    /// A Wildcard or Free Hit is active, so this week's transfers cost nothing.
+    pub unlimited_transfers: bool,
     pub extra_transfers: i32,
     pub points_hit: i32,
     pub transferred_out: Option<String>,
     pub transferred_in: Option<String>,
 }
 
+/// A bench player brought on automatically for a starter who didn't play.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct AutoSubstitution {
+    pub week_number: i32,
+    pub player_in_id: Uuid,
+    pub player_in_name: String,
+    pub player_out_id: Uuid,
+    pub player_out_name: String,
+    pub position: PlayerPosition,
+}
+
 /// A completed transfer record.
 #[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct TransferRecord {
@@ -126,5 +255,37 @@ pub struct TransferRecord {
     pub match_week_id: Uuid,
     pub player_out_id: Uuid,
     pub player_in_id: Uuid,
+    /// Prices when the transfer was made; unknown for transfers before they were recorded.
+    pub player_out_price: Option<Decimal>,
+    pub player_in_price: Option<Decimal>,
+    /// What the team had paid for the outgoing player.
+    pub player_out_purchase_price: Option<Decimal>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
+
+/// A transfer in a team's history, with player names.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TransferHistoryEntry {
+    pub id: Uuid,
+    pub match_week_id: Uuid,
+    pub week_number: i32,
+    pub player_out_id: Uuid,
+    pub player_out_name: String,
+    pub player_out_price: Option<Decimal>,
+    pub player_in_id: Uuid,
+    pub player_in_name: String,
+    pub player_in_price: Option<Decimal>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// The weekly lineup lock used when a gameweek has no deadline of its own.
+/// Weekdays are ISO 8601: 1 is Monday, 7 is Sunday.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LockSchedule {
+    /// An IANA name, e.g. `America/New_York`.
+    pub timezone: String,
+    pub lock_weekday: i16,
+    pub lock_time: NaiveTime,
+    pub unlock_weekday: i16,
+    pub unlock_time: NaiveTime,
+}
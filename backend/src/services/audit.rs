@@ -0,0 +1,64 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+/// An admin action to record in `audit_events`.
+///
+/// Write it with the same executor as the change itself, so a rolled-back
+/// change leaves no audit entry behind.
+pub struct AuditEntry {
+    actor_id: Uuid,
+    action: &'static str,
+    target_type: &'static str,
+    target_id: String,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        actor_id: Uuid,
+        action: &'static str,
+        target_type: &'static str,
+        target_id: impl ToString,
+    ) -> Self {
+        Self {
+            actor_id,
+            action,
+            target_type,
+            target_id: target_id.to_string(),
+            before: None,
+            after: None,
+        }
+    }
+
+    /// State of the target before the change.
+    pub fn before(mut self, value: impl Serialize) -> Self {
+        self.before = serde_json::to_value(value).ok();
+        self
+    }
+
+    /// State of the target after the change.
+    pub fn after(mut self, value: impl Serialize) -> Self {
+        self.after = serde_json::to_value(value).ok();
+        self
+    }
+
+    pub async fn record<'e, E>(self, executor: E) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"INSERT INTO audit_events (actor_id, action, target_type, target_id, before, after)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+        )
+        .bind(self.actor_id)
+        .bind(self.action)
+        .bind(self.target_type)
+        .bind(self.target_id)
+        .bind(self.before)
+        .bind(self.after)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
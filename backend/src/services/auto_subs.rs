@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::models::PlayerPosition;
+
+/// A squad member from a gameweek lineup snapshot, with their minutes that week.
+#[derive(Debug, Clone)]
+pub struct SquadMember {
+    pub player_id: Uuid,
+    pub position: PlayerPosition,
+    pub secondary_position: Option<PlayerPosition>,
+    /// The role a starter was picked for. Bench players have none.
+    pub assigned_position: Option<PlayerPosition>,
+    pub is_bench: bool,
+    pub bench_order: Option<i16>,
+    pub minutes_played: i32,
+}
+
+/// A bench player brought on for a starter who did not play.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoSub {
+    pub player_out: Uuid,
+    pub player_in: Uuid,
+    /// The role the substitute plays.
+    pub position: PlayerPosition,
+}
+
+fn position_rank(position: &PlayerPosition) -> u8 {
+    match position {
+        PlayerPosition::Gk => 0,
+        PlayerPosition::Def => 1,
+        PlayerPosition::Mid => 2,
+        PlayerPosition::Fwd => 3,
+    }
+}
+
+/// The starting formation rule `set_team_players` enforces: exactly 1 GK and at
+/// least 1 each of DEF, MID and FWD.
+pub fn formation_is_valid<'a>(positions: impl IntoIterator<Item = &'a PlayerPosition>) -> bool {
+    let mut counts = [0u8; 4];
+    for position in positions {
+        counts[position_rank(position) as usize] += 1;
+    }
+    counts[0] == 1 && counts[1] >= 1 && counts[2] >= 1 && counts[3] >= 1
+}
+
+/// Substitutions for one gameweek.
+///
+/// Starters who played no minutes are handled in formation order (GK, DEF, MID,
+/// FWD). Each is replaced by the first bench player, in bench order, who played
+/// and can fill a role — primary position first, then secondary — that keeps the
+/// formation valid. A starter nobody can replace stays in the lineup.
+pub fn auto_substitutions(squad: &[SquadMember]) -> Vec<AutoSub> {
+    let mut lineup: Vec<(Uuid, PlayerPosition)> = squad
+        .iter()
+        .filter(|m| !m.is_bench)
+        .map(|m| {
+            let role = m
+                .assigned_position
+                .clone()
+                .unwrap_or_else(|| m.position.clone());
+            (m.player_id, role)
+        })
+        .collect();
+
+    let mut absent: Vec<&SquadMember> = squad
+        .iter()
+        .filter(|m| !m.is_bench && m.minutes_played == 0)
+        .collect();
+    absent.sort_by_key(|m| position_rank(m.assigned_position.as_ref().unwrap_or(&m.position)));
+
+    let mut bench: Vec<&SquadMember> = squad
+        .iter()
+        .filter(|m| m.is_bench && m.minutes_played > 0)
+        .collect();
+    bench.sort_by_key(|m| m.bench_order.unwrap_or(i16::MAX));
+
+    let mut used: HashSet<Uuid> = HashSet::new();
+    let mut subs = Vec::new();
+
+    for starter in absent {
+        let slot = lineup
+            .iter()
+            .position(|(id, _)| *id == starter.player_id)
+            .expect("absent starters come from the lineup");
+
+        let replacement = bench
+            .iter()
+            .filter(|b| !used.contains(&b.player_id))
+            .find_map(|b| {
+                std::iter::once(&b.position)
+                    .chain(b.secondary_position.as_ref())
+                    .find(|role| {
+                        formation_is_valid(lineup.iter().enumerate().map(|(i, (_, pos))| {
+                            if i == slot {
+                                *role
+                            } else {
+                                pos
+                            }
+                        }))
+                    })
+                    .map(|role| (b.player_id, role.clone()))
+            });
+
+        if let Some((player_in, position)) = replacement {
+            used.insert(player_in);
+            lineup[slot] = (player_in, position.clone());
+            subs.push(AutoSub {
+                player_out: starter.player_id,
+                player_in,
+                position,
+            });
+        }
+    }
+
+    subs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(
+        n: u128,
+        position: PlayerPosition,
+        assigned: Option<PlayerPosition>,
+        bench_order: Option<i16>,
+        minutes_played: i32,
+    ) -> SquadMember {
+        SquadMember {
+            player_id: Uuid::from_u128(n),
+            position,
+            secondary_position: None,
+            is_bench: bench_order.is_some(),
+            assigned_position: assigned,
+            bench_order,
+            minutes_played,
+        }
+    }
+
+    /// GK, DEF, DEF, MID, MID, FWD starting; bench GK (1), DEF (2), FWD (3).
+    fn squad() -> Vec<SquadMember> {
+        use PlayerPosition::*;
+        vec![
+            member(1, Gk, Some(Gk), None, 60),
+            member(2, Def, Some(Def), None, 60),
+            member(3, Def, Some(Def), None, 60),
+            member(4, Mid, Some(Mid), None, 60),
+            member(5, Mid, Some(Mid), None, 60),
+            member(6, Fwd, Some(Fwd), None, 60),
+            member(7, Gk, None, Some(1), 60),
+            member(8, Def, None, Some(2), 60),
+            member(9, Fwd, None, Some(3), 60),
+        ]
+    }
+
+    #[test]
+    fn everyone_played_means_no_subs() {
+        assert!(auto_substitutions(&squad()).is_empty());
+    }
+
+    #[test]
+    fn first_bench_player_who_keeps_formation_comes_on() {
+        let mut squad = squad();
+        squad[3].minutes_played = 0; // a MID
+                                     // The bench GK cannot replace a MID without a second GK, so the DEF
+                                     // (next in bench order) comes on.
+        assert_eq!(
+            auto_substitutions(&squad),
+            vec![AutoSub {
+                player_out: Uuid::from_u128(4),
+                player_in: Uuid::from_u128(8),
+                position: PlayerPosition::Def,
+            }]
+        );
+    }
+
+    #[test]
+    fn goalkeeper_only_replaced_by_goalkeeper() {
+        let mut squad = squad();
+        squad[0].minutes_played = 0;
+        squad[6].minutes_played = 0; // bench GK did not play either
+        assert!(auto_substitutions(&squad).is_empty());
+    }
+
+    #[test]
+    fn last_of_a_position_needs_a_like_for_like_sub() {
+        let mut squad = squad();
+        squad[5].minutes_played = 0; // the only FWD
+        squad[8].minutes_played = 0; // bench FWD did not play
+                                     // A DEF coming on would leave no FWD, so nobody can replace him.
+        assert!(auto_substitutions(&squad).is_empty());
+
+        squad[7].secondary_position = Some(PlayerPosition::Fwd);
+        let subs = auto_substitutions(&squad);
+        assert_eq!(subs.len(), 1);
+        assert_eq!(subs[0].player_in, Uuid::from_u128(8));
+        assert_eq!(subs[0].position, PlayerPosition::Fwd);
+    }
+
+    #[test]
+    fn each_bench_player_comes_on_once() {
+        let mut squad = squad();
+        squad[1].minutes_played = 0;
+        squad[2].minutes_played = 0;
+        let subs = auto_substitutions(&squad);
+        let ins: Vec<Uuid> = subs.iter().map(|s| s.player_in).collect();
+        assert_eq!(ins, vec![Uuid::from_u128(8), Uuid::from_u128(9)]);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::models::{CupDecider, CupMatch, CupRound};
+
+/// A team's score in one gameweek, with the cup tiebreakers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WeekScore {
+    pub points: i32,
+    pub captain_points: i32,
+    pub goals: i32,
+}
+
+/// Number of rounds needed to find a winner among `entrants` teams.
+pub fn round_count(entrants: usize) -> usize {
+    entrants.next_power_of_two().trailing_zeros() as usize
+}
+
+/// Seeds in bracket order for a draw of `size` (a power of two), so that the top
+/// two seeds can only meet in the final: 1, 8, 4, 5, 2, 7, 3, 6 for eight.
+fn seed_order(size: usize) -> Vec<usize> {
+    let mut order = vec![1];
+    while order.len() < size {
+        let n = order.len() * 2;
+        order = order.iter().flat_map(|&s| [s, n + 1 - s]).collect();
+    }
+    order
+}
+
+/// Who goes through: more points, then more captain points, then more goals,
+/// and failing all of those the higher seed.
+fn decide(home: (Uuid, WeekScore), away: (Uuid, WeekScore)) -> (Uuid, CupDecider) {
+    let (h, a) = (home.1, away.1);
+    let (winner_is_home, decider) = if h.points != a.points {
+        (h.points > a.points, CupDecider::Points)
+    } else if h.captain_points != a.captain_points {
+        (
+            h.captain_points > a.captain_points,
+            CupDecider::CaptainPoints,
+        )
+    } else if h.goals != a.goals {
+        (h.goals > a.goals, CupDecider::Goals)
+    } else {
+        // The home side is always the higher seed.
+        (true, CupDecider::Seed)
+    };
+    (if winner_is_home { home.0 } else { away.0 }, decider)
+}
+
+/// The full bracket for `entrants`, listed best seed first.
+///
+/// Round `r` (from 1) is played in gameweek `start_week + r - 1`. `scores` holds
+/// the gameweeks that have been scored; a team missing from a scored week's map
+/// scored nothing. Rounds whose gameweek is not scored yet, and the slots they
+/// feed, are left open. When the draw is not a power of two the top seeds get
+/// first-round byes.
+pub fn bracket(
+    entrants: &[Uuid],
+    start_week: i32,
+    scores: &HashMap<i32, HashMap<Uuid, WeekScore>>,
+) -> Vec<CupRound> {
+    let rounds = round_count(entrants.len());
+    let size = entrants.len().next_power_of_two();
+    let seed_of: HashMap<Uuid, i32> = entrants
+        .iter()
+        .enumerate()
+        .map(|(i, &team)| (team, i as i32 + 1))
+        .collect();
+
+    // Round one's slots in bracket order; a seed beyond the field is a bye.
+    let mut slots: Vec<Option<Uuid>> = seed_order(size)
+        .into_iter()
+        .map(|seed| entrants.get(seed - 1).copied())
+        .collect();
+
+    let mut out = Vec::with_capacity(rounds);
+    for round in 1..=rounds {
+        let week_number = start_week + round as i32 - 1;
+        let week_scores = scores.get(&week_number);
+
+        let matches: Vec<CupMatch> = slots
+            .chunks(2)
+            .map(|pair| {
+                // The higher seed is listed first.
+                let (home, away) = match (pair[0], pair[1]) {
+                    (Some(a), Some(b)) if seed_of[&b] < seed_of[&a] => (Some(b), Some(a)),
+                    (None, b) => (b, None),
+                    (a, b) => (a, b),
+                };
+                let mut m = CupMatch {
+                    home_team_id: home,
+                    home_seed: home.map(|t| seed_of[&t]),
+                    away_team_id: away,
+                    away_seed: away.map(|t| seed_of[&t]),
+                    home_points: None,
+                    away_points: None,
+                    winner_team_id: None,
+                    decided_by: None,
+                };
+                match (home, away) {
+                    // Byes only happen in round one, where a slot can never be
+                    // waiting on an earlier result.
+                    (Some(team), None) if round == 1 => {
+                        m.winner_team_id = Some(team);
+                        m.decided_by = Some(CupDecider::Bye);
+                    }
+                    (Some(h), Some(a)) => {
+                        if let Some(week) = week_scores {
+                            let hs = week.get(&h).copied().unwrap_or_default();
+                            let aws = week.get(&a).copied().unwrap_or_default();
+                            let (winner, decider) = decide((h, hs), (a, aws));
+                            m.home_points = Some(hs.points);
+                            m.away_points = Some(aws.points);
+                            m.winner_team_id = Some(winner);
+                            m.decided_by = Some(decider);
+                        }
+                    }
+                    _ => {}
+                }
+                m
+            })
+            .collect();
+
+        slots = matches.iter().map(|m| m.winner_team_id).collect();
+        out.push(CupRound {
+            round: round as i32,
+            week_number,
+            matches,
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn teams(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    fn score(points: i32, captain_points: i32, goals: i32) -> WeekScore {
+        WeekScore {
+            points,
+            captain_points,
+            goals,
+        }
+    }
+
+    #[test]
+    fn seeds_meet_late() {
+        assert_eq!(seed_order(8), vec![1, 8, 4, 5, 2, 7, 3, 6]);
+        assert_eq!(round_count(5), 3);
+        assert_eq!(round_count(8), 3);
+        assert_eq!(round_count(2), 1);
+    }
+
+    #[test]
+    fn top_seeds_get_byes() {
+        let t = teams(6);
+        let rounds = bracket(&t, 10, &HashMap::new());
+        assert_eq!(rounds.len(), 3);
+        assert_eq!(rounds[0].week_number, 10);
+        assert_eq!(rounds[2].week_number, 12);
+
+        let byes: Vec<Uuid> = rounds[0]
+            .matches
+            .iter()
+            .filter(|m| m.decided_by == Some(CupDecider::Bye))
+            .filter_map(|m| m.winner_team_id)
+            .collect();
+        assert_eq!(byes, vec![t[0], t[1]]);
+        // Nothing scored yet, so round two only knows about the bye winners.
+        assert!(rounds[1].matches.iter().all(|m| m.winner_team_id.is_none()));
+    }
+
+    #[test]
+    fn ties_fall_through_captain_goals_then_seed() {
+        let t = teams(2);
+        let cases = [
+            (
+                score(50, 10, 2),
+                score(50, 12, 0),
+                t[1],
+                CupDecider::CaptainPoints,
+            ),
+            (score(50, 10, 2), score(50, 10, 3), t[1], CupDecider::Goals),
+            (score(50, 10, 2), score(50, 10, 2), t[0], CupDecider::Seed),
+            (score(40, 0, 0), score(60, 0, 0), t[1], CupDecider::Points),
+        ];
+        for (home, away, winner, decider) in cases {
+            let scores = HashMap::from([(1, HashMap::from([(t[0], home), (t[1], away)]))]);
+            let m = &bracket(&t, 1, &scores)[0].matches[0];
+            assert_eq!(m.winner_team_id, Some(winner));
+            assert_eq!(m.decided_by, Some(decider));
+        }
+    }
+
+    #[test]
+    fn winners_advance_and_missing_scores_count_as_zero() {
+        let t = teams(4);
+        let scores = HashMap::from([
+            (
+                1,
+                HashMap::from([
+                    (t[0], score(30, 0, 0)),
+                    (t[3], score(40, 0, 0)),
+                    (t[1], score(20, 0, 0)),
+                ]),
+            ),
+            (2, HashMap::from([(t[3], score(10, 0, 0))])),
+        ]);
+        let rounds = bracket(&t, 1, &scores);
+
+        // 1v4 goes to the 4th seed; 2v3 to the 2nd because seed 3 never scored.
+        let final_ = &rounds[1].matches[0];
+        assert_eq!(final_.home_team_id, Some(t[1]));
+        assert_eq!(final_.away_team_id, Some(t[3]));
+        assert_eq!(final_.winner_team_id, Some(t[3]));
+        assert_eq!(final_.home_points, Some(0));
+    }
+}
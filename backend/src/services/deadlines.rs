@@ -0,0 +1,201 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+use crate::models::LockSchedule;
+
+/// When lineups lock and unlock again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockWindow {
+    pub deadline_at: DateTime<Utc>,
+    pub unlock_at: DateTime<Utc>,
+}
+
+impl LockWindow {
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        self.deadline_at <= now && now < self.unlock_at
+    }
+}
+
+/// The default lock, repeating every week in a fixed timezone.
+#[derive(Debug, Clone, Copy)]
+pub struct WeeklyLock {
+    pub timezone: Tz,
+    pub lock_weekday: Weekday,
+    pub lock_time: NaiveTime,
+    pub unlock_weekday: Weekday,
+    pub unlock_time: NaiveTime,
+}
+
+fn iso_weekday(day: i16) -> Result<Weekday, String> {
+    match day {
+        1..=7 => Ok(Weekday::try_from(day as u8 - 1).expect("weekday in range")),
+        _ => Err(format!(
+            "Weekday {day} must be between 1 (Monday) and 7 (Sunday)"
+        )),
+    }
+}
+
+/// A wall-clock time in `tz`, taking the earlier reading when clocks go back
+/// and the first valid minute after a gap when they go forward.
+fn resolve(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    (0..=120)
+        .find_map(|m| {
+            tz.from_local_datetime(&(local + Duration::minutes(m)))
+                .earliest()
+        })
+        .expect("a valid local time within two hours")
+        .with_timezone(&Utc)
+}
+
+impl WeeklyLock {
+    /// Check stored settings: a known timezone and a window that is not empty.
+    pub fn from_settings(settings: &LockSchedule) -> Result<Self, String> {
+        let lock = WeeklyLock {
+            timezone: settings
+                .timezone
+                .parse()
+                .map_err(|_| format!("Unknown timezone {}", settings.timezone))?,
+            lock_weekday: iso_weekday(settings.lock_weekday)?,
+            lock_time: settings.lock_time,
+            unlock_weekday: iso_weekday(settings.unlock_weekday)?,
+            unlock_time: settings.unlock_time,
+        };
+        if lock.length().is_zero() {
+            return Err("The lock must end at a different time from when it starts".to_string());
+        }
+        Ok(lock)
+    }
+
+    /// How long each lock lasts, in wall-clock time.
+    fn length(&self) -> Duration {
+        let days = (self.unlock_weekday.num_days_from_monday() + 7
+            - self.lock_weekday.num_days_from_monday())
+            % 7;
+        let length = Duration::days(days as i64) + (self.unlock_time - self.lock_time);
+        if length < Duration::zero() {
+            length + Duration::weeks(1)
+        } else {
+            length
+        }
+    }
+
+    /// The window `now` falls in, or the next one to come.
+    pub fn window_at(&self, now: DateTime<Utc>) -> LockWindow {
+        let local_now = now.with_timezone(&self.timezone).naive_local();
+        let back = (local_now.weekday().num_days_from_monday() + 7
+            - self.lock_weekday.num_days_from_monday())
+            % 7;
+        let mut start = (local_now.date() - Duration::days(back as i64)).and_time(self.lock_time);
+        if start > local_now {
+            start -= Duration::weeks(1);
+        }
+
+        let window = |start: NaiveDateTime| LockWindow {
+            deadline_at: resolve(self.timezone, start),
+            unlock_at: resolve(self.timezone, start + self.length()),
+        };
+        let latest = window(start);
+        if now < latest.unlock_at {
+            latest
+        } else {
+            window(start + Duration::weeks(1))
+        }
+    }
+}
+
+/// The lock window in force at `now`: the active gameweek's own when it has one,
+/// otherwise the weekly schedule's.
+pub fn current_window(
+    now: DateTime<Utc>,
+    gameweek: Option<LockWindow>,
+    schedule: &WeeklyLock,
+) -> LockWindow {
+    gameweek.unwrap_or_else(|| schedule.window_at(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weekend() -> WeeklyLock {
+        WeeklyLock::from_settings(&LockSchedule {
+            timezone: "America/New_York".to_string(),
+            lock_weekday: 6,
+            lock_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            unlock_weekday: 7,
+            unlock_time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+        })
+        .unwrap()
+    }
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn default_schedule_locks_saturday_night_to_sunday_noon() {
+        let lock = weekend();
+        // Sat 17 Oct 2026, 23:00 EDT.
+        let w = lock.window_at(utc(2026, 10, 18, 3, 0));
+        assert!(w.contains(utc(2026, 10, 18, 3, 0)));
+        assert_eq!(w.deadline_at, utc(2026, 10, 18, 2, 0));
+        assert_eq!(w.unlock_at, utc(2026, 10, 18, 16, 0));
+
+        // Wednesday: unlocked, with the coming Saturday's deadline next.
+        let now = utc(2026, 10, 21, 15, 0);
+        let w = lock.window_at(now);
+        assert!(!w.contains(now));
+        assert_eq!(w.deadline_at, utc(2026, 10, 25, 2, 0));
+    }
+
+    #[test]
+    fn wall_clock_holds_across_daylight_saving() {
+        // Clocks go back on Sunday 1 Nov 2026, inside the window.
+        let w = weekend().window_at(utc(2026, 10, 30, 12, 0));
+        assert_eq!(w.deadline_at, utc(2026, 11, 1, 2, 0));
+        assert_eq!(w.unlock_at, utc(2026, 11, 1, 17, 0));
+    }
+
+    #[test]
+    fn windows_can_wrap_the_week() {
+        let lock = WeeklyLock {
+            lock_weekday: Weekday::Sun,
+            unlock_weekday: Weekday::Mon,
+            ..weekend()
+        };
+        // Mon 19 Oct 2026, 08:00 EDT: still locked from Sunday night.
+        let now = utc(2026, 10, 19, 12, 0);
+        assert!(lock.window_at(now).contains(now));
+    }
+
+    #[test]
+    fn a_gameweek_deadline_replaces_the_schedule() {
+        let own = LockWindow {
+            deadline_at: utc(2026, 10, 21, 22, 0),
+            unlock_at: utc(2026, 10, 22, 4, 0),
+        };
+        let saturday_night = utc(2026, 10, 25, 3, 0);
+        let w = current_window(saturday_night, Some(own), &weekend());
+        assert_eq!(w, own);
+        assert!(!w.contains(saturday_night));
+        assert!(w.contains(utc(2026, 10, 21, 23, 0)));
+    }
+
+    #[test]
+    fn bad_settings_are_rejected() {
+        let mut settings = LockSchedule {
+            timezone: "Mars/Olympus".to_string(),
+            lock_weekday: 6,
+            lock_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            unlock_weekday: 7,
+            unlock_time: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+        };
+        assert!(WeeklyLock::from_settings(&settings).is_err());
+        settings.timezone = "Europe/London".to_string();
+        settings.unlock_weekday = 8;
+        assert!(WeeklyLock::from_settings(&settings).is_err());
+        settings.unlock_weekday = 6;
+        settings.unlock_time = settings.lock_time;
+        assert!(WeeklyLock::from_settings(&settings).is_err());
+    }
+}
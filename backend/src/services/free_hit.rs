@@ -0,0 +1,126 @@
+use uuid::Uuid;
+
+/// Keep a copy of the team's squad, captain, vice-captain and budget against a Free Hit chip.
+///
+/// Changes made while the chip is active go into the next gameweek's lineup
+/// snapshot like any other; [`revert_finished`] then puts this copy back.
+pub async fn save_squad(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    team_chip_id: Uuid,
+    team_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"INSERT INTO free_hit_squad_players
+             (team_chip_id, player_id, is_bench, assigned_position, bench_order, purchase_price)
+           SELECT $1, player_id, is_bench, assigned_position, bench_order, purchase_price
+           FROM team_players WHERE team_id = $2"#,
+    )
+    .bind(team_chip_id)
+    .bind(team_id)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"UPDATE team_chips tc
+           SET saved_captain_id = ft.captain_id, saved_vice_captain_id = ft.vice_captain_id,
+               saved_budget_limit = ft.budget_limit
+           FROM fantasy_teams ft
+           WHERE tc.id = $1 AND ft.id = $2"#,
+    )
+    .bind(team_chip_id)
+    .bind(team_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Put back the squad saved by [`save_squad`] and mark the chip as reverted.
+pub async fn restore_squad(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    team_chip_id: Uuid,
+    team_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM team_players WHERE team_id = $1")
+        .bind(team_id)
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query(
+        r#"INSERT INTO team_players
+             (team_id, player_id, is_bench, assigned_position, bench_order, purchase_price)
+           SELECT $2, player_id, is_bench, assigned_position, bench_order, purchase_price
+           FROM free_hit_squad_players WHERE team_chip_id = $1"#,
+    )
+    .bind(team_chip_id)
+    .bind(team_id)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"UPDATE fantasy_teams ft
+           SET captain_id = tc.saved_captain_id, vice_captain_id = tc.saved_vice_captain_id,
+               budget_limit = COALESCE(tc.saved_budget_limit, ft.budget_limit)
+           FROM team_chips tc
+           WHERE tc.id = $1 AND ft.id = $2"#,
+    )
+    .bind(team_chip_id)
+    .bind(team_id)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query("UPDATE team_chips SET reverted_at = NOW() WHERE id = $1")
+        .bind(team_chip_id)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// Gameweek of the Free Hit whose squad is in the lineup snapshot for `week_number`.
+///
+/// A Free Hit played in gameweek N changes the squad that scores in the next
+/// gameweek, so that week cannot take a chip of its own.
+pub async fn squad_in_play<'e, E: sqlx::PgExecutor<'e>>(
+    executor: E,
+    team_id: Uuid,
+    week_number: i32,
+) -> Result<Option<i32>, sqlx::Error> {
+    sqlx::query_scalar::<_, i32>(
+        r#"SELECT mw.week_number FROM team_chips tc
+           INNER JOIN match_weeks mw ON mw.id = tc.match_week_id
+           WHERE tc.team_id = $1 AND tc.chip_type = 'free_hit'
+             AND mw.week_number = (SELECT MAX(week_number) FROM match_weeks WHERE week_number < $2)"#,
+    )
+    .bind(team_id)
+    .bind(week_number)
+    .fetch_optional(executor)
+    .await
+}
+
+/// Revert every Free Hit played in a gameweek before `match_week_id`, once
+/// that week's lineups have been snapshotted with the Free Hit squads in them.
+///
+/// Free Hits from the same or later gameweeks are left alone, so re-activating
+/// an older week does not restore a squad that is still in play.
+pub async fn revert_finished(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    match_week_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    let chips = sqlx::query_as::<_, (Uuid, Uuid)>(
+        r#"SELECT tc.id, tc.team_id FROM team_chips tc
+           INNER JOIN match_weeks mw ON mw.id = tc.match_week_id
+           WHERE tc.chip_type = 'free_hit' AND tc.reverted_at IS NULL
+             AND mw.week_number < (SELECT week_number FROM match_weeks WHERE id = $1)"#,
+    )
+    .bind(match_week_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for (team_chip_id, team_id) in chips {
+        restore_squad(tx, team_chip_id, team_id).await?;
+        tracing::info!("Free Hit squad restored for team {team_id}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::models::GameweekStatus;
+
+/// Something an admin does to a gameweek.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameweekAction {
+    /// Make it the active week.
+    Activate,
+    /// Enter or change its stats, or its matches.
+    Score,
+    /// Confirm its stats and commit prices and league results.
+    Finalise,
+    /// Take back a finalisation so the stats can be corrected.
+    Reopen,
+}
+
+/// The status a gameweek moves to when `action` is taken, or why it can't be.
+///
+/// upcoming → live → provisional → finalised, with reopen going back from
+/// finalised to provisional. Activating a week that is past upcoming leaves it
+/// where it is.
+pub fn transition(
+    status: GameweekStatus,
+    action: GameweekAction,
+) -> Result<GameweekStatus, &'static str> {
+    use GameweekStatus::*;
+
+    match (action, status) {
+        (GameweekAction::Activate, Upcoming) => Ok(Live),
+        (GameweekAction::Activate, status) => Ok(status),
+        (GameweekAction::Score, Finalised) => {
+            Err("is finalised; reopen it before changing its stats")
+        }
+        (GameweekAction::Score, _) => Ok(Provisional),
+        (GameweekAction::Finalise, Provisional) => Ok(Finalised),
+        (GameweekAction::Finalise, Finalised) => Err("is already finalised"),
+        (GameweekAction::Finalise, _) => Err("has no stats to finalise"),
+        (GameweekAction::Reopen, Finalised) => Ok(Provisional),
+        (GameweekAction::Reopen, _) => Err("is not finalised"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GameweekStatus::*;
+
+    #[test]
+    fn a_week_runs_from_upcoming_to_finalised() {
+        let live = transition(Upcoming, GameweekAction::Activate).unwrap();
+        assert_eq!(live, Live);
+        let provisional = transition(live, GameweekAction::Score).unwrap();
+        assert_eq!(provisional, Provisional);
+        assert_eq!(
+            transition(provisional, GameweekAction::Score),
+            Ok(Provisional)
+        );
+        assert_eq!(
+            transition(provisional, GameweekAction::Finalise),
+            Ok(Finalised)
+        );
+    }
+
+    #[test]
+    fn finalised_weeks_need_reopening_before_edits() {
+        assert!(transition(Finalised, GameweekAction::Score).is_err());
+        assert!(transition(Finalised, GameweekAction::Finalise).is_err());
+        assert_eq!(
+            transition(Finalised, GameweekAction::Activate),
+            Ok(Finalised)
+        );
+        assert_eq!(
+            transition(Finalised, GameweekAction::Reopen),
+            Ok(Provisional)
+        );
+    }
+
+    #[test]
+    fn only_scored_weeks_finalise_and_only_finalised_ones_reopen() {
+        for status in [Upcoming, Live] {
+            assert!(transition(status, GameweekAction::Finalise).is_err());
+        }
+        for status in [Upcoming, Live, Provisional] {
+            assert!(transition(status, GameweekAction::Reopen).is_err());
+        }
+    }
+}
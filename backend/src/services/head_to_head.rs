@@ -0,0 +1,243 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+/// One pairing in a round. `away` is `None` when `home` has a bye.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pairing {
+    pub home: Uuid,
+    pub away: Option<Uuid>,
+}
+
+/// `rounds` rounds of a round-robin between `teams`, by the circle method.
+///
+/// Every team meets every other once per cycle of `n - 1` rounds (`n` rounds with
+/// an odd number of teams, where each team sits out once). Later cycles repeat
+/// the schedule with home and away swapped.
+pub fn round_robin(teams: &[Uuid], rounds: usize) -> Vec<Vec<Pairing>> {
+    if teams.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut slots: Vec<Option<Uuid>> = teams.iter().copied().map(Some).collect();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+    let n = slots.len();
+    let cycle = n - 1;
+
+    (0..rounds)
+        .map(|round| {
+            let swap = (round / cycle) % 2 == 1;
+            let r = round % cycle;
+            // Slot 0 stays put; the rest rotate one place each round.
+            let at = |i: usize| {
+                if i == 0 {
+                    slots[0]
+                } else {
+                    slots[1 + (i - 1 + r) % cycle]
+                }
+            };
+            (0..n / 2)
+                .filter_map(|i| {
+                    let (mut a, mut b) = (at(i), at(n - 1 - i));
+                    // Alternate who is at home for the fixed team.
+                    if (i == 0 && r % 2 == 1) != swap {
+                        std::mem::swap(&mut a, &mut b);
+                    }
+                    match (a, b) {
+                        (Some(home), away) => Some(Pairing { home, away }),
+                        (None, Some(home)) => Some(Pairing { home, away: None }),
+                        (None, None) => None,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// How many rounds it takes for every team to meet every other once.
+pub fn cycle_length(team_count: usize) -> usize {
+    match team_count {
+        0 | 1 => 0,
+        n if n % 2 == 0 => n - 1,
+        n => n,
+    }
+}
+
+/// A played fixture's score.
+#[derive(Debug, Clone, Copy)]
+pub struct FixtureResult {
+    pub home: Uuid,
+    pub away: Uuid,
+    pub home_points: i32,
+    pub away_points: i32,
+}
+
+/// One team's line in a head-to-head table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableRow {
+    pub team_id: Uuid,
+    pub played: i32,
+    pub won: i32,
+    pub drawn: i32,
+    pub lost: i32,
+    pub points_for: i64,
+    pub points_against: i64,
+    /// 3 for a win, 1 for a draw.
+    pub points: i32,
+}
+
+/// The table for `teams` from played fixtures, ordered by points and then
+/// points scored. Teams with no results yet are listed with zeros.
+pub fn table(teams: &[Uuid], results: &[FixtureResult]) -> Vec<TableRow> {
+    let mut rows: HashMap<Uuid, TableRow> = teams
+        .iter()
+        .map(|&team_id| {
+            (
+                team_id,
+                TableRow {
+                    team_id,
+                    ..TableRow::default()
+                },
+            )
+        })
+        .collect();
+
+    for r in results {
+        for (team, scored, conceded) in [
+            (r.home, r.home_points, r.away_points),
+            (r.away, r.away_points, r.home_points),
+        ] {
+            let row = rows.entry(team).or_insert_with(|| TableRow {
+                team_id: team,
+                ..TableRow::default()
+            });
+            row.played += 1;
+            row.points_for += scored as i64;
+            row.points_against += conceded as i64;
+            match scored.cmp(&conceded) {
+                std::cmp::Ordering::Greater => {
+                    row.won += 1;
+                    row.points += 3;
+                }
+                std::cmp::Ordering::Equal => {
+                    row.drawn += 1;
+                    row.points += 1;
+                }
+                std::cmp::Ordering::Less => row.lost += 1,
+            }
+        }
+    }
+
+    let mut rows: Vec<TableRow> = rows.into_values().collect();
+    rows.sort_by_key(|r| (Reverse(r.points), Reverse(r.points_for), r.team_id));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn teams(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    fn meetings(rounds: &[Vec<Pairing>]) -> HashSet<(Uuid, Uuid)> {
+        rounds
+            .iter()
+            .flatten()
+            .filter_map(|p| p.away.map(|away| (p.home.min(away), p.home.max(away))))
+            .collect()
+    }
+
+    #[test]
+    fn even_league_meets_everyone_once_per_cycle() {
+        let t = teams(6);
+        let rounds = round_robin(&t, cycle_length(6));
+        assert_eq!(rounds.len(), 5);
+        for round in &rounds {
+            assert_eq!(round.len(), 3);
+            let playing: HashSet<Uuid> = round
+                .iter()
+                .flat_map(|p| [Some(p.home), p.away])
+                .flatten()
+                .collect();
+            assert_eq!(playing.len(), 6);
+        }
+        assert_eq!(meetings(&rounds).len(), 15);
+    }
+
+    #[test]
+    fn odd_league_gives_each_team_one_bye() {
+        let t = teams(5);
+        let rounds = round_robin(&t, cycle_length(5));
+        assert_eq!(rounds.len(), 5);
+        let byes: Vec<Uuid> = rounds
+            .iter()
+            .flatten()
+            .filter(|p| p.away.is_none())
+            .map(|p| p.home)
+            .collect();
+        assert_eq!(byes.len(), 5);
+        assert_eq!(byes.iter().collect::<HashSet<_>>().len(), 5);
+        assert_eq!(meetings(&rounds).len(), 10);
+    }
+
+    #[test]
+    fn second_cycle_swaps_home_and_away() {
+        let t = teams(4);
+        let rounds = round_robin(&t, 6);
+        for (first, second) in rounds[..3].iter().zip(&rounds[3..]) {
+            for (a, b) in first.iter().zip(second) {
+                assert_eq!(Some(a.home), b.away);
+                assert_eq!(a.away, Some(b.home));
+            }
+        }
+    }
+
+    #[test]
+    fn too_few_teams_have_no_fixtures() {
+        assert!(round_robin(&teams(1), 3).is_empty());
+    }
+
+    #[test]
+    fn table_uses_three_one_zero_then_points_for() {
+        let t = teams(3);
+        let results = [
+            FixtureResult {
+                home: t[0],
+                away: t[1],
+                home_points: 50,
+                away_points: 40,
+            },
+            FixtureResult {
+                home: t[1],
+                away: t[2],
+                home_points: 30,
+                away_points: 30,
+            },
+            FixtureResult {
+                home: t[2],
+                away: t[0],
+                home_points: 60,
+                away_points: 20,
+            },
+        ];
+        let rows = table(&t, &results);
+
+        // t[0] and t[2] both have 4 points; t[2] scored 90 to t[0]'s 70.
+        assert_eq!(rows[0].team_id, t[2]);
+        assert_eq!(
+            (rows[0].won, rows[0].drawn, rows[0].lost, rows[0].points),
+            (1, 1, 0, 4)
+        );
+        assert_eq!(rows[1].team_id, t[0]);
+        assert_eq!(rows[1].points_against, 100);
+        assert_eq!(rows[2].team_id, t[1]);
+        assert_eq!(rows[2].points, 1);
+    }
+}
@@ -0,0 +1,460 @@
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use crate::models::{
+    FoulSeverity, MatchEvent, MatchEventRequest, MatchEventType, MatchLineupEntry, MatchSide,
+    PlayerStatInput,
+};
+
+/// The rules' "fantasy time": only the first hour of a match counts.
+pub const DEFAULT_FANTASY_MINUTES: i32 = 60;
+
+/// One player's part in one match, as scoring sees it.
+#[derive(Debug, Clone, Copy, sqlx::FromRow)]
+pub struct Appearance {
+    pub player_id: Uuid,
+    /// Goals the player's side let in during fantasy time.
+    pub conceded: i32,
+    pub fantasy_minutes: i32,
+    /// Minutes from the match's lineup; `None` means the whole of fantasy time.
+    pub minutes_played: Option<i32>,
+}
+
+impl Appearance {
+    /// Fantasy-time minutes the player was on the pitch for.
+    pub fn minutes(&self) -> i32 {
+        self.minutes_played
+            .map_or(self.fantasy_minutes, |m| m.min(self.fantasy_minutes))
+    }
+}
+
+/// Check a match's lineups: both sides fielded and nobody on the pitch twice.
+pub fn validate_lineups(lineups: &[MatchLineupEntry]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    if let Some(dup) = lineups.iter().find(|e| !seen.insert(e.player_id)) {
+        return Err(format!("Player {} is listed more than once", dup.player_id));
+    }
+    if lineups
+        .iter()
+        .any(|e| e.minutes_played.is_some_and(|m| m < 0))
+    {
+        return Err("minutes_played cannot be negative".to_string());
+    }
+    for (side, name) in [(MatchSide::Home, "home"), (MatchSide::Away, "away")] {
+        if !lineups.iter().any(|e| e.side == side) {
+            return Err(format!("The {name} side has no players"));
+        }
+    }
+    Ok(())
+}
+
+/// Apply what a gameweek's matches say to the submitted stats.
+///
+/// Once a week has matches, each player's clean sheets are the matches they
+/// played in where their side conceded nothing during fantasy time, and their
+/// minutes are capped at what their lineups say they played of it. A week
+/// without matches keeps the stats as submitted.
+///
+/// Returns the ids of players credited with minutes who are in no lineup.
+pub fn apply_match_facts(
+    stats: &[PlayerStatInput],
+    appearances: &[Appearance],
+) -> Result<Vec<PlayerStatInput>, Vec<Uuid>> {
+    if appearances.is_empty() {
+        return Ok(stats.to_vec());
+    }
+
+    let mut by_player: HashMap<Uuid, Vec<&Appearance>> = HashMap::new();
+    for a in appearances {
+        by_player.entry(a.player_id).or_default().push(a);
+    }
+
+    let unlisted: Vec<Uuid> = stats
+        .iter()
+        .filter(|s| s.minutes_played > 0 && !by_player.contains_key(&s.player_id))
+        .map(|s| s.player_id)
+        .collect();
+    if !unlisted.is_empty() {
+        return Err(unlisted);
+    }
+
+    Ok(stats
+        .iter()
+        .map(|s| {
+            let played = by_player
+                .get(&s.player_id)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+            let lineup_minutes: i32 = played.iter().map(|a| a.minutes()).sum();
+            let minutes_played = s.minutes_played.min(lineup_minutes);
+            let clean_sheets = if minutes_played > 0 {
+                played
+                    .iter()
+                    .filter(|a| a.conceded == 0 && a.minutes() > 0)
+                    .count() as i32
+            } else {
+                0
+            };
+            PlayerStatInput {
+                minutes_played,
+                clean_sheets,
+                ..s.clone()
+            }
+        })
+        .collect())
+}
+
+/// Whether an event at `minute` falls inside fantasy time and so counts.
+pub fn in_fantasy_time(minute: i32, fantasy_minutes: i32) -> bool {
+    minute <= fantasy_minutes
+}
+
+/// Check an event against the match's lineups before logging it.
+pub fn validate_event(
+    event: &MatchEventRequest,
+    lineups: &[MatchLineupEntry],
+) -> Result<(), String> {
+    if !(0..=200).contains(&event.minute) {
+        return Err("minute must be between 0 and 200".to_string());
+    }
+    let side_of = |player_id: Uuid| {
+        lineups
+            .iter()
+            .find(|e| e.player_id == player_id)
+            .map(|e| e.side)
+    };
+    let side = side_of(event.player_id).ok_or("The player is not in this match's lineups")?;
+
+    if let Some(assister) = event.assist_player_id {
+        if event.event_type != MatchEventType::Goal {
+            return Err("Only a goal can have an assist".to_string());
+        }
+        if assister == event.player_id {
+            return Err("A player cannot assist their own goal".to_string());
+        }
+        if side_of(assister) != Some(side) {
+            return Err("The assister must be on the scorer's side".to_string());
+        }
+    }
+    match (event.event_type, event.foul_severity) {
+        (MatchEventType::Foul, None) => Err("A foul needs a severity".to_string()),
+        (MatchEventType::Foul, Some(_)) | (_, None) => Ok(()),
+        (_, Some(_)) => Err("Only a foul has a severity".to_string()),
+    }
+}
+
+/// The score from a match's events: goals for the scorer's side, own goals for
+/// the other one, counting only fantasy time. Returns (home, away).
+pub fn score_from_events(
+    events: &[MatchEvent],
+    lineups: &[MatchLineupEntry],
+    fantasy_minutes: i32,
+) -> (i32, i32) {
+    let side_of: HashMap<Uuid, MatchSide> = lineups.iter().map(|e| (e.player_id, e.side)).collect();
+    let (mut home, mut away) = (0, 0);
+    for e in events
+        .iter()
+        .filter(|e| in_fantasy_time(e.minute, fantasy_minutes))
+    {
+        let Some(&side) = side_of.get(&e.player_id) else {
+            continue;
+        };
+        let scoring_side = match (e.event_type, side) {
+            (MatchEventType::Goal, side) => side,
+            (MatchEventType::OwnGoal, MatchSide::Home) => MatchSide::Away,
+            (MatchEventType::OwnGoal, MatchSide::Away) => MatchSide::Home,
+            _ => continue,
+        };
+        match scoring_side {
+            MatchSide::Home => home += 1,
+            MatchSide::Away => away += 1,
+        }
+    }
+    (home, away)
+}
+
+/// One match's lineups and events, for building a week's stats.
+pub struct MatchRecord<'a> {
+    pub fantasy_minutes: i32,
+    pub lineups: &'a [MatchLineupEntry],
+    pub events: &'a [MatchEvent],
+}
+
+/// Per-player stats for a gameweek, added up from its matches.
+///
+/// Everyone in a lineup gets a row, with their minutes capped at fantasy time.
+/// Events after fantasy time are ignored. Clean sheets are left at 0 for
+/// [`apply_match_facts`] to fill in from the score.
+pub fn stats_from_events(matches: &[MatchRecord]) -> Vec<PlayerStatInput> {
+    fn entry<'a>(
+        stats: &'a mut Vec<PlayerStatInput>,
+        index: &mut HashMap<Uuid, usize>,
+        player_id: Uuid,
+    ) -> &'a mut PlayerStatInput {
+        let i = *index.entry(player_id).or_insert_with(|| {
+            stats.push(PlayerStatInput {
+                player_id,
+                goals: 0,
+                assists: 0,
+                clean_sheets: 0,
+                saves: 0,
+                penalty_saves: 0,
+                own_goals: 0,
+                penalty_misses: 0,
+                regular_fouls: 0,
+                serious_fouls: 0,
+                minutes_played: 0,
+            });
+            stats.len() - 1
+        });
+        &mut stats[i]
+    }
+
+    let mut stats: Vec<PlayerStatInput> = Vec::new();
+    let mut index: HashMap<Uuid, usize> = HashMap::new();
+    for m in matches {
+        for slot in m.lineups {
+            let minutes = slot.minutes_played.unwrap_or(m.fantasy_minutes);
+            entry(&mut stats, &mut index, slot.player_id).minutes_played +=
+                minutes.min(m.fantasy_minutes);
+        }
+        for e in m
+            .events
+            .iter()
+            .filter(|e| in_fantasy_time(e.minute, m.fantasy_minutes))
+        {
+            let stat = entry(&mut stats, &mut index, e.player_id);
+            match (e.event_type, e.foul_severity) {
+                (MatchEventType::Goal, _) => stat.goals += 1,
+                (MatchEventType::OwnGoal, _) => stat.own_goals += 1,
+                (MatchEventType::Save, _) => stat.saves += 1,
+                (MatchEventType::PenaltySave, _) => stat.penalty_saves += 1,
+                (MatchEventType::PenaltyMiss, _) => stat.penalty_misses += 1,
+                (MatchEventType::Foul, Some(FoulSeverity::Serious)) => stat.serious_fouls += 1,
+                (MatchEventType::Foul, _) => stat.regular_fouls += 1,
+            }
+            if let Some(assister) = e.assist_player_id {
+                entry(&mut stats, &mut index, assister).assists += 1;
+            }
+        }
+    }
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(player_id: Uuid, minutes_played: i32, clean_sheets: i32) -> PlayerStatInput {
+        PlayerStatInput {
+            player_id,
+            goals: 0,
+            assists: 0,
+            clean_sheets,
+            saves: 0,
+            penalty_saves: 0,
+            own_goals: 0,
+            penalty_misses: 0,
+            regular_fouls: 0,
+            serious_fouls: 0,
+            minutes_played,
+        }
+    }
+
+    fn appearance(player_id: Uuid, conceded: i32) -> Appearance {
+        Appearance {
+            player_id,
+            conceded,
+            fantasy_minutes: DEFAULT_FANTASY_MINUTES,
+            minutes_played: None,
+        }
+    }
+
+    #[test]
+    fn clean_sheets_come_from_goals_conceded() {
+        let (keeper, striker, sub) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let stats = [stat(keeper, 60, 0), stat(striker, 60, 1), stat(sub, 0, 1)];
+        let appearances = [
+            appearance(keeper, 0),
+            appearance(striker, 2),
+            appearance(sub, 0),
+        ];
+
+        let out = apply_match_facts(&stats, &appearances).unwrap();
+        assert_eq!(out[0].clean_sheets, 1);
+        assert_eq!(out[1].clean_sheets, 0);
+        // On the team sheet but never came on.
+        assert_eq!(out[2].clean_sheets, 0);
+    }
+
+    #[test]
+    fn minutes_stop_at_fantasy_time() {
+        let player = Uuid::new_v4();
+        let out = apply_match_facts(&[stat(player, 90, 0)], &[appearance(player, 1)]).unwrap();
+        assert_eq!(out[0].minutes_played, 60);
+    }
+
+    #[test]
+    fn two_match_weeks_follow_each_lineup() {
+        let player = Uuid::new_v4();
+        let appearances = [
+            Appearance {
+                minutes_played: Some(25),
+                ..appearance(player, 0)
+            },
+            Appearance {
+                minutes_played: Some(0),
+                ..appearance(player, 0)
+            },
+        ];
+
+        let out = apply_match_facts(&[stat(player, 120, 0)], &appearances).unwrap();
+        assert_eq!(out[0].minutes_played, 25);
+        // Unused in the second match, so only the first clean sheet counts.
+        assert_eq!(out[0].clean_sheets, 1);
+
+        let full = [appearance(player, 0), appearance(player, 3)];
+        let out = apply_match_facts(&[stat(player, 120, 0)], &full).unwrap();
+        assert_eq!((out[0].minutes_played, out[0].clean_sheets), (120, 1));
+    }
+
+    #[test]
+    fn players_outside_every_lineup_are_rejected() {
+        let (listed, missing) = (Uuid::new_v4(), Uuid::new_v4());
+        let stats = [stat(listed, 60, 0), stat(missing, 30, 0)];
+        assert_eq!(
+            apply_match_facts(&stats, &[appearance(listed, 0)]).unwrap_err(),
+            vec![missing]
+        );
+    }
+
+    #[test]
+    fn weeks_without_matches_keep_submitted_stats() {
+        let stats = [stat(Uuid::new_v4(), 90, 1)];
+        let out = apply_match_facts(&stats, &[]).unwrap();
+        assert_eq!((out[0].minutes_played, out[0].clean_sheets), (90, 1));
+    }
+
+    fn slot(player_id: Uuid, side: MatchSide) -> MatchLineupEntry {
+        MatchLineupEntry {
+            player_id,
+            side,
+            minutes_played: None,
+        }
+    }
+
+    fn event(minute: i32, event_type: MatchEventType, player_id: Uuid) -> MatchEvent {
+        MatchEvent {
+            id: Uuid::new_v4(),
+            match_id: Uuid::nil(),
+            minute,
+            event_type,
+            player_id,
+            assist_player_id: None,
+            foul_severity: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn events_after_fantasy_time_do_not_count() {
+        let (home, away) = (Uuid::new_v4(), Uuid::new_v4());
+        let lineups = [slot(home, MatchSide::Home), slot(away, MatchSide::Away)];
+        let events = [
+            event(12, MatchEventType::Goal, home),
+            event(60, MatchEventType::OwnGoal, home),
+            event(61, MatchEventType::Goal, away),
+        ];
+
+        assert_eq!(score_from_events(&events, &lineups, 60), (1, 1));
+
+        let stats = stats_from_events(&[MatchRecord {
+            fantasy_minutes: 60,
+            lineups: &lineups,
+            events: &events,
+        }]);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            (stats[0].goals, stats[0].own_goals, stats[0].minutes_played),
+            (1, 1, 60)
+        );
+        assert_eq!(stats[1].goals, 0);
+    }
+
+    #[test]
+    fn goals_credit_the_assister_and_fouls_their_severity() {
+        let (scorer, assister) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut lineups = [
+            slot(scorer, MatchSide::Home),
+            slot(assister, MatchSide::Home),
+        ];
+        lineups[1].minutes_played = Some(25);
+        let mut goal = event(30, MatchEventType::Goal, scorer);
+        goal.assist_player_id = Some(assister);
+        let mut foul = event(40, MatchEventType::Foul, assister);
+        foul.foul_severity = Some(FoulSeverity::Serious);
+
+        let stats = stats_from_events(&[MatchRecord {
+            fantasy_minutes: 60,
+            lineups: &lineups,
+            events: &[goal, foul],
+        }]);
+        assert_eq!(stats[1].assists, 1);
+        assert_eq!(stats[1].serious_fouls, 1);
+        assert_eq!(stats[1].minutes_played, 25);
+    }
+
+    #[test]
+    fn events_must_fit_the_lineups() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let lineups = [
+            slot(a, MatchSide::Home),
+            slot(b, MatchSide::Home),
+            slot(c, MatchSide::Away),
+        ];
+        let request = |event_type, player_id, assist_player_id, foul_severity| MatchEventRequest {
+            minute: 10,
+            event_type,
+            player_id,
+            assist_player_id,
+            foul_severity,
+        };
+
+        assert!(validate_event(&request(MatchEventType::Goal, a, Some(b), None), &lineups).is_ok());
+        assert!(
+            validate_event(&request(MatchEventType::Goal, a, Some(c), None), &lineups).is_err()
+        );
+        assert!(
+            validate_event(&request(MatchEventType::Goal, a, Some(a), None), &lineups).is_err()
+        );
+        assert!(
+            validate_event(&request(MatchEventType::Save, a, Some(b), None), &lineups).is_err()
+        );
+        assert!(validate_event(&request(MatchEventType::Foul, c, None, None), &lineups).is_err());
+        assert!(validate_event(
+            &request(MatchEventType::Goal, a, None, Some(FoulSeverity::Regular)),
+            &lineups
+        )
+        .is_err());
+        assert!(validate_event(
+            &request(MatchEventType::Goal, Uuid::new_v4(), None, None),
+            &lineups
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn lineups_need_two_sides_and_no_duplicates() {
+        let p = Uuid::new_v4();
+        let entry = slot;
+        assert!(validate_lineups(&[entry(p, MatchSide::Home)]).is_err());
+        assert!(validate_lineups(&[entry(p, MatchSide::Home), entry(p, MatchSide::Away)]).is_err());
+        assert!(validate_lineups(&[
+            entry(p, MatchSide::Home),
+            entry(Uuid::new_v4(), MatchSide::Away)
+        ])
+        .is_ok());
+    }
+}
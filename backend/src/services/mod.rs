@@ -1,3 +1,16 @@
+pub mod audit;
+pub mod auto_subs;
+pub mod cup;
+pub mod deadlines;
+pub mod free_hit;
+pub mod gameweek;
+pub mod head_to_head;
+pub mod matches;
 pub mod points_engine;
 pub mod points_sql;
+pub mod price_model;
 pub mod seed;
+pub mod squad_rules;
+pub mod standings;
+pub mod stats_csv;
+pub mod transfer_policy;
@@ -1,121 +1,241 @@
-use crate::models::PlayerPosition;
+use uuid::Uuid;
+
+use crate::models::{PlayerPosition, PlayerStatInput, ScoringRules};
+
+/// One player's raw stats for a match week.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatchStats {
+    pub goals: i32,
+    pub assists: i32,
+    pub clean_sheets: i32,
+    pub saves: i32,
+    pub penalty_saves: i32,
+    pub own_goals: i32,
+    pub penalty_misses: i32,
+    pub regular_fouls: i32,
+    pub serious_fouls: i32,
+    pub minutes_played: i32,
+}
+
+impl From<&PlayerStatInput> for MatchStats {
+    fn from(s: &PlayerStatInput) -> Self {
+        Self {
+            goals: s.goals,
+            assists: s.assists,
+            clean_sheets: s.clean_sheets,
+            saves: s.saves,
+            penalty_saves: s.penalty_saves,
+            own_goals: s.own_goals,
+            penalty_misses: s.penalty_misses,
+            regular_fouls: s.regular_fouls,
+            serious_fouls: s.serious_fouls,
+            minutes_played: s.minutes_played,
+        }
+    }
+}
 
 pub struct PointsEngine;
 
 impl PointsEngine {
-    /// Calculate total fantasy points for a player in a match week.
+    /// Calculate total fantasy points for a player in a match week under the
+    /// given rule set.
     ///
-    /// Scoring per MRR Fantasy rules:
-    /// - Goals: GK=10, DEF=6, MID=5, FWD=4
-    /// - Assists: 5 pts each
-    /// - Clean Sheets: GK=2, DEF=2
-    /// - Saves (GK only): 1 pt per 5 saves
-    /// - Penalty Save: +8
-    /// - Minutes: 35+ = 2, 1-34 = 1
-    /// - Regular Foul: -1
-    /// - Serious Foul: -3
-    /// - Own Goal: -2
-    /// - Penalty Miss: -2
-    pub fn calculate(
-        position: &PlayerPosition,
-        goals: i32,
-        assists: i32,
-        clean_sheets: i32,
-        saves: i32,
-        penalty_saves: i32,
-        own_goals: i32,
-        penalty_misses: i32,
-        regular_fouls: i32,
-        serious_fouls: i32,
-        minutes_played: i32,
-    ) -> i32 {
-        let goal_pts = goals
+    /// Goals and clean sheets are rated per position, saves count for
+    /// goalkeepers only, and appearances earn a short or long bonus depending on
+    /// `long_appearance_minutes`. Deductions come from the rule set already
+    /// signed.
+    pub fn calculate(rules: &ScoringRules, position: &PlayerPosition, stats: &MatchStats) -> i32 {
+        let goal_pts = stats.goals
             * match position {
-                PlayerPosition::Gk => 10,
-                PlayerPosition::Def => 6,
-                PlayerPosition::Mid => 5,
-                PlayerPosition::Fwd => 4,
+                PlayerPosition::Gk => rules.goal_gk,
+                PlayerPosition::Def => rules.goal_def,
+                PlayerPosition::Mid => rules.goal_mid,
+                PlayerPosition::Fwd => rules.goal_fwd,
             };
 
-        let assist_pts = assists * 5;
+        let assist_pts = stats.assists * rules.assist;
 
-        let cs_pts = clean_sheets
+        let cs_pts = stats.clean_sheets
             * match position {
-                PlayerPosition::Gk => 2,
-                PlayerPosition::Def => 2,
-                _ => 0,
+                PlayerPosition::Gk => rules.clean_sheet_gk,
+                PlayerPosition::Def => rules.clean_sheet_def,
+                PlayerPosition::Mid => rules.clean_sheet_mid,
+                PlayerPosition::Fwd => rules.clean_sheet_fwd,
             };
 
         let save_pts = match position {
-            PlayerPosition::Gk => saves / 5,
+            PlayerPosition::Gk => stats.saves / rules.saves_per_point,
             _ => 0,
         };
 
-        let pen_save_pts = penalty_saves * 8;
+        let pen_save_pts = stats.penalty_saves * rules.penalty_save;
 
-        let minutes_pts = if minutes_played >= 35 {
-            2
-        } else if minutes_played >= 1 {
-            1
+        let minutes_pts = if stats.minutes_played >= rules.long_appearance_minutes {
+            rules.long_appearance
+        } else if stats.minutes_played >= 1 {
+            rules.short_appearance
         } else {
             0
         };
 
-        let negative =
-            own_goals * -2 + penalty_misses * -2 + regular_fouls * -1 + serious_fouls * -3;
+        let negative = stats.own_goals * rules.own_goal
+            + stats.penalty_misses * rules.penalty_miss
+            + stats.regular_fouls * rules.regular_foul
+            + stats.serious_fouls * rules.serious_foul;
 
         goal_pts + assist_pts + cs_pts + save_pts + pen_save_pts + minutes_pts + negative
     }
+
+    /// The player whose points get the captain multiplier in a gameweek.
+    ///
+    /// Always the captain, unless the season enables `vice_captain_fallback` and
+    /// the captain played no minutes, in which case the vice-captain (if any)
+    /// takes the armband.
+    pub fn armband(
+        captain_id: Option<Uuid>,
+        vice_captain_id: Option<Uuid>,
+        captain_minutes: i32,
+        vice_captain_fallback: bool,
+    ) -> Option<Uuid> {
+        if vice_captain_fallback && vice_captain_id.is_some() && captain_minutes == 0 {
+            vice_captain_id
+        } else {
+            captain_id
+        }
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
+    /// The original MRR Fantasy rates, as seeded by migration 021.
+    pub(crate) fn original_rules() -> ScoringRules {
+        ScoringRules {
+            id: uuid::Uuid::nil(),
+            name: "Original rules".to_string(),
+            season_id: uuid::Uuid::nil(),
+            goal_gk: 10,
+            goal_def: 6,
+            goal_mid: 5,
+            goal_fwd: 4,
+            assist: 5,
+            clean_sheet_gk: 2,
+            clean_sheet_def: 2,
+            clean_sheet_mid: 0,
+            clean_sheet_fwd: 0,
+            saves_per_point: 5,
+            penalty_save: 8,
+            long_appearance_minutes: 35,
+            long_appearance: 2,
+            short_appearance: 1,
+            own_goal: -2,
+            penalty_miss: -2,
+            regular_foul: -1,
+            serious_foul: -3,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    fn calc(position: PlayerPosition, stats: MatchStats) -> i32 {
+        PointsEngine::calculate(&original_rules(), &position, &stats)
+    }
+
     #[test]
     fn test_forward_goal_points() {
-        let pts = PointsEngine::calculate(&PlayerPosition::Fwd, 2, 1, 0, 0, 0, 0, 0, 0, 0, 60);
+        let pts = calc(
+            PlayerPosition::Fwd,
+            MatchStats {
+                goals: 2,
+                assists: 1,
+                minutes_played: 60,
+                ..Default::default()
+            },
+        );
         // 2 goals * 4 + 1 assist * 5 + 2 (minutes 60) = 15
         assert_eq!(pts, 15);
     }
 
     #[test]
     fn test_midfielder_goal_and_assist() {
-        let pts = PointsEngine::calculate(&PlayerPosition::Mid, 1, 2, 0, 0, 0, 0, 0, 0, 0, 50);
+        let pts = calc(
+            PlayerPosition::Mid,
+            MatchStats {
+                goals: 1,
+                assists: 2,
+                minutes_played: 50,
+                ..Default::default()
+            },
+        );
         // 1 goal * 5 + 2 assists * 5 + 2 (minutes 50) = 17
         assert_eq!(pts, 17);
     }
 
     #[test]
     fn test_defender_clean_sheet() {
-        let pts = PointsEngine::calculate(&PlayerPosition::Def, 0, 0, 1, 0, 0, 0, 0, 0, 0, 60);
+        let pts = calc(
+            PlayerPosition::Def,
+            MatchStats {
+                clean_sheets: 1,
+                minutes_played: 60,
+                ..Default::default()
+            },
+        );
         // 1 cs * 2 + 2 (minutes 60) = 4
         assert_eq!(pts, 4);
     }
 
     #[test]
     fn test_goalkeeper_saves() {
-        let pts = PointsEngine::calculate(&PlayerPosition::Gk, 0, 0, 1, 10, 0, 0, 0, 0, 0, 60);
+        let pts = calc(
+            PlayerPosition::Gk,
+            MatchStats {
+                clean_sheets: 1,
+                saves: 10,
+                minutes_played: 60,
+                ..Default::default()
+            },
+        );
         // 1 cs * 2 + 10/5=2 saves + 2 (minutes 60) = 6
         assert_eq!(pts, 6);
     }
 
     #[test]
     fn test_goalkeeper_heroic_goal() {
-        let pts = PointsEngine::calculate(&PlayerPosition::Gk, 1, 0, 1, 5, 1, 0, 0, 0, 0, 60);
+        let pts = calc(
+            PlayerPosition::Gk,
+            MatchStats {
+                goals: 1,
+                clean_sheets: 1,
+                saves: 5,
+                penalty_saves: 1,
+                minutes_played: 60,
+                ..Default::default()
+            },
+        );
         // 1 goal * 10 + 1 cs * 2 + 5/5=1 save + 1 pen_save * 8 + 2 (minutes) = 23
         assert_eq!(pts, 23);
     }
 
     #[test]
     fn test_zero_stats() {
-        let pts = PointsEngine::calculate(&PlayerPosition::Fwd, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0);
+        let pts = calc(PlayerPosition::Fwd, MatchStats::default());
         assert_eq!(pts, 0);
     }
 
     #[test]
     fn test_negative_points() {
-        let pts = PointsEngine::calculate(&PlayerPosition::Mid, 0, 0, 0, 0, 0, 1, 1, 2, 1, 60);
+        let pts = calc(
+            PlayerPosition::Mid,
+            MatchStats {
+                own_goals: 1,
+                penalty_misses: 1,
+                regular_fouls: 2,
+                serious_fouls: 1,
+                minutes_played: 60,
+                ..Default::default()
+            },
+        );
         // 0 goals + 0 assists + 1 OG * -2 + 1 pen_miss * -2 + 2 reg_fouls * -1 + 1 serious * -3 + 2 (mins)
         // = -2 + -2 + -2 + -3 + 2 = -7
         assert_eq!(pts, -7);
@@ -123,8 +243,58 @@ mod tests {
 
     #[test]
     fn test_low_minutes() {
-        let pts = PointsEngine::calculate(&PlayerPosition::Fwd, 1, 0, 0, 0, 0, 0, 0, 0, 0, 20);
+        let pts = calc(
+            PlayerPosition::Fwd,
+            MatchStats {
+                goals: 1,
+                minutes_played: 20,
+                ..Default::default()
+            },
+        );
         // 1 goal * 4 + 1 (minutes 20) = 5
         assert_eq!(pts, 5);
     }
+
+    #[test]
+    fn test_armband_defaults_to_captain() {
+        let (c, v) = (Some(Uuid::from_u128(1)), Some(Uuid::from_u128(2)));
+        // Without the season rule the vice-captain never inherits.
+        assert_eq!(PointsEngine::armband(c, v, 0, false), c);
+        assert_eq!(PointsEngine::armband(c, v, 60, false), c);
+    }
+
+    #[test]
+    fn test_armband_vice_captain_fallback() {
+        let (c, v) = (Some(Uuid::from_u128(1)), Some(Uuid::from_u128(2)));
+        assert_eq!(PointsEngine::armband(c, v, 0, true), v);
+        assert_eq!(PointsEngine::armband(c, v, 1, true), c);
+        assert_eq!(PointsEngine::armband(c, None, 0, true), c);
+    }
+
+    #[test]
+    fn test_rule_book_clean_sheets() {
+        let rules = ScoringRules {
+            clean_sheet_gk: 10,
+            clean_sheet_def: 6,
+            ..original_rules()
+        };
+        let stats = MatchStats {
+            clean_sheets: 1,
+            minutes_played: 60,
+            ..Default::default()
+        };
+        // A later rule set only changes the rates it overrides.
+        assert_eq!(
+            PointsEngine::calculate(&rules, &PlayerPosition::Gk, &stats),
+            12
+        );
+        assert_eq!(
+            PointsEngine::calculate(&rules, &PlayerPosition::Def, &stats),
+            8
+        );
+        assert_eq!(
+            PointsEngine::calculate(&rules, &PlayerPosition::Mid, &stats),
+            2
+        );
+    }
 }
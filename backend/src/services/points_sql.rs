@@ -1,7 +1,8 @@
 //! Canonical SQL for MRR Fantasy scoring.
 //!
 //! This is the SQL counterpart to [`super::points_engine::PointsEngine`] and must
-//! stay in lockstep with it; `tests::sql_matches_rust_engine` enforces that.
+//! stay in lockstep with it; `tests::sql_matches_rust_engine` enforces that. Both
+//! read their rates from the `scoring_rules` version in force for the week.
 //!
 //! Every scoring query lives here rather than inline at the call sites. The
 //! expression used to be copy-pasted across eight queries, which is how starters
@@ -21,53 +22,109 @@ fn scoring_position(alias: &str) -> String {
 /// SQL expression for one `player_points` row's fantasy points.
 ///
 /// `position` must be a `::text` position expression; `pp` is the alias of the
-/// `player_points` row. Parenthesised so it is safe to multiply or aggregate.
-fn week_points(position: &str, pp: &str) -> String {
+/// `player_points` row and `sr` the alias of its week's
+/// `match_week_scoring_rules` row. Parenthesised so it is safe to multiply or
+/// aggregate.
+fn week_points(position: &str, pp: &str, sr: &str) -> String {
     format!(
         r#"(
           CASE {position}
-            WHEN 'GK'  THEN COALESCE({pp}.goals, 0) * 10
-            WHEN 'DEF' THEN COALESCE({pp}.goals, 0) * 6
-            WHEN 'MID' THEN COALESCE({pp}.goals, 0) * 5
-            WHEN 'FWD' THEN COALESCE({pp}.goals, 0) * 4
+            WHEN 'GK'  THEN COALESCE({pp}.goals, 0) * {sr}.goal_gk
+            WHEN 'DEF' THEN COALESCE({pp}.goals, 0) * {sr}.goal_def
+            WHEN 'MID' THEN COALESCE({pp}.goals, 0) * {sr}.goal_mid
+            WHEN 'FWD' THEN COALESCE({pp}.goals, 0) * {sr}.goal_fwd
             ELSE 0
           END
-          + COALESCE({pp}.assists, 0) * 5
+          + COALESCE({pp}.assists, 0) * {sr}.assist
           + CASE {position}
-              WHEN 'GK'  THEN COALESCE({pp}.clean_sheets, 0) * 2
-              WHEN 'DEF' THEN COALESCE({pp}.clean_sheets, 0) * 2
+              WHEN 'GK'  THEN COALESCE({pp}.clean_sheets, 0) * {sr}.clean_sheet_gk
+              WHEN 'DEF' THEN COALESCE({pp}.clean_sheets, 0) * {sr}.clean_sheet_def
+              WHEN 'MID' THEN COALESCE({pp}.clean_sheets, 0) * {sr}.clean_sheet_mid
+              WHEN 'FWD' THEN COALESCE({pp}.clean_sheets, 0) * {sr}.clean_sheet_fwd
               ELSE 0
             END
-          + CASE WHEN {position} = 'GK' THEN COALESCE({pp}.saves, 0) / 5 ELSE 0 END
-          + COALESCE({pp}.penalty_saves, 0) * 8
-          + CASE WHEN COALESCE({pp}.minutes_played, 0) >= 35 THEN 2
-                 WHEN COALESCE({pp}.minutes_played, 0) >= 1  THEN 1
+          + CASE WHEN {position} = 'GK'
+                 THEN COALESCE({pp}.saves, 0) / {sr}.saves_per_point ELSE 0 END
+          + COALESCE({pp}.penalty_saves, 0) * {sr}.penalty_save
+          + CASE WHEN COALESCE({pp}.minutes_played, 0) >= {sr}.long_appearance_minutes
+                   THEN {sr}.long_appearance
+                 WHEN COALESCE({pp}.minutes_played, 0) >= 1 THEN {sr}.short_appearance
                  ELSE 0 END
-          - COALESCE({pp}.own_goals, 0) * 2
-          - COALESCE({pp}.penalty_misses, 0) * 2
-          - COALESCE({pp}.regular_fouls, 0) * 1
-          - COALESCE({pp}.serious_fouls, 0) * 3
+          + COALESCE({pp}.own_goals, 0) * {sr}.own_goal
+          + COALESCE({pp}.penalty_misses, 0) * {sr}.penalty_miss
+          + COALESCE({pp}.regular_fouls, 0) * {sr}.regular_foul
+          + COALESCE({pp}.serious_fouls, 0) * {sr}.serious_foul
         )"#
     )
 }
 
+/// The scoring rule set in force for a match week.
+///
+/// Binds `$1` = match week id. Selects every `ScoringRules` column.
+pub fn rules_for_week() -> &'static str {
+    r#"SELECT id, name, season_id, goal_gk, goal_def, goal_mid, goal_fwd,
+              assist, clean_sheet_gk, clean_sheet_def, clean_sheet_mid, clean_sheet_fwd,
+              saves_per_point, penalty_save, long_appearance_minutes, long_appearance,
+              short_appearance, own_goal, penalty_miss, regular_foul, serious_foul,
+              created_at
+       FROM match_week_scoring_rules
+       WHERE match_week_id = $1"#
+}
+
+/// SQL expression for the player wearing the armband in a gameweek, the SQL
+/// counterpart to [`super::points_engine::PointsEngine::armband`].
+///
+/// Each argument is an SQL expression: the captain and vice-captain ids, the
+/// captain's minutes that week, and the season's `vice_captain_fallback`.
+fn armband(captain: &str, vice_captain: &str, captain_minutes: &str, fallback: &str) -> String {
+    format!(
+        r#"(CASE
+             WHEN {fallback} AND {vice_captain} IS NOT NULL AND {captain_minutes} = 0
+               THEN {vice_captain}
+             ELSE {captain}
+           END)"#
+    )
+}
+
 /// SQL expression for the captain multiplier on a single gameweek: 2x as
-/// captain, 3x when Triple Captain was played that week, otherwise 1x.
+/// captain, 3x when Triple Captain was played that week, 2x for the second
+/// captain under Double Captain, otherwise 1x.
 ///
 /// Resolves the captain the way scoring does — that gameweek's lineup snapshot
-/// first, falling back to the team's current captain when the snapshot has none.
-/// Expects `tgl`, `ft`, `p` and `pp` in scope.
-fn captain_multiplier() -> &'static str {
-    r#"CASE
-         WHEN COALESCE(tgl.captain_id, ft.captain_id) = p.id THEN
+/// first, falling back to the team's current captain when the snapshot has none —
+/// and hands the armband to the vice-captain when the season's rule says so.
+/// Expects `tgl`, `ft`, `mw`, `p` and `pp` in scope.
+fn captain_multiplier() -> String {
+    let captain = "COALESCE(tgl.captain_id, ft.captain_id)";
+    let armband = armband(
+        captain,
+        "COALESCE(tgl.vice_captain_id, ft.vice_captain_id)",
+        &format!(
+            r#"COALESCE((
+                 SELECT cpp.minutes_played FROM player_points cpp
+                 WHERE cpp.player_id = {captain} AND cpp.match_week_id = pp.match_week_id
+               ), 0)"#
+        ),
+        "(SELECT s.vice_captain_fallback FROM seasons s WHERE s.id = mw.season_id)",
+    );
+    format!(
+        r#"CASE
+         WHEN {armband} = p.id THEN
            CASE WHEN EXISTS (
              SELECT 1 FROM team_chips tc
              WHERE tc.team_id = ft.id
                AND tc.match_week_id = pp.match_week_id
                AND tc.chip_type = 'triple_captain'
            ) THEN 3 ELSE 2 END
+         WHEN p.id = (
+           SELECT tc.second_captain_id FROM team_chips tc
+           WHERE tc.team_id = ft.id
+             AND tc.match_week_id = pp.match_week_id
+             AND tc.chip_type = 'double_captain'
+         ) THEN 2
          ELSE 1
        END"#
+    )
 }
 
 /// Which squad table a gameweek is scored from.
@@ -97,12 +154,14 @@ impl Source {
             Source::Snapshot => {
                 r#"FROM team_gameweek_lineup_players tglp
                    JOIN players p ON p.id = tglp.player_id
+                   JOIN match_week_scoring_rules sr ON sr.match_week_id = $2
                    LEFT JOIN player_points pp ON pp.player_id = tglp.player_id AND pp.match_week_id = $2
                    WHERE tglp.team_gameweek_lineup_id = $1"#
             }
             Source::LiveSquad => {
                 r#"FROM team_players tp
                    JOIN players p ON p.id = tp.player_id
+                   JOIN match_week_scoring_rules sr ON sr.match_week_id = $2
                    LEFT JOIN player_points pp ON pp.player_id = tp.player_id AND pp.match_week_id = $2
                    WHERE tp.team_id = $1"#
             }
@@ -117,7 +176,18 @@ pub fn squad_half_total(source: Source, is_bench: bool) -> String {
     let alias = source.alias();
     format!(
         "SELECT COALESCE(SUM({points}), 0) {from} AND {alias}.is_bench = {is_bench}",
-        points = week_points(&scoring_position(alias), "pp"),
+        points = week_points(&scoring_position(alias), "pp", "sr"),
+        from = source.squad_join(),
+    )
+}
+
+/// Goals scored in a gameweek by one half of a squad, a cup tiebreaker.
+///
+/// Binds `$1` = lineup or team id, `$2` = match week id. Returns `bigint`.
+pub fn squad_half_goals(source: Source, is_bench: bool) -> String {
+    let alias = source.alias();
+    format!(
+        "SELECT COALESCE(SUM(COALESCE(pp.goals, 0)), 0) {from} AND {alias}.is_bench = {is_bench}",
         from = source.squad_join(),
     )
 }
@@ -130,7 +200,7 @@ pub fn single_starter_total(source: Source) -> String {
     let alias = source.alias();
     format!(
         "SELECT COALESCE({points}, 0) {from} AND {alias}.is_bench = false AND {alias}.player_id = $3",
-        points = week_points(&scoring_position(alias), "pp"),
+        points = week_points(&scoring_position(alias), "pp", "sr"),
         from = source.squad_join(),
     )
 }
@@ -147,7 +217,9 @@ pub fn single_starter_total(source: Source) -> String {
 pub fn scored_teams() -> &'static str {
     r#"SELECT ft.id,
               tgl.id AS lineup_id,
-              COALESCE(tgl.captain_id, ft.captain_id) AS captain_id
+              COALESCE(tgl.captain_id, ft.captain_id) AS captain_id,
+              COALESCE(tgl.vice_captain_id, ft.vice_captain_id) AS vice_captain_id,
+              COALESCE(tgl.transfer_hits_waived, false) AS transfer_hits_waived
        FROM fantasy_teams ft
        LEFT JOIN team_gameweek_lineups tgl
          ON tgl.team_id = ft.id AND tgl.match_week_id = $1
@@ -158,7 +230,7 @@ pub fn scored_teams() -> &'static str {
 /// Season points each squad member earned *for a given team*, summed per gameweek
 /// so both the assigned role and that week's captaincy apply.
 ///
-/// Binds `$1` = team id. Selects the player columns plus `assigned_position` and
+/// Binds `$1` = team id, `$2` = season id. Selects the player columns plus `assigned_position` and
 /// `total_points`. The captain multiplier is inert for bench players, since a
 /// captain must be a starter.
 pub fn squad_season_points(is_bench: bool) -> String {
@@ -169,15 +241,17 @@ pub fn squad_season_points(is_bench: bool) -> String {
                   COALESCE((
                     SELECT SUM({points} * {captain})
                     FROM player_points pp
+                    JOIN match_weeks mw ON mw.id = pp.match_week_id
+                    JOIN match_week_scoring_rules sr ON sr.match_week_id = pp.match_week_id
                     LEFT JOIN team_gameweek_lineups tgl
                       ON tgl.team_id = ft.id AND tgl.match_week_id = pp.match_week_id
-                    WHERE pp.player_id = p.id
+                    WHERE pp.player_id = p.id AND mw.season_id = $2
                   ), 0)::int AS total_points
            FROM players p
            INNER JOIN team_players tp ON p.id = tp.player_id
            INNER JOIN fantasy_teams ft ON ft.id = tp.team_id
            WHERE tp.team_id = $1 AND tp.is_bench = {is_bench}"#,
-        points = week_points(&scoring_position("tp"), "pp"),
+        points = week_points(&scoring_position("tp"), "pp", "sr"),
         captain = captain_multiplier(),
     )
 }
@@ -186,48 +260,89 @@ pub fn squad_season_points(is_bench: bool) -> String {
 mod tests {
     use super::*;
     use crate::models::PlayerPosition;
-    use crate::services::points_engine::PointsEngine;
-
-    /// Stats for one gameweek, in the order `PointsEngine::calculate` takes them.
-    struct Stats {
-        goals: i32,
-        assists: i32,
-        clean_sheets: i32,
-        saves: i32,
-        penalty_saves: i32,
-        own_goals: i32,
-        penalty_misses: i32,
-        regular_fouls: i32,
-        serious_fouls: i32,
-        minutes_played: i32,
-    }
+    use crate::models::ScoringRules;
+    use crate::services::points_engine::{MatchStats as Stats, PointsEngine};
 
     fn cases() -> Vec<Stats> {
         vec![
             // Blank week.
-            Stats { goals: 0, assists: 0, clean_sheets: 0, saves: 0, penalty_saves: 0,
-                    own_goals: 0, penalty_misses: 0, regular_fouls: 0, serious_fouls: 0,
-                    minutes_played: 0 },
+            Stats {
+                goals: 0,
+                assists: 0,
+                clean_sheets: 0,
+                saves: 0,
+                penalty_saves: 0,
+                own_goals: 0,
+                penalty_misses: 0,
+                regular_fouls: 0,
+                serious_fouls: 0,
+                minutes_played: 0,
+            },
             // Saves that do not divide evenly by 5, plus a penalty save.
-            Stats { goals: 1, assists: 0, clean_sheets: 1, saves: 12, penalty_saves: 1,
-                    own_goals: 0, penalty_misses: 0, regular_fouls: 0, serious_fouls: 0,
-                    minutes_played: 60 },
+            Stats {
+                goals: 1,
+                assists: 0,
+                clean_sheets: 1,
+                saves: 12,
+                penalty_saves: 1,
+                own_goals: 0,
+                penalty_misses: 0,
+                regular_fouls: 0,
+                serious_fouls: 0,
+                minutes_played: 60,
+            },
             // Fewer than 5 saves should round down to zero.
-            Stats { goals: 2, assists: 1, clean_sheets: 1, saves: 3, penalty_saves: 0,
-                    own_goals: 0, penalty_misses: 0, regular_fouls: 1, serious_fouls: 0,
-                    minutes_played: 40 },
+            Stats {
+                goals: 2,
+                assists: 1,
+                clean_sheets: 1,
+                saves: 3,
+                penalty_saves: 0,
+                own_goals: 0,
+                penalty_misses: 0,
+                regular_fouls: 1,
+                serious_fouls: 0,
+                minutes_played: 40,
+            },
             // Short appearance: 1 point, not 2.
-            Stats { goals: 1, assists: 2, clean_sheets: 1, saves: 0, penalty_saves: 0,
-                    own_goals: 0, penalty_misses: 0, regular_fouls: 0, serious_fouls: 0,
-                    minutes_played: 20 },
+            Stats {
+                goals: 1,
+                assists: 2,
+                clean_sheets: 1,
+                saves: 0,
+                penalty_saves: 0,
+                own_goals: 0,
+                penalty_misses: 0,
+                regular_fouls: 0,
+                serious_fouls: 0,
+                minutes_played: 20,
+            },
             // Exactly on the 35-minute boundary.
-            Stats { goals: 0, assists: 0, clean_sheets: 0, saves: 0, penalty_saves: 0,
-                    own_goals: 0, penalty_misses: 0, regular_fouls: 0, serious_fouls: 0,
-                    minutes_played: 35 },
+            Stats {
+                goals: 0,
+                assists: 0,
+                clean_sheets: 0,
+                saves: 0,
+                penalty_saves: 0,
+                own_goals: 0,
+                penalty_misses: 0,
+                regular_fouls: 0,
+                serious_fouls: 0,
+                minutes_played: 35,
+            },
             // Every deduction at once, enough to go negative.
-            Stats { goals: 0, assists: 0, clean_sheets: 0, saves: 0, penalty_saves: 0,
-                    own_goals: 1, penalty_misses: 1, regular_fouls: 2, serious_fouls: 1,
-                    minutes_played: 60 },
+            Stats {
+                goals: 0,
+                assists: 0,
+                clean_sheets: 0,
+                saves: 0,
+                penalty_saves: 0,
+                own_goals: 1,
+                penalty_misses: 1,
+                regular_fouls: 2,
+                serious_fouls: 1,
+                minutes_played: 60,
+            },
         ]
     }
 
@@ -248,60 +363,134 @@ mod tests {
     /// The SQL and the Rust engine must never disagree. Two implementations of the
     /// same rules will drift otherwise, and the stored per-gameweek totals come
     /// from the Rust side while every team-scoped query uses the SQL side.
+    ///
+    /// Runs against every stored rule set, plus one using the rule book's
+    /// clean-sheet rates so a non-default version is always covered.
     #[tokio::test]
     async fn sql_matches_rust_engine() {
         let Some(pool) = pool().await else {
             eprintln!("skipping: DATABASE_URL not set or unreachable");
             return;
         };
+        let mut tx = pool.begin().await.expect("begin");
 
-        for (pos_text, position) in all_positions() {
-            for stats in cases() {
-                let sql = format!(
-                    "SELECT {expr} FROM (VALUES ($1::int, $2::int, $3::int, $4::int, $5::int, \
-                     $6::int, $7::int, $8::int, $9::int, $10::int)) AS pp(goals, assists, \
-                     clean_sheets, saves, penalty_saves, own_goals, penalty_misses, \
-                     regular_fouls, serious_fouls, minutes_played)",
-                    expr = week_points(&format!("'{pos_text}'"), "pp"),
-                );
-
-                let from_sql: i32 = sqlx::query_scalar(&sql)
-                    .bind(stats.goals)
-                    .bind(stats.assists)
-                    .bind(stats.clean_sheets)
-                    .bind(stats.saves)
-                    .bind(stats.penalty_saves)
-                    .bind(stats.own_goals)
-                    .bind(stats.penalty_misses)
-                    .bind(stats.regular_fouls)
-                    .bind(stats.serious_fouls)
-                    .bind(stats.minutes_played)
-                    .fetch_one(&pool)
-                    .await
-                    .expect("points SQL should execute");
-
-                let from_rust = PointsEngine::calculate(
-                    &position,
-                    stats.goals,
-                    stats.assists,
-                    stats.clean_sheets,
-                    stats.saves,
-                    stats.penalty_saves,
-                    stats.own_goals,
-                    stats.penalty_misses,
-                    stats.regular_fouls,
-                    stats.serious_fouls,
-                    stats.minutes_played,
-                );
-
-                assert_eq!(
-                    from_sql, from_rust,
-                    "{pos_text} disagreed: SQL gave {from_sql}, engine gave {from_rust} \
-                     for {}g {}a {}cs {}sv {}ps {}og {}pm {}rf {}sf {}min",
-                    stats.goals, stats.assists, stats.clean_sheets, stats.saves,
-                    stats.penalty_saves, stats.own_goals, stats.penalty_misses,
-                    stats.regular_fouls, stats.serious_fouls, stats.minutes_played,
-                );
+        let probe_season: uuid::Uuid = sqlx::query_scalar(
+            "INSERT INTO seasons (name, year, start_week, closed_at)
+             VALUES ('Summer', 100000, 100000, NOW())
+             RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .expect("insert probe season");
+
+        sqlx::query(
+            "INSERT INTO scoring_rules (
+                 name, season_id, goal_gk, goal_def, goal_mid, goal_fwd, assist,
+                 clean_sheet_gk, clean_sheet_def, clean_sheet_mid, clean_sheet_fwd,
+                 saves_per_point, penalty_save, long_appearance_minutes, long_appearance,
+                 short_appearance, own_goal, penalty_miss, regular_foul, serious_foul)
+             VALUES ('Rule book probe', $1, 10, 6, 5, 4, 5, 10, 6, 0, 0,
+                     4, 8, 30, 3, 1, -2, -2, -1, -3)",
+        )
+        .bind(probe_season)
+        .execute(&mut *tx)
+        .await
+        .expect("insert probe rule set");
+
+        let rule_sets: Vec<ScoringRules> = sqlx::query_as("SELECT * FROM scoring_rules")
+            .fetch_all(&mut *tx)
+            .await
+            .expect("load rule sets");
+        assert!(
+            rule_sets.len() >= 2,
+            "seeded and probe rule sets must both load"
+        );
+
+        for rules in &rule_sets {
+            for (pos_text, position) in all_positions() {
+                for stats in cases() {
+                    let sql = format!(
+                        "SELECT {expr} FROM (VALUES ($1::int, $2::int, $3::int, $4::int, \
+                         $5::int, $6::int, $7::int, $8::int, $9::int, $10::int)) AS pp(goals, \
+                         assists, clean_sheets, saves, penalty_saves, own_goals, penalty_misses, \
+                         regular_fouls, serious_fouls, minutes_played), scoring_rules sr \
+                         WHERE sr.id = $11",
+                        expr = week_points(&format!("'{pos_text}'"), "pp", "sr"),
+                    );
+
+                    let from_sql: i32 = sqlx::query_scalar(&sql)
+                        .bind(stats.goals)
+                        .bind(stats.assists)
+                        .bind(stats.clean_sheets)
+                        .bind(stats.saves)
+                        .bind(stats.penalty_saves)
+                        .bind(stats.own_goals)
+                        .bind(stats.penalty_misses)
+                        .bind(stats.regular_fouls)
+                        .bind(stats.serious_fouls)
+                        .bind(stats.minutes_played)
+                        .bind(rules.id)
+                        .fetch_one(&mut *tx)
+                        .await
+                        .expect("points SQL should execute");
+
+                    let from_rust = PointsEngine::calculate(rules, &position, &stats);
+
+                    assert_eq!(
+                        from_sql,
+                        from_rust,
+                        "{pos_text} disagreed under '{}': SQL gave {from_sql}, engine gave \
+                         {from_rust} for {}g {}a {}cs {}sv {}ps {}og {}pm {}rf {}sf {}min",
+                        rules.name,
+                        stats.goals,
+                        stats.assists,
+                        stats.clean_sheets,
+                        stats.saves,
+                        stats.penalty_saves,
+                        stats.own_goals,
+                        stats.penalty_misses,
+                        stats.regular_fouls,
+                        stats.serious_fouls,
+                        stats.minutes_played,
+                    );
+                }
+            }
+        }
+
+        tx.rollback().await.expect("rollback");
+    }
+
+    /// The vice-captain fallback is decided in both places too: the engine when a
+    /// gameweek is scored, the SQL when a squad's season breakdown is shown.
+    #[tokio::test]
+    async fn armband_matches_rust_engine() {
+        let Some(pool) = pool().await else {
+            eprintln!("skipping: DATABASE_URL not set or unreachable");
+            return;
+        };
+
+        let sql = format!(
+            "SELECT {}",
+            armband("$1::uuid", "$2::uuid", "$3::int", "$4::bool")
+        );
+        let captain = Some(uuid::Uuid::from_u128(1));
+        for vice_captain in [None, Some(uuid::Uuid::from_u128(2))] {
+            for minutes in [0, 1, 60] {
+                for fallback in [false, true] {
+                    let from_sql: Option<uuid::Uuid> = sqlx::query_scalar(&sql)
+                        .bind(captain)
+                        .bind(vice_captain)
+                        .bind(minutes)
+                        .bind(fallback)
+                        .fetch_one(&pool)
+                        .await
+                        .expect("armband SQL should execute");
+                    let from_rust = PointsEngine::armband(captain, vice_captain, minutes, fallback);
+                    assert_eq!(
+                        from_sql, from_rust,
+                        "armband disagreed for vice {vice_captain:?}, {minutes}min, fallback {fallback}"
+                    );
+                }
             }
         }
     }
@@ -410,13 +599,12 @@ mod tests {
         }
 
         // Recompute the stored total exactly as `submit_week_stats` does.
-        let starter_base: i64 =
-            sqlx::query_scalar(&squad_half_total(Source::LiveSquad, false))
-                .bind(team_id)
-                .bind(week_id)
-                .fetch_one(&mut *tx)
-                .await
-                .expect("starter base");
+        let starter_base: i64 = sqlx::query_scalar(&squad_half_total(Source::LiveSquad, false))
+            .bind(team_id)
+            .bind(week_id)
+            .fetch_one(&mut *tx)
+            .await
+            .expect("starter base");
         let captain_points: i32 = sqlx::query_scalar(&single_starter_total(Source::LiveSquad))
             .bind(team_id)
             .bind(week_id)
@@ -428,11 +616,18 @@ mod tests {
         let gross = starter_base + captain_points as i64;
 
         // Sum what each starter is shown as having contributed.
+        let season_id: uuid::Uuid =
+            sqlx::query_scalar("SELECT season_id FROM match_weeks WHERE id = $1")
+                .bind(week_id)
+                .fetch_one(&mut *tx)
+                .await
+                .expect("week season");
         let breakdown: i64 = sqlx::query_scalar(&format!(
             "SELECT COALESCE(SUM(total_points), 0)::bigint FROM ({}) AS s",
             squad_season_points(false)
         ))
         .bind(team_id)
+        .bind(season_id)
         .fetch_one(&mut *tx)
         .await
         .expect("breakdown");
@@ -444,6 +639,42 @@ mod tests {
              ({gross}); starter base {starter_base}, captain bonus {captain_points}"
         );
 
+        // Double Captain counts a second starter twice as well.
+        let second_captain = outfield[2];
+        let chip_type: crate::models::ChipType = sqlx::query_scalar(
+            "INSERT INTO team_chips (team_id, chip_type, match_week_id, season_id, second_captain_id)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING chip_type",
+        )
+        .bind(team_id)
+        .bind(crate::models::ChipType::DoubleCaptain)
+        .bind(week_id)
+        .bind(season_id)
+        .bind(second_captain)
+        .fetch_one(&mut *tx)
+        .await
+        .expect("insert chip");
+        assert_eq!(chip_type, crate::models::ChipType::DoubleCaptain);
+
+        let second_points: i32 = sqlx::query_scalar(&single_starter_total(Source::LiveSquad))
+            .bind(team_id)
+            .bind(week_id)
+            .bind(second_captain)
+            .fetch_optional(&mut *tx)
+            .await
+            .expect("second captain points")
+            .unwrap_or(0);
+        let breakdown: i64 = sqlx::query_scalar(&format!(
+            "SELECT COALESCE(SUM(total_points), 0)::bigint FROM ({}) AS s",
+            squad_season_points(false)
+        ))
+        .bind(team_id)
+        .bind(season_id)
+        .fetch_one(&mut *tx)
+        .await
+        .expect("breakdown");
+        assert_eq!(breakdown, gross + second_points as i64);
+
         tx.rollback().await.expect("rollback");
     }
 
@@ -502,24 +733,20 @@ mod tests {
 
         // The snapshotted team joined late too, but played the week, so its snapshot
         // must override the date test.
-        sqlx::query(
-            "INSERT INTO team_gameweek_lineups (team_id, match_week_id) VALUES ($1, $2)",
-        )
-        .bind(snapshotted)
-        .bind(week_id)
-        .execute(&mut *tx)
-        .await
-        .expect("insert snapshot");
+        sqlx::query("INSERT INTO team_gameweek_lineups (team_id, match_week_id) VALUES ($1, $2)")
+            .bind(snapshotted)
+            .bind(week_id)
+            .execute(&mut *tx)
+            .await
+            .expect("insert snapshot");
 
-        let scored: Vec<uuid::Uuid> = sqlx::query_scalar(&format!(
-            "SELECT id FROM ({}) AS t",
-            scored_teams()
-        ))
-        .bind(week_id)
-        .bind(end_date)
-        .fetch_all(&mut *tx)
-        .await
-        .expect("scored teams");
+        let scored: Vec<uuid::Uuid> =
+            sqlx::query_scalar(&format!("SELECT id FROM ({}) AS t", scored_teams()))
+                .bind(week_id)
+                .bind(end_date)
+                .fetch_all(&mut *tx)
+                .await
+                .expect("scored teams");
 
         assert!(
             scored.contains(&snapshotted),
@@ -573,6 +800,7 @@ mod tests {
         for is_bench in [false, true] {
             let sql = squad_season_points(is_bench);
             sqlx::query(&sql)
+                .bind(id)
                 .bind(id)
                 .fetch_all(&pool)
                 .await
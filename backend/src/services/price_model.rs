@@ -0,0 +1,292 @@
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::models::{PriceModelKind, PricePoint};
+
+/// One player's gameweek, as seen by a price model.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PlayerWeek {
+    pub player_id: Uuid,
+    pub name: String,
+    /// Fantasy points scored this gameweek; 0 if the player has no stats.
+    pub week_points: i32,
+    /// Transfers into and out of squads made during this gameweek.
+    pub transfers_in: i64,
+    pub transfers_out: i64,
+}
+
+impl PlayerWeek {
+    pub fn net_transfers(&self) -> i64 {
+        self.transfers_in - self.transfers_out
+    }
+}
+
+/// Decides how much each player's price moves after a gameweek.
+///
+/// Returns the intended delta per player; players left out do not move. The
+/// caller applies the price floor and records what actually changed.
+pub trait PriceModel {
+    fn deltas(&self, players: &[PlayerWeek]) -> Vec<(Uuid, Decimal)>;
+}
+
+impl PriceModelKind {
+    /// The model this kind names.
+    pub fn model(self) -> Box<dyn PriceModel + Send + Sync> {
+        match self {
+            PriceModelKind::Points => Box::new(PointsModel),
+            PriceModelKind::NetTransfers => Box::new(NetTransfersModel),
+        }
+    }
+}
+
+/// Rises for first, second and third place; falls are the same, negated.
+fn step_deltas() -> [Decimal; 3] {
+    [Decimal::new(3, 1), Decimal::new(2, 1), Decimal::new(1, 1)]
+}
+
+/// The original rule: +0.3/+0.2/+0.1 for the week's top three scorers and
+/// -0.3/-0.2/-0.1 for the bottom three, ties broken by name.
+pub struct PointsModel;
+
+impl PriceModel for PointsModel {
+    fn deltas(&self, players: &[PlayerWeek]) -> Vec<(Uuid, Decimal)> {
+        let mut ordered: Vec<&PlayerWeek> = players.iter().collect();
+        ordered.sort_by(|a, b| {
+            b.week_points
+                .cmp(&a.week_points)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let steps = step_deltas();
+        let top: Vec<Uuid> = ordered
+            .iter()
+            .take(steps.len())
+            .map(|p| p.player_id)
+            .collect();
+        let bottom = ordered
+            .iter()
+            .rev()
+            .map(|p| p.player_id)
+            .filter(|id| !top.contains(id))
+            .take(steps.len());
+
+        let mut deltas: Vec<(Uuid, Decimal)> = top.iter().copied().zip(steps).collect();
+        deltas.extend(bottom.zip(steps.map(|d| -d)));
+        deltas
+    }
+}
+
+/// Supply and demand: +0.3/+0.2/+0.1 for the three players with the highest
+/// net transfers in, -0.3/-0.2/-0.1 for the three with the highest net out.
+/// Players nobody moved towards or away from keep their price.
+pub struct NetTransfersModel;
+
+impl PriceModel for NetTransfersModel {
+    fn deltas(&self, players: &[PlayerWeek]) -> Vec<(Uuid, Decimal)> {
+        let steps = step_deltas();
+
+        let mut risers: Vec<&PlayerWeek> =
+            players.iter().filter(|p| p.net_transfers() > 0).collect();
+        risers.sort_by(|a, b| {
+            b.net_transfers()
+                .cmp(&a.net_transfers())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let mut fallers: Vec<&PlayerWeek> =
+            players.iter().filter(|p| p.net_transfers() < 0).collect();
+        fallers.sort_by(|a, b| {
+            a.net_transfers()
+                .cmp(&b.net_transfers())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let mut deltas: Vec<(Uuid, Decimal)> =
+            risers.iter().map(|p| p.player_id).zip(steps).collect();
+        deltas.extend(fallers.iter().map(|p| p.player_id).zip(steps.map(|d| -d)));
+        deltas
+    }
+}
+
+/// Fill in the price after each change, working back from `current_price`, and
+/// return the price before the first one. `history` must be in the order the
+/// changes happened.
+pub fn rebuild_prices(current_price: Decimal, history: &mut [PricePoint]) -> Decimal {
+    let mut price = current_price;
+    for point in history.iter_mut().rev() {
+        point.price = price;
+        price -= point.delta;
+    }
+    price
+}
+
+/// Season price change by quartile of season points, best quartile first.
+fn season_price_deltas() -> [Decimal; 4] {
+    [
+        Decimal::new(10, 1),
+        Decimal::new(5, 1),
+        Decimal::ZERO,
+        Decimal::new(-5, 1),
+    ]
+}
+
+/// New prices at the end of a season, one per `(price, season_points)` entry.
+///
+/// `players` must be ordered by season points, best first. Players on equal
+/// points share the quartile of their group's middle position, so a tie is
+/// never split by name; no price drops below `floor`.
+pub fn season_prices(players: &[(Decimal, i32)], floor: Decimal) -> Vec<Decimal> {
+    let deltas = season_price_deltas();
+    let count = players.len();
+    let mut prices = Vec::with_capacity(count);
+    for group in players.chunk_by(|a, b| a.1 == b.1) {
+        let middle = prices.len() + (group.len() - 1) / 2;
+        let delta = deltas[middle * deltas.len() / count];
+        prices.extend(group.iter().map(|(price, _)| (*price + delta).max(floor)));
+    }
+    prices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(name: &str, week_points: i32, transfers_in: i64, transfers_out: i64) -> PlayerWeek {
+        PlayerWeek {
+            player_id: Uuid::new_v4(),
+            name: name.to_string(),
+            week_points,
+            transfers_in,
+            transfers_out,
+        }
+    }
+
+    fn delta_for(deltas: &[(Uuid, Decimal)], player: &PlayerWeek) -> Option<Decimal> {
+        deltas
+            .iter()
+            .find(|(id, _)| *id == player.player_id)
+            .map(|(_, d)| *d)
+    }
+
+    #[test]
+    fn points_model_moves_top_and_bottom_three() {
+        let players: Vec<PlayerWeek> = (0..8)
+            .map(|i| player(&format!("P{i}"), i * 2, 0, 0))
+            .collect();
+        let deltas = PointsModel.deltas(&players);
+
+        assert_eq!(deltas.len(), 6);
+        assert_eq!(delta_for(&deltas, &players[7]), Some(Decimal::new(3, 1)));
+        assert_eq!(delta_for(&deltas, &players[5]), Some(Decimal::new(1, 1)));
+        assert_eq!(delta_for(&deltas, &players[0]), Some(Decimal::new(-3, 1)));
+        assert_eq!(delta_for(&deltas, &players[2]), Some(Decimal::new(-1, 1)));
+        assert_eq!(delta_for(&deltas, &players[3]), None);
+    }
+
+    #[test]
+    fn points_model_never_moves_a_player_both_ways() {
+        let players: Vec<PlayerWeek> = (0..4).map(|i| player(&format!("P{i}"), i, 0, 0)).collect();
+        let deltas = PointsModel.deltas(&players);
+
+        assert_eq!(deltas.len(), 4);
+        assert_eq!(delta_for(&deltas, &players[0]), Some(Decimal::new(-3, 1)));
+        assert_eq!(delta_for(&deltas, &players[1]), Some(Decimal::new(1, 1)));
+    }
+
+    #[test]
+    fn points_ties_break_by_name() {
+        let players = vec![player("B", 5, 0, 0), player("A", 5, 0, 0)];
+        let deltas = PointsModel.deltas(&players);
+        assert_eq!(delta_for(&deltas, &players[1]), Some(Decimal::new(3, 1)));
+        assert_eq!(delta_for(&deltas, &players[0]), Some(Decimal::new(2, 1)));
+    }
+
+    #[test]
+    fn net_transfers_model_follows_demand() {
+        let players = vec![
+            player("Popular", 0, 9, 1),
+            player("Liked", 10, 3, 0),
+            player("Unloved", 20, 0, 4),
+            player("Ignored", 30, 2, 2),
+        ];
+        let deltas = NetTransfersModel.deltas(&players);
+
+        assert_eq!(delta_for(&deltas, &players[0]), Some(Decimal::new(3, 1)));
+        assert_eq!(delta_for(&deltas, &players[1]), Some(Decimal::new(2, 1)));
+        assert_eq!(delta_for(&deltas, &players[2]), Some(Decimal::new(-3, 1)));
+        assert_eq!(delta_for(&deltas, &players[3]), None);
+    }
+
+    #[test]
+    fn prices_rebuild_backwards_from_today() {
+        use crate::models::PriceChangeReason;
+
+        let point = |week_number, reason, delta| PricePoint {
+            week_number,
+            reason,
+            delta: Decimal::new(delta, 1),
+            price: Decimal::ZERO,
+        };
+        let mut history = vec![
+            point(1, PriceChangeReason::Gameweek, 3),
+            point(2, PriceChangeReason::Gameweek, 0),
+            point(2, PriceChangeReason::SeasonRollover, -5),
+            point(3, PriceChangeReason::Gameweek, -1),
+        ];
+
+        let start = rebuild_prices(Decimal::new(72, 1), &mut history);
+        assert_eq!(start, Decimal::new(75, 1));
+        let prices: Vec<Decimal> = history.iter().map(|p| p.price).collect();
+        assert_eq!(
+            prices,
+            [78, 78, 73, 72].map(|p| Decimal::new(p, 1)).to_vec()
+        );
+    }
+
+    #[test]
+    fn quiet_week_moves_nobody() {
+        let players = vec![player("A", 10, 0, 0), player("B", 0, 0, 0)];
+        assert!(NetTransfersModel.deltas(&players).is_empty());
+    }
+
+    fn season_points(points: &[i32]) -> Vec<(Decimal, i32)> {
+        points.iter().map(|p| (Decimal::new(50, 1), *p)).collect()
+    }
+
+    #[test]
+    fn season_tie_across_a_quartile_boundary_moves_together() {
+        // Positions 2-5 tie; their middle (3) sits in the second quartile.
+        let players = season_points(&[90, 80, 50, 50, 50, 50, 20, 10]);
+        let prices = season_prices(&players, Decimal::new(1, 1));
+        let tied = &prices[2..6];
+        assert!(tied.iter().all(|p| *p == Decimal::new(55, 1)));
+        assert_eq!(prices[0], Decimal::new(60, 1));
+        assert_eq!(prices[7], Decimal::new(45, 1));
+    }
+
+    #[test]
+    fn season_prices_with_fewer_than_four_players() {
+        assert!(season_prices(&[], Decimal::new(1, 1)).is_empty());
+        let prices = season_prices(&season_points(&[30, 20, 10]), Decimal::new(1, 1));
+        assert_eq!(
+            prices,
+            vec![
+                Decimal::new(60, 1),
+                Decimal::new(55, 1),
+                Decimal::new(50, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn season_prices_never_fall_below_the_floor() {
+        let players = vec![
+            (Decimal::new(50, 1), 40),
+            (Decimal::new(50, 1), 30),
+            (Decimal::new(50, 1), 20),
+            (Decimal::new(3, 1), 0),
+        ];
+        let prices = season_prices(&players, Decimal::new(1, 1));
+        assert_eq!(prices[3], Decimal::new(1, 1));
+    }
+}
@@ -1,5 +1,8 @@
 use sqlx::PgPool;
 
+/// (name, primary_position, secondary_position or None, team, price, is_top_player)
+type SeedPlayer<'a> = (&'a str, &'a str, Option<&'a str>, &'a str, f64, bool);
+
 /// Seed the database with sample football players.
 ///
 /// This populates the players table with well-known football players
@@ -20,8 +23,7 @@ pub async fn seed_players(pool: &PgPool) -> Result<(), sqlx::Error> {
 
     tracing::info!("Seeding players...");
 
-    // (name, primary_position, secondary_position or None, team, price, is_top_player)
-    let players: Vec<(&str, &str, Option<&str>, &str, f64, bool)> = vec![
+    let players: Vec<SeedPlayer> = vec![
         // Goalkeepers
         ("Nitesh Das", "GK", Some("DEF"), "MRR Fantasy", 7.0, false),
         (
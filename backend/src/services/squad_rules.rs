@@ -0,0 +1,374 @@
+use std::collections::{HashMap, HashSet};
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use uuid::Uuid;
+
+use crate::models::{Player, PlayerPosition};
+use crate::services::auto_subs::formation_is_valid;
+
+/// One place in a team's squad, as stored in `team_players`.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SquadSlot {
+    pub player_id: Uuid,
+    pub is_bench: bool,
+    /// The role a starter plays. Bench players have none.
+    pub assigned_position: Option<PlayerPosition>,
+    pub bench_order: Option<i16>,
+    /// What the team paid; `None` for a player being bought at today's price.
+    pub purchase_price: Option<Decimal>,
+}
+
+/// What a team gets for selling a player: the purchase price plus half of any
+/// rise since, rounded down to 0.1, or the current price after a fall.
+pub fn selling_price(purchase_price: Decimal, current_price: Decimal) -> Decimal {
+    if current_price <= purchase_price {
+        return current_price;
+    }
+    let kept = ((current_price - purchase_price) / Decimal::TWO)
+        .round_dp_with_strategy(1, RoundingStrategy::ToZero);
+    purchase_price + kept
+}
+
+/// The profit (or loss, if negative) a sale adds to the team's budget.
+pub fn sale_profit(purchase_price: Decimal, current_price: Decimal) -> Decimal {
+    selling_price(purchase_price, current_price) - purchase_price
+}
+
+/// One swap in a batch: the incoming player takes the outgoing player's place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Swap {
+    pub player_out_id: Uuid,
+    pub player_in_id: Uuid,
+    /// Role for an incoming starter; defaults to the outgoing starter's role.
+    pub assigned_position: Option<PlayerPosition>,
+}
+
+/// The squad after every swap in a batch, or why the batch is malformed.
+///
+/// Swaps are applied together rather than in turn, so a player cannot be both
+/// transferred out and transferred in, and no player may appear twice.
+/// `positions` then reassigns starters' roles, including those not transferred.
+pub fn apply_swaps(
+    squad: &[SquadSlot],
+    swaps: &[Swap],
+    positions: &[(Uuid, PlayerPosition)],
+) -> Result<Vec<SquadSlot>, String> {
+    if swaps.is_empty() {
+        return Err("At least one transfer is required".to_string());
+    }
+
+    let current: HashSet<Uuid> = squad.iter().map(|s| s.player_id).collect();
+    let mut outgoing = HashSet::new();
+    let mut incoming = HashSet::new();
+    for swap in swaps {
+        if !current.contains(&swap.player_out_id) {
+            return Err(format!(
+                "Player {} is not in your squad, so cannot be transferred out",
+                swap.player_out_id
+            ));
+        }
+        if current.contains(&swap.player_in_id) {
+            return Err(format!(
+                "Player {} is already in your squad, so cannot be transferred in",
+                swap.player_in_id
+            ));
+        }
+        if !outgoing.insert(swap.player_out_id) || !incoming.insert(swap.player_in_id) {
+            return Err("Each player can appear in only one transfer".to_string());
+        }
+    }
+
+    let mut result: Vec<SquadSlot> = squad
+        .iter()
+        .map(
+            |slot| match swaps.iter().find(|s| s.player_out_id == slot.player_id) {
+                Some(swap) => SquadSlot {
+                    player_id: swap.player_in_id,
+                    is_bench: slot.is_bench,
+                    assigned_position: if slot.is_bench {
+                        None
+                    } else {
+                        swap.assigned_position
+                            .clone()
+                            .or_else(|| slot.assigned_position.clone())
+                    },
+                    bench_order: slot.bench_order,
+                    purchase_price: None,
+                },
+                None => slot.clone(),
+            },
+        )
+        .collect();
+
+    for (player_id, position) in positions {
+        let slot = result
+            .iter_mut()
+            .find(|s| s.player_id == *player_id)
+            .ok_or_else(|| format!("Player {player_id} will not be in your squad"))?;
+        if slot.is_bench {
+            return Err(format!(
+                "Player {player_id} is on the bench, so cannot be given a position"
+            ));
+        }
+        slot.assigned_position = Some(position.clone());
+    }
+
+    Ok(result)
+}
+
+/// Check a whole squad against the squad rules: 6 starters in a valid formation,
+/// each in a role they can play, a bench with exactly 1 GK, at most 2 top players
+/// and a total cost within `budget`. `players` must hold every squad member.
+///
+/// Players already owned cost their purchase price and new ones today's price, so
+/// `budget` is the team's `budget_limit` plus the profit on any players sold.
+pub fn validate_squad(
+    squad: &[SquadSlot],
+    players: &HashMap<Uuid, Player>,
+    budget: Decimal,
+) -> Result<(), String> {
+    let mut members = Vec::with_capacity(squad.len());
+    for slot in squad {
+        let player = players
+            .get(&slot.player_id)
+            .ok_or_else(|| format!("Player {} not found", slot.player_id))?;
+        members.push((slot, player));
+    }
+
+    let starters: Vec<_> = members.iter().filter(|(s, _)| !s.is_bench).collect();
+    if starters.len() != 6 || members.len() != 9 {
+        return Err("A squad must have exactly 6 starters and 3 bench players".to_string());
+    }
+
+    let mut roles = Vec::with_capacity(starters.len());
+    for (slot, player) in &starters {
+        let role = slot
+            .assigned_position
+            .clone()
+            .unwrap_or_else(|| player.position.clone());
+        let can_play = player.position == role || player.secondary_position.as_ref() == Some(&role);
+        if !can_play {
+            return Err(format!(
+                "{} cannot play as {:?}. Valid positions: {:?}{}",
+                player.name,
+                role,
+                player.position,
+                player
+                    .secondary_position
+                    .as_ref()
+                    .map_or(String::new(), |sp| format!(", {:?}", sp))
+            ));
+        }
+        roles.push(role);
+    }
+    if !formation_is_valid(&roles) {
+        return Err(
+            "Starting lineup must have exactly 1 GK and at least 1 each of DEF, MID and FWD"
+                .to_string(),
+        );
+    }
+
+    let bench_gks = members
+        .iter()
+        .filter(|(s, p)| s.is_bench && p.position == PlayerPosition::Gk)
+        .count();
+    if bench_gks != 1 {
+        return Err("Bench must include exactly 1 goalkeeper (GK)".to_string());
+    }
+
+    if members.iter().filter(|(_, p)| p.is_top_player).count() > 2 {
+        return Err(
+            "Maximum 2 top players allowed per team (starters + bench combined)".to_string(),
+        );
+    }
+
+    let total_cost: Decimal = members
+        .iter()
+        .map(|(s, p)| s.purchase_price.unwrap_or(p.price))
+        .sum();
+    if total_cost > budget {
+        return Err(format!(
+            "Team cost ${total_cost} exceeds your ${budget} budget"
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PlayerPosition::*;
+
+    fn player(position: PlayerPosition, price: i64, is_top_player: bool) -> Player {
+        Player {
+            id: Uuid::new_v4(),
+            name: "Player".to_string(),
+            position,
+            secondary_position: None,
+            is_top_player,
+            team_name: "MRR".to_string(),
+            photo_url: None,
+            price: Decimal::from(price),
+            total_points: 0,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// A valid squad: GK, DEF, DEF, MID, MID, FWD starting, GK, DEF, MID on the bench.
+    fn squad() -> (Vec<SquadSlot>, HashMap<Uuid, Player>) {
+        let starters = [Gk, Def, Def, Mid, Mid, Fwd];
+        let bench = [Gk, Def, Mid];
+        let mut slots = Vec::new();
+        let mut players = HashMap::new();
+        for position in starters {
+            let p = player(position.clone(), 7, false);
+            slots.push(SquadSlot {
+                player_id: p.id,
+                is_bench: false,
+                assigned_position: Some(position),
+                bench_order: None,
+                purchase_price: Some(p.price),
+            });
+            players.insert(p.id, p);
+        }
+        for (order, position) in (1i16..).zip(bench) {
+            let p = player(position, 7, false);
+            slots.push(SquadSlot {
+                player_id: p.id,
+                is_bench: true,
+                assigned_position: None,
+                bench_order: Some(order),
+                purchase_price: Some(p.price),
+            });
+            players.insert(p.id, p);
+        }
+        (slots, players)
+    }
+
+    fn swap(out: &SquadSlot, player_in: &Player) -> Swap {
+        Swap {
+            player_out_id: out.player_id,
+            player_in_id: player_in.id,
+            assigned_position: None,
+        }
+    }
+
+    #[test]
+    fn incoming_players_take_the_outgoing_slot() {
+        let (slots, _) = squad();
+        let fwd = player(Fwd, 7, false);
+        let bench_mid = player(Mid, 7, false);
+        let swaps = [swap(&slots[5], &fwd), swap(&slots[8], &bench_mid)];
+
+        let result = apply_swaps(&slots, &swaps, &[]).unwrap();
+        assert_eq!(result[5].player_id, fwd.id);
+        assert_eq!(result[5].assigned_position, Some(Fwd));
+        assert_eq!(result[8].player_id, bench_mid.id);
+        assert_eq!(result[8].bench_order, Some(3));
+        assert_eq!(result[8].assigned_position, None);
+    }
+
+    #[test]
+    fn malformed_batches_are_rejected() {
+        let (slots, _) = squad();
+        let a = player(Fwd, 7, false);
+
+        assert!(apply_swaps(&slots, &[], &[]).is_err());
+        // Out twice.
+        let b = player(Fwd, 7, false);
+        assert!(apply_swaps(&slots, &[swap(&slots[5], &a), swap(&slots[5], &b)], &[]).is_err());
+        // In twice.
+        assert!(apply_swaps(&slots, &[swap(&slots[4], &a), swap(&slots[5], &a)], &[]).is_err());
+        // In is already in the squad.
+        let existing = Swap {
+            player_out_id: slots[5].player_id,
+            player_in_id: slots[4].player_id,
+            assigned_position: None,
+        };
+        assert!(apply_swaps(&slots, &[existing], &[]).is_err());
+        // Position for a bench player.
+        assert!(apply_swaps(&slots, &[swap(&slots[5], &a)], &[(slots[7].player_id, Def)]).is_err());
+    }
+
+    #[test]
+    fn positions_can_reassign_untouched_starters() {
+        let (slots, mut players) = squad();
+        // Swap the only FWD for a MID, and move a DEF to FWD.
+        let mid = player(Mid, 7, false);
+        players
+            .get_mut(&slots[1].player_id)
+            .unwrap()
+            .secondary_position = Some(Fwd);
+        let mut s = swap(&slots[5], &mid);
+        s.assigned_position = Some(Mid);
+        players.insert(mid.id, mid);
+
+        let without = apply_swaps(&slots, &[s.clone()], &[]).unwrap();
+        assert!(validate_squad(&without, &players, Decimal::from(70)).is_err());
+
+        let with = apply_swaps(&slots, &[s], &[(slots[1].player_id, Fwd)]).unwrap();
+        assert_eq!(validate_squad(&with, &players, Decimal::from(70)), Ok(()));
+    }
+
+    #[test]
+    fn final_squad_is_checked_as_a_whole() {
+        let (slots, mut players) = squad();
+        assert_eq!(validate_squad(&slots, &players, Decimal::from(63)), Ok(()));
+
+        // Two expensive players in, two cheap ones out: each swap alone would be
+        // over budget, but together they fit.
+        let expensive = player(Def, 10, false);
+        let cheap_mid = player(Mid, 4, false);
+        let swaps = [swap(&slots[2], &expensive), swap(&slots[4], &cheap_mid)];
+        players.insert(expensive.id, expensive);
+        players.insert(cheap_mid.id, cheap_mid);
+        let result = apply_swaps(&slots, &swaps, &[]).unwrap();
+        assert_eq!(validate_squad(&result, &players, Decimal::from(63)), Ok(()));
+        assert!(validate_squad(&result, &players, Decimal::from(62)).is_err());
+    }
+
+    #[test]
+    fn owned_players_cost_their_purchase_price() {
+        let (mut slots, mut players) = squad();
+        // Prices drift up after purchase: the squad is still within budget.
+        for p in players.values_mut() {
+            p.price += Decimal::ONE;
+        }
+        assert_eq!(validate_squad(&slots, &players, Decimal::from(63)), Ok(()));
+
+        slots[0].purchase_price = None;
+        assert!(validate_squad(&slots, &players, Decimal::from(63)).is_err());
+    }
+
+    #[test]
+    fn selling_keeps_half_of_a_rise_rounded_down() {
+        let d = |s: &str| s.parse::<Decimal>().unwrap();
+        assert_eq!(selling_price(d("7.0"), d("7.3")), d("7.1"));
+        assert_eq!(selling_price(d("7.0"), d("7.4")), d("7.2"));
+        assert_eq!(selling_price(d("7.0"), d("7.1")), d("7.0"));
+        assert_eq!(selling_price(d("7.0"), d("7.0")), d("7.0"));
+        assert_eq!(selling_price(d("7.0"), d("6.5")), d("6.5"));
+        assert_eq!(sale_profit(d("7.0"), d("7.4")), d("0.2"));
+        assert_eq!(sale_profit(d("7.0"), d("6.5")), d("-0.5"));
+    }
+
+    #[test]
+    fn squad_rules() {
+        let (slots, players) = squad();
+
+        let mut three_tops = players.clone();
+        for slot in &slots[..3] {
+            three_tops.get_mut(&slot.player_id).unwrap().is_top_player = true;
+        }
+        assert!(validate_squad(&slots, &three_tops, Decimal::from(70)).is_err());
+
+        let mut no_bench_gk = players.clone();
+        no_bench_gk.get_mut(&slots[6].player_id).unwrap().position = Def;
+        assert!(validate_squad(&slots, &no_bench_gk, Decimal::from(70)).is_err());
+
+        let mut wrong_role = slots.clone();
+        wrong_role[3].assigned_position = Some(Fwd);
+        assert!(validate_squad(&wrong_role, &players, Decimal::from(70)).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::models::{LeaderboardPage, LeagueMemberStanding};
+
+/// Table order: more points, then fewer hits, then a higher best gameweek, then
+/// whoever joined first. The user id settles anything left so the order is total.
+///
+/// SQL `ORDER BY` over a row with `total_points`, `transfer_hits`,
+/// `best_gameweek`, `joined_at` and `user_id` columns.
+pub const TABLE_ORDER: &str =
+    "total_points DESC, transfer_hits, best_gameweek DESC, joined_at, user_id";
+
+/// The same order as the previous gameweek's table saw it.
+pub const PREVIOUS_TABLE_ORDER: &str =
+    "previous_total DESC, previous_hits, previous_best DESC, joined_at, user_id";
+
+/// [`TABLE_ORDER`] as columns that compare ascending, for keyset paging: a row
+/// comes after the cursor when its key is greater than the cursor's.
+pub const TABLE_KEY: &str = "-total_points, transfer_hits, -best_gameweek, joined_at, user_id";
+
+/// Trim a page fetched with one row more than `limit`, and return the cursor
+/// for the next page: the last id kept, or `None` if nothing was left over.
+pub fn finish_page<T>(rows: &mut Vec<T>, limit: usize, id: impl Fn(&T) -> Uuid) -> Option<Uuid> {
+    if rows.len() <= limit {
+        return None;
+    }
+    rows.truncate(limit);
+    rows.last().map(id)
+}
+
+/// One page of a league's classic table for a season, best first, and the
+/// cursor for the next page.
+///
+/// The table is ranked and paged in SQL on the [`TABLE_ORDER`] key,
+/// starting after the member `cursor`; `limit` of `None` returns the rest of
+/// the table. The previous rank is the same table with the latest scored
+/// gameweek left out.
+pub async fn league_table(
+    conn: &mut PgConnection,
+    league_id: Uuid,
+    season_id: Uuid,
+    cursor: Option<Uuid>,
+    limit: Option<i64>,
+) -> Result<LeaderboardPage, sqlx::Error> {
+    let (latest_week, scored_weeks) = sqlx::query_as::<_, (Option<i32>, i64)>(
+        r#"SELECT MAX(mw.week_number), COUNT(*)
+           FROM match_weeks mw
+           WHERE mw.season_id = $1
+             AND EXISTS (SELECT 1 FROM team_gameweek_points tgp WHERE tgp.match_week_id = mw.id)"#,
+    )
+    .bind(season_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    #[derive(sqlx::FromRow)]
+    struct MemberRow {
+        rank: i64,
+        previous_rank: i64,
+        user_id: Uuid,
+        username: String,
+        full_name: String,
+        team_name: Option<String>,
+        joined_at: DateTime<Utc>,
+        total_points: i64,
+        transfer_hits: i64,
+        best_gameweek: i64,
+    }
+
+    let total_members =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM league_members WHERE league_id = $1")
+            .bind(league_id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+    let rows = sqlx::query_as::<_, MemberRow>(&format!(
+        r#"WITH members AS (
+             SELECT
+               u.id AS user_id,
+               u.username,
+               u.full_name,
+               ft.name AS team_name,
+               lm.joined_at,
+               COALESCE(SUM(w.total_points), 0)::bigint AS total_points,
+               COALESCE(SUM(w.transfer_points_hit), 0)::bigint AS transfer_hits,
+               COALESCE(MAX(w.total_points), 0)::bigint AS best_gameweek,
+               COALESCE(SUM(w.total_points) FILTER (WHERE w.week_number < $3), 0)::bigint
+                 AS previous_total,
+               COALESCE(SUM(w.transfer_points_hit) FILTER (WHERE w.week_number < $3), 0)::bigint
+                 AS previous_hits,
+               COALESCE(MAX(w.total_points) FILTER (WHERE w.week_number < $3), 0)::bigint
+                 AS previous_best
+             FROM league_members lm
+             INNER JOIN users u ON u.id = lm.user_id
+             LEFT JOIN fantasy_teams ft ON ft.user_id = u.id
+             LEFT JOIN (
+               SELECT tgp.team_id, tgp.total_points, tgp.transfer_points_hit, mw.week_number
+               FROM team_gameweek_points tgp
+               INNER JOIN match_weeks mw ON mw.id = tgp.match_week_id
+               WHERE mw.season_id = $2
+             ) w ON w.team_id = ft.id
+             WHERE lm.league_id = $1
+             GROUP BY u.id, u.username, u.full_name, ft.id, ft.name, lm.joined_at
+           ),
+           ranked AS (
+             SELECT *,
+                    ROW_NUMBER() OVER (ORDER BY {order}) AS rank,
+                    ROW_NUMBER() OVER (ORDER BY {previous_order}) AS previous_rank
+             FROM members
+           )
+           SELECT rank, previous_rank, user_id, username, full_name, team_name, joined_at,
+                  total_points, transfer_hits, best_gameweek
+           FROM ranked
+           WHERE $4::uuid IS NULL
+              OR ({key}) > (SELECT {key} FROM ranked WHERE user_id = $4)
+           ORDER BY {order}
+           LIMIT $5"#,
+        order = TABLE_ORDER,
+        previous_order = PREVIOUS_TABLE_ORDER,
+        key = TABLE_KEY,
+    ))
+    .bind(league_id)
+    .bind(season_id)
+    .bind(latest_week)
+    .bind(cursor)
+    .bind(limit.map(|l| l + 1))
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // With a single scored week there is nothing to move from.
+    let has_previous = scored_weeks > 1;
+    let mut table: Vec<LeagueMemberStanding> = rows
+        .into_iter()
+        .map(|r| {
+            let rank = r.rank as i32;
+            let previous_rank = has_previous.then_some(r.previous_rank as i32);
+            LeagueMemberStanding {
+                rank,
+                previous_rank,
+                rank_change: previous_rank.map(|p| p - rank),
+                user_id: r.user_id,
+                username: r.username,
+                full_name: r.full_name,
+                team_name: r.team_name,
+                total_points: Some(r.total_points),
+                transfer_hits: r.transfer_hits,
+                best_gameweek_points: r.best_gameweek,
+                joined_at: r.joined_at,
+            }
+        })
+        .collect();
+    let next_cursor = match limit {
+        Some(limit) => finish_page(&mut table, limit as usize, |m| m.user_id),
+        None => None,
+    };
+
+    Ok(LeaderboardPage {
+        standings: table,
+        next_cursor,
+        total_members,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_end_when_nothing_is_left_over() {
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+
+        let mut full = ids.clone();
+        assert_eq!(finish_page(&mut full, 2, |id| *id), Some(ids[1]));
+        assert_eq!(full, ids[..2]);
+
+        let mut last = ids[2..].to_vec();
+        assert_eq!(finish_page(&mut last, 2, |id| *id), None);
+        assert_eq!(last, ids[2..]);
+    }
+
+    async fn pool() -> Option<sqlx::PgPool> {
+        let url = std::env::var("DATABASE_URL").ok()?;
+        sqlx::PgPool::connect(&url).await.ok()
+    }
+
+    /// Five teams level on 40 points, bar one, separated by each tiebreak in
+    /// turn. Week one alone ranks them differently, so every rank moves. Built
+    /// in an isolated season inside a transaction that is rolled back.
+    #[tokio::test]
+    async fn table_breaks_ties_tracks_movement_and_pages_everyone_once() {
+        let Some(pool) = pool().await else {
+            eprintln!("skipping: DATABASE_URL not set or unreachable");
+            return;
+        };
+        let mut tx = pool.begin().await.expect("begin");
+
+        let season_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO seasons (name, year, start_week, closed_at)
+             VALUES ('Fall', 100001, 100001, NOW())
+             RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .expect("insert season");
+
+        let mut weeks = Vec::new();
+        for week_number in [100001, 100002] {
+            let week_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO match_weeks (week_number, start_date, end_date, season_id)
+                 VALUES ($1, '2030-01-01', '2030-01-07', $2)
+                 RETURNING id",
+            )
+            .bind(week_number)
+            .bind(season_id)
+            .fetch_one(&mut *tx)
+            .await
+            .expect("insert match week");
+            weeks.push(week_id);
+        }
+
+        // (name, day joined, [(points, hits) per week])
+        let teams = [
+            ("leader", 5, [(20, 0), (30, 0)]),
+            ("best_week", 4, [(28, 0), (12, 0)]),
+            ("early", 1, [(15, 0), (25, 0)]),
+            ("late", 2, [(25, 0), (15, 0)]),
+            ("hits", 3, [(30, 4), (10, 0)]),
+        ];
+
+        let owner: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (username, email, password_hash, full_name)
+             VALUES ('table_probe_owner', 'table_probe_owner@example.test', 'x', 'Owner')
+             RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .expect("insert owner");
+        let league_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO leagues (name, invite_code, created_by)
+             VALUES ('Table probe', 'TBLPROBE', $1)
+             RETURNING id",
+        )
+        .bind(owner)
+        .fetch_one(&mut *tx)
+        .await
+        .expect("insert league");
+
+        let mut ids = std::collections::HashMap::new();
+        for (name, day, scores) in teams {
+            let user_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO users (username, email, password_hash, full_name)
+                 VALUES ($1, $1 || '@example.test', 'x', $1)
+                 RETURNING id",
+            )
+            .bind(format!("table_probe_{name}"))
+            .fetch_one(&mut *tx)
+            .await
+            .expect("insert user");
+            let team_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO fantasy_teams (user_id, name) VALUES ($1, $2) RETURNING id",
+            )
+            .bind(user_id)
+            .bind(name)
+            .fetch_one(&mut *tx)
+            .await
+            .expect("insert team");
+            sqlx::query(
+                "INSERT INTO league_members (league_id, user_id, joined_at)
+                 VALUES ($1, $2, TIMESTAMPTZ '2030-01-01' + make_interval(days => $3))",
+            )
+            .bind(league_id)
+            .bind(user_id)
+            .bind(day)
+            .execute(&mut *tx)
+            .await
+            .expect("insert member");
+            for (week_id, (points, hits)) in weeks.iter().zip(scores) {
+                sqlx::query(
+                    "INSERT INTO team_gameweek_points
+                       (team_id, match_week_id, total_points, transfer_points_hit)
+                     VALUES ($1, $2, $3, $4)",
+                )
+                .bind(team_id)
+                .bind(week_id)
+                .bind(points)
+                .bind(hits)
+                .execute(&mut *tx)
+                .await
+                .expect("insert gameweek points");
+            }
+            ids.insert(user_id, name);
+        }
+
+        let table = league_table(&mut tx, league_id, season_id, None, None)
+            .await
+            .expect("whole table");
+        let order: Vec<(&str, i32, Option<i32>)> = table
+            .standings
+            .iter()
+            .filter_map(|m| ids.get(&m.user_id).map(|n| (*n, m.rank, m.rank_change)))
+            .collect();
+        assert_eq!(table.total_members, 5);
+        assert_eq!(
+            order,
+            vec![
+                ("leader", 1, Some(3)),
+                ("best_week", 2, Some(0)),
+                ("early", 3, Some(2)),
+                ("late", 4, Some(-1)),
+                ("hits", 5, Some(-4)),
+            ]
+        );
+
+        let mut paged = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = league_table(&mut tx, league_id, season_id, cursor, Some(2))
+                .await
+                .expect("page");
+            assert!(page.standings.len() <= 2);
+            paged.extend(page.standings.iter().map(|m| m.user_id));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        let whole: Vec<Uuid> = table.standings.iter().map(|m| m.user_id).collect();
+        assert_eq!(paged, whole);
+
+        tx.rollback().await.expect("rollback");
+    }
+}
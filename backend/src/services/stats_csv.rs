@@ -0,0 +1,334 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::{PlayerStatInput, StatsImportError};
+
+/// Stat columns every import file must carry, named as in `PlayerStatInput`.
+pub const STAT_COLUMNS: [&str; 10] = [
+    "goals",
+    "assists",
+    "clean_sheets",
+    "saves",
+    "penalty_saves",
+    "own_goals",
+    "penalty_misses",
+    "regular_fouls",
+    "serious_fouls",
+    "minutes_played",
+];
+
+/// One CSV record before the player is resolved. Blank stat cells count as 0.
+#[derive(Debug, Deserialize)]
+struct CsvStatRecord {
+    #[serde(default)]
+    player_id: Option<String>,
+    #[serde(default)]
+    player_name: Option<String>,
+    goals: Option<i32>,
+    assists: Option<i32>,
+    clean_sheets: Option<i32>,
+    saves: Option<i32>,
+    penalty_saves: Option<i32>,
+    own_goals: Option<i32>,
+    penalty_misses: Option<i32>,
+    regular_fouls: Option<i32>,
+    serious_fouls: Option<i32>,
+    minutes_played: Option<i32>,
+}
+
+/// A CSV line resolved to a player and ready to submit.
+#[derive(Debug)]
+pub struct ParsedStatRow {
+    pub line: u64,
+    pub player_name: String,
+    pub stat: PlayerStatInput,
+}
+
+/// Parse an import file against the player roster.
+///
+/// Players are matched by `player_id`, or by `player_name` (case-insensitive)
+/// when no id is given. Every line is checked, so the caller gets all problems
+/// in one pass instead of stopping at the first.
+pub fn parse_stats_csv(
+    text: &str,
+    roster: &[(Uuid, String)],
+) -> (Vec<ParsedStatRow>, Vec<StatsImportError>) {
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => {
+            errors.push(StatsImportError {
+                line: 1,
+                message: e.to_string(),
+            });
+            return (rows, errors);
+        }
+    };
+
+    let known: HashSet<&str> = STAT_COLUMNS
+        .iter()
+        .copied()
+        .chain(["player_id", "player_name"])
+        .collect();
+    let mut header_problems = Vec::new();
+    if !headers
+        .iter()
+        .any(|h| h == "player_id" || h == "player_name")
+    {
+        header_problems.push("a player_id or player_name column is required".to_string());
+    }
+    let missing: Vec<&str> = STAT_COLUMNS
+        .iter()
+        .copied()
+        .filter(|c| !headers.iter().any(|h| h == *c))
+        .collect();
+    if !missing.is_empty() {
+        header_problems.push(format!("missing columns: {}", missing.join(", ")));
+    }
+    let unknown: Vec<&str> = headers.iter().filter(|h| !known.contains(h)).collect();
+    if !unknown.is_empty() {
+        header_problems.push(format!("unknown columns: {}", unknown.join(", ")));
+    }
+    if !header_problems.is_empty() {
+        errors.push(StatsImportError {
+            line: 1,
+            message: header_problems.join("; "),
+        });
+        return (rows, errors);
+    }
+
+    let by_id: HashMap<Uuid, &str> = roster
+        .iter()
+        .map(|(id, name)| (*id, name.as_str()))
+        .collect();
+    let mut by_name: HashMap<String, Vec<Uuid>> = HashMap::new();
+    for (id, name) in roster {
+        by_name
+            .entry(name.trim().to_lowercase())
+            .or_default()
+            .push(*id);
+    }
+
+    let mut seen: HashMap<Uuid, u64> = HashMap::new();
+
+    for result in reader.records() {
+        let raw = match result {
+            Ok(raw) => raw,
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                errors.push(StatsImportError {
+                    line,
+                    message: record_error_message(&e),
+                });
+                continue;
+            }
+        };
+        let line = raw.position().map_or(0, |p| p.line());
+        let record = match raw.deserialize::<CsvStatRecord>(Some(&headers)) {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(StatsImportError {
+                    line,
+                    message: field_error_message(&e, &headers),
+                });
+                continue;
+            }
+        };
+
+        let player_id = match resolve_player(&record, &by_id, &by_name) {
+            Ok(id) => id,
+            Err(message) => {
+                errors.push(StatsImportError { line, message });
+                continue;
+            }
+        };
+
+        if let Some(first) = seen.insert(player_id, line) {
+            errors.push(StatsImportError {
+                line,
+                message: format!("{} already appears on line {first}", by_id[&player_id]),
+            });
+            continue;
+        }
+
+        let stat = PlayerStatInput {
+            player_id,
+            goals: record.goals.unwrap_or(0),
+            assists: record.assists.unwrap_or(0),
+            clean_sheets: record.clean_sheets.unwrap_or(0),
+            saves: record.saves.unwrap_or(0),
+            penalty_saves: record.penalty_saves.unwrap_or(0),
+            own_goals: record.own_goals.unwrap_or(0),
+            penalty_misses: record.penalty_misses.unwrap_or(0),
+            regular_fouls: record.regular_fouls.unwrap_or(0),
+            serious_fouls: record.serious_fouls.unwrap_or(0),
+            minutes_played: record.minutes_played.unwrap_or(0),
+        };
+
+        let negative: Vec<&str> = STAT_COLUMNS
+            .iter()
+            .copied()
+            .zip(stat_values(&stat))
+            .filter(|(_, v)| *v < 0)
+            .map(|(c, _)| c)
+            .collect();
+        if !negative.is_empty() {
+            errors.push(StatsImportError {
+                line,
+                message: format!("{} cannot be negative", negative.join(", ")),
+            });
+            continue;
+        }
+
+        rows.push(ParsedStatRow {
+            line,
+            player_name: by_id[&player_id].to_string(),
+            stat,
+        });
+    }
+
+    if rows.is_empty() && errors.is_empty() {
+        errors.push(StatsImportError {
+            line: 1,
+            message: "The file has no stat rows".to_string(),
+        });
+    }
+
+    (rows, errors)
+}
+
+fn resolve_player(
+    record: &CsvStatRecord,
+    by_id: &HashMap<Uuid, &str>,
+    by_name: &HashMap<String, Vec<Uuid>>,
+) -> Result<Uuid, String> {
+    let id = record.player_id.as_deref().filter(|s| !s.is_empty());
+    let name = record.player_name.as_deref().filter(|s| !s.is_empty());
+
+    match (id, name) {
+        (Some(raw), _) => {
+            let id =
+                Uuid::parse_str(raw).map_err(|_| format!("'{raw}' is not a valid player_id"))?;
+            let found = by_id
+                .get(&id)
+                .ok_or_else(|| format!("No player with id {id}"))?;
+            if let Some(name) = name {
+                if found.trim().to_lowercase() != name.to_lowercase() {
+                    return Err(format!("player_id {id} is {found}, not {name}"));
+                }
+            }
+            Ok(id)
+        }
+        (None, Some(name)) => match by_name.get(&name.to_lowercase()).map(Vec::as_slice) {
+            Some([id]) => Ok(*id),
+            Some(_) => Err(format!(
+                "More than one player is named {name}; use player_id"
+            )),
+            None => Err(format!("No player named {name}")),
+        },
+        (None, None) => Err("Either player_id or player_name is required".to_string()),
+    }
+}
+
+fn stat_values(s: &PlayerStatInput) -> [i32; 10] {
+    [
+        s.goals,
+        s.assists,
+        s.clean_sheets,
+        s.saves,
+        s.penalty_saves,
+        s.own_goals,
+        s.penalty_misses,
+        s.regular_fouls,
+        s.serious_fouls,
+        s.minutes_played,
+    ]
+}
+
+fn record_error_message(e: &csv::Error) -> String {
+    match e.kind() {
+        csv::ErrorKind::UnequalLengths {
+            expected_len, len, ..
+        } => {
+            format!("expected {expected_len} fields, found {len}")
+        }
+        _ => e.to_string(),
+    }
+}
+
+fn field_error_message(e: &csv::Error, headers: &csv::StringRecord) -> String {
+    match e.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => {
+            match err.field().and_then(|i| headers.get(i as usize)) {
+                Some(column) => format!("{column}: {}", err.kind()),
+                None => err.kind().to_string(),
+            }
+        }
+        _ => e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "player_id,player_name,goals,assists,clean_sheets,saves,penalty_saves,own_goals,penalty_misses,regular_fouls,serious_fouls,minutes_played";
+
+    fn roster() -> Vec<(Uuid, String)> {
+        vec![
+            (Uuid::from_u128(1), "Alex Morgan".to_string()),
+            (Uuid::from_u128(2), "Sam Kerr".to_string()),
+            (Uuid::from_u128(3), "Jo Smith".to_string()),
+            (Uuid::from_u128(4), "Jo Smith".to_string()),
+        ]
+    }
+
+    #[test]
+    fn matches_by_id_or_name() {
+        let text = format!(
+            "{HEADER}\n{},,2,1,0,0,0,0,0,0,0,60\n,sam kerr,,,1,,,,,,,45\n",
+            Uuid::from_u128(1)
+        );
+        let (rows, errors) = parse_stats_csv(&text, &roster());
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].stat.goals, 2);
+        assert_eq!(rows[1].line, 3);
+        assert_eq!(rows[1].stat.player_id, Uuid::from_u128(2));
+        assert_eq!(rows[1].stat.clean_sheets, 1);
+        assert_eq!(rows[1].stat.goals, 0);
+    }
+
+    #[test]
+    fn reports_every_bad_line() {
+        let text = format!(
+            "{HEADER}\n,Nobody,0,0,0,0,0,0,0,0,0,60\n,Jo Smith,0,0,0,0,0,0,0,0,0,60\n,Sam Kerr,x,0,0,0,0,0,0,0,0,60\n,Sam Kerr,0,-1,0,0,0,0,0,0,0,60\n,Alex Morgan,1,0,0,0,0,0,0,0,0,60\n,alex morgan,1,0,0,0,0,0,0,0,0,60\n"
+        );
+        let (rows, errors) = parse_stats_csv(&text, &roster());
+        assert_eq!(rows.len(), 1);
+        let lines: Vec<u64> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![2, 3, 4, 5, 7]);
+        assert!(errors[1].message.contains("use player_id"));
+        assert!(errors[3].message.contains("assists"));
+        assert!(errors[4].message.contains("line 6"));
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        let (rows, errors) = parse_stats_csv("player_name,goals,bogus\nSam Kerr,1,2\n", &roster());
+        assert!(rows.is_empty());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 1);
+        assert!(errors[0].message.contains("missing columns"));
+        assert!(errors[0].message.contains("bogus"));
+    }
+}
@@ -0,0 +1,219 @@
+use uuid::Uuid;
+
+/// A season's transfer rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::FromRow)]
+pub struct TransferPolicy {
+    /// Free transfers added to the bank at the start of each gameweek.
+    pub free_transfers_per_week: i32,
+    /// Most free transfers the bank can hold.
+    pub max_banked_transfers: i32,
+    /// Points deducted for each transfer beyond the free ones.
+    pub transfer_hit_cost: i32,
+}
+
+impl Default for TransferPolicy {
+    /// One free transfer a week that does not roll over, and -4 for each extra.
+    fn default() -> Self {
+        Self {
+            free_transfers_per_week: 1,
+            max_banked_transfers: 1,
+            transfer_hit_cost: 4,
+        }
+    }
+}
+
+/// The open season's policy, or the default if no season is open.
+pub async fn current_policy(conn: &mut sqlx::PgConnection) -> Result<TransferPolicy, sqlx::Error> {
+    let policy = sqlx::query_as::<_, TransferPolicy>(
+        r#"SELECT free_transfers_per_week, max_banked_transfers, transfer_hit_cost
+           FROM seasons WHERE closed_at IS NULL"#,
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(policy.unwrap_or_default())
+}
+
+/// Transfers a team made in one gameweek.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::FromRow)]
+pub struct WeekTransfers {
+    pub used: i64,
+    /// A Wildcard or Free Hit was played: the week's transfers are free and
+    /// leave the bank untouched.
+    pub hits_waived: bool,
+}
+
+/// Where a team stands on transfers in a gameweek.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferAllowance {
+    pub free_transfers: i32,
+    pub transfers_used: i32,
+    pub extra_transfers: i32,
+    pub points_hit: i32,
+    pub hits_waived: bool,
+    pub policy: TransferPolicy,
+}
+
+/// Work out the current gameweek's allowance from the team's history.
+///
+/// `weeks` runs in gameweek order and ends with the current week. Each week
+/// tops the bank up by the weekly allowance, capped at the maximum, and spends
+/// it on that week's transfers; whatever is left rolls over.
+pub fn allowance(policy: &TransferPolicy, weeks: &[WeekTransfers]) -> TransferAllowance {
+    let mut bank = 0;
+    let mut current = WeekTransfers {
+        used: 0,
+        hits_waived: false,
+    };
+
+    for (i, week) in weeks.iter().enumerate() {
+        bank = (bank + policy.free_transfers_per_week).min(policy.max_banked_transfers);
+        if i + 1 == weeks.len() {
+            current = *week;
+        } else if !week.hits_waived {
+            bank = (bank - week.used as i32).max(0);
+        }
+    }
+    if weeks.is_empty() {
+        bank = policy
+            .free_transfers_per_week
+            .min(policy.max_banked_transfers);
+    }
+
+    let transfers_used = current.used as i32;
+    let extra_transfers = if current.hits_waived {
+        0
+    } else {
+        (transfers_used - bank).max(0)
+    };
+
+    TransferAllowance {
+        free_transfers: bank,
+        transfers_used,
+        extra_transfers,
+        points_hit: extra_transfers * policy.transfer_hit_cost,
+        hits_waived: current.hits_waived,
+        policy: *policy,
+    }
+}
+
+/// The allowance for `team_id` in `match_week_id`, from its season's policy and
+/// the team's `transfers` in that season up to and including the week.
+///
+/// Weeks that ended before the team was created are skipped, so a new team
+/// starts with one week's allowance rather than a full bank.
+pub async fn team_allowance(
+    conn: &mut sqlx::PgConnection,
+    team_id: Uuid,
+    match_week_id: Uuid,
+) -> Result<TransferAllowance, sqlx::Error> {
+    let policy = sqlx::query_as::<_, TransferPolicy>(
+        r#"SELECT s.free_transfers_per_week, s.max_banked_transfers, s.transfer_hit_cost
+           FROM match_weeks mw
+           INNER JOIN seasons s ON s.id = mw.season_id
+           WHERE mw.id = $1"#,
+    )
+    .bind(match_week_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let weeks = sqlx::query_as::<_, WeekTransfers>(
+        r#"SELECT
+             (SELECT COUNT(*) FROM transfers t
+              WHERE t.team_id = ft.id AND t.match_week_id = mw.id) AS used,
+             COALESCE(tgl.transfer_hits_waived, false) AS hits_waived
+           FROM match_weeks current
+           INNER JOIN match_weeks mw
+             ON mw.season_id = current.season_id AND mw.week_number <= current.week_number
+           INNER JOIN fantasy_teams ft ON ft.id = $1
+           LEFT JOIN team_gameweek_lineups tgl
+             ON tgl.team_id = ft.id AND tgl.match_week_id = mw.id
+           WHERE current.id = $2
+             AND (mw.id = current.id OR ft.created_at::date <= mw.end_date)
+           ORDER BY mw.week_number"#,
+    )
+    .bind(team_id)
+    .bind(match_week_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(allowance(&policy, &weeks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: TransferPolicy = TransferPolicy {
+        free_transfers_per_week: 1,
+        max_banked_transfers: 1,
+        transfer_hit_cost: 4,
+    };
+
+    #[test]
+    fn default_is_the_original_rule() {
+        assert_eq!(TransferPolicy::default(), ORIGINAL);
+    }
+
+    const ROLLING: TransferPolicy = TransferPolicy {
+        free_transfers_per_week: 1,
+        max_banked_transfers: 5,
+        transfer_hit_cost: 4,
+    };
+
+    fn weeks(used: &[i64]) -> Vec<WeekTransfers> {
+        used.iter()
+            .map(|&used| WeekTransfers {
+                used,
+                hits_waived: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn original_rule_never_banks() {
+        let a = allowance(&ORIGINAL, &weeks(&[0, 0, 0, 3]));
+        assert_eq!(a.free_transfers, 1);
+        assert_eq!(a.extra_transfers, 2);
+        assert_eq!(a.points_hit, 8);
+    }
+
+    #[test]
+    fn unused_transfers_roll_over_up_to_the_cap() {
+        assert_eq!(allowance(&ROLLING, &weeks(&[0, 0, 2])).free_transfers, 3);
+        assert_eq!(allowance(&ROLLING, &weeks(&[0; 9])).free_transfers, 5);
+
+        // 3 banked, 2 spent, 1 left, plus this week's 1.
+        let a = allowance(&ROLLING, &weeks(&[0, 0, 2, 4]));
+        assert_eq!(a.free_transfers, 2);
+        assert_eq!(a.extra_transfers, 2);
+        assert_eq!(a.points_hit, 8);
+    }
+
+    #[test]
+    fn hits_do_not_push_the_bank_negative() {
+        let a = allowance(&ROLLING, &weeks(&[5, 0]));
+        assert_eq!(a.free_transfers, 1);
+    }
+
+    #[test]
+    fn waived_weeks_are_free_and_keep_the_bank() {
+        let mut history = weeks(&[0, 6, 0]);
+        history[1].hits_waived = true;
+        assert_eq!(allowance(&ROLLING, &history).free_transfers, 3);
+
+        let mut history = weeks(&[0, 6]);
+        history[1].hits_waived = true;
+        let a = allowance(&ROLLING, &history);
+        assert_eq!(a.transfers_used, 6);
+        assert_eq!(a.points_hit, 0);
+    }
+
+    #[test]
+    fn custom_hit_cost() {
+        let policy = TransferPolicy {
+            transfer_hit_cost: 2,
+            ..ORIGINAL
+        };
+        assert_eq!(allowance(&policy, &weeks(&[3])).points_hit, 4);
+    }
+}
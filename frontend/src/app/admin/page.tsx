@@ -292,11 +292,11 @@ export default function AdminPage() {
               className="text-sm font-bold tracking-wider"
               style={{ fontFamily: "var(--font-display)", color: "var(--text-primary)" }}
             >
-              WEEKEND LOCK OVERRIDE
+              LINEUP LOCK OVERRIDE
             </h3>
           </div>
           <p className="text-xs mb-4" style={{ color: "var(--text-muted)", fontFamily: "var(--font-body)" }}>
-            Use this only if you need to reopen transfers/lineups during the scheduled lock window (Sat 10:00 PM – Sun 12:00 PM ET).
+            Use this only if you need to reopen transfers/lineups during the lock window, which follows the gameweek's own deadline or the weekly schedule.
           </p>
           <div className="flex items-center justify-between p-3 rounded-xl" style={{ background: "var(--bg-elevated)", border: "1px solid var(--border-color)" }}>
             <div>
@@ -7,7 +7,7 @@ import { Users, Plus, ArrowRight, Copy, Check, AlertCircle, Trophy, Crown, Shiel
 import Nav from "@/components/nav";
 import Formation from "@/components/formation";
 import type { FormationPlayer } from "@/components/formation";
-import { createLeague, joinLeague, getLeague, getMyLeagues, getLockStatus, getMemberLineup, getLeagueGameweek, getWeekPoints, type League, type LeagueDetail, type MyLeague, type MemberLineup, type LeagueGameweekDetail, type PlayerPointsDisplay } from "@/lib/api";
+import { createLeague, joinLeague, getLeague, getMyLeagues, getLockStatus, getMemberLineup, getLeagueGameweek, getWeekPoints, type JoinLeagueResponse, type League, type LeagueDetail, type MyLeague, type MemberLineup, type LeagueGameweekDetail, type PlayerPointsDisplay } from "@/lib/api";
 import { getToken, getUser, isAuthenticated } from "@/lib/auth";
 
 export default function LeaguePage() {
@@ -16,7 +16,7 @@ export default function LeaguePage() {
   const [leagueName, setLeagueName] = useState("");
   const [inviteCode, setInviteCode] = useState("");
   const [createdLeague, setCreatedLeague] = useState<League | null>(null);
-  const [joinedLeague, setJoinedLeague] = useState<League | null>(null);
+  const [joinedLeague, setJoinedLeague] = useState<JoinLeagueResponse | null>(null);
   const [leagueDetail, setLeagueDetail] = useState<LeagueDetail | null>(null);
   const [myLeagues, setMyLeagues] = useState<MyLeague[]>([]);
   const [myLeaguesLoading, setMyLeaguesLoading] = useState(true);
@@ -129,8 +129,8 @@ export default function LeaguePage() {
     if (!token) return;
 
     try {
-      const league = await joinLeague(inviteCode.toUpperCase(), token);
-      setJoinedLeague(league);
+      const joined = await joinLeague(inviteCode.toUpperCase(), token);
+      setJoinedLeague(joined);
       setInviteCode("");
       loadMyLeagues();
     } catch (err) {
@@ -392,14 +392,18 @@ export default function LeaguePage() {
                 style={{ background: "rgba(0, 230, 118, 0.06)", border: "1px solid rgba(0, 230, 118, 0.2)" }}
               >
                 <p className="text-sm font-medium" style={{ color: "var(--accent-green)" }}>
-                  Successfully joined &quot;{joinedLeague.name}&quot;!
+                  {joinedLeague.status === "pending"
+                    ? <>Request sent to &quot;{joinedLeague.league.name}&quot;. The owner has to accept it.</>
+                    : <>Successfully joined &quot;{joinedLeague.league.name}&quot;!</>}
                 </p>
-                <button
-                  onClick={() => handleViewLeague(joinedLeague.id)}
-                  className="btn-secondary text-xs mt-3 py-2 px-4"
-                >
-                  View League
-                </button>
+                {joinedLeague.status === "joined" && (
+                  <button
+                    onClick={() => handleViewLeague(joinedLeague.league.id)}
+                    className="btn-secondary text-xs mt-3 py-2 px-4"
+                  >
+                    View League
+                  </button>
+                )}
               </motion.div>
             )}
           </motion.div>
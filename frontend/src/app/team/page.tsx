@@ -69,6 +69,8 @@ export default function TeamBuilderPage() {
   // Chip state
   const [chipStatus, setChipStatus] = useState<ChipStatus | null>(null);
   const [activatingChip, setActivatingChip] = useState<string | null>(null);
+  const tripleCaptain = chipStatus?.chips.find((c) => c.chip_type === "triple_captain");
+  const benchBoost = chipStatus?.chips.find((c) => c.chip_type === "bench_boost");
   // Transfer state
   const [transferStatus, setTransferStatus] = useState<TransferStatus | null>(null);
   const [transferOutPlayer, setTransferOutPlayer] = useState<Player | null>(null);
@@ -165,9 +167,19 @@ export default function TeamBuilderPage() {
     setPendingPlayer(null);
   };
 
+  const unlockLabel = lockStatus?.unlock_at
+    ? new Date(lockStatus.unlock_at).toLocaleString(undefined, {
+        weekday: "short",
+        hour: "numeric",
+        minute: "2-digit",
+        timeZone: lockStatus.timezone,
+        timeZoneName: "short",
+      })
+    : "the gameweek deadline passes";
+
   const handleSelect = (player: Player) => {
     if (lockStatus?.locked) {
-      setError("Lineup changes are locked until Sunday 12:00 PM ET");
+      setError(`Lineup changes are locked until ${unlockLabel}`);
       return;
     }
 
@@ -404,7 +416,7 @@ export default function TeamBuilderPage() {
 
   const handleStartTransfer = (player: Player, isBench: boolean) => {
     if (lockStatus?.locked) {
-      setError("Transfers are locked until Sunday 12:00 PM ET");
+      setError(`Transfers are locked until ${unlockLabel}`);
       return;
     }
     if (captainId === player.id) {
@@ -739,7 +751,7 @@ export default function TeamBuilderPage() {
                 LINEUP LOCKED
               </p>
               <p className="text-xs" style={{ color: "var(--text-muted)" }}>
-                Lineup changes are locked from Saturday 10:00 PM ET to Sunday 12:00 PM ET
+                Lineup changes are locked until {unlockLabel}
               </p>
             </div>
           </motion.div>
@@ -1105,29 +1117,29 @@ export default function TeamBuilderPage() {
                     <div
                       className="p-3 rounded-xl transition-all"
                       style={{
-                        background: chipStatus?.triple_captain.available
+                        background: tripleCaptain?.available
                           ? "rgba(255,171,0,0.06)"
-                          : chipStatus?.triple_captain.can_deactivate
+                          : tripleCaptain?.can_deactivate
                           ? "rgba(255,171,0,0.04)"
                           : "rgba(255,255,255,0.02)",
-                        border: chipStatus?.triple_captain.available
+                        border: tripleCaptain?.available
                           ? "1px solid rgba(255,171,0,0.25)"
-                          : chipStatus?.triple_captain.can_deactivate
+                          : tripleCaptain?.can_deactivate
                           ? "1px solid rgba(255,171,0,0.15)"
                           : "1px solid var(--border-color)",
-                        opacity: chipStatus?.triple_captain.available || chipStatus?.triple_captain.can_deactivate ? 1 : 0.6,
+                        opacity: tripleCaptain?.available || tripleCaptain?.can_deactivate ? 1 : 0.6,
                       }}
                     >
                       <div className="flex items-center gap-2 mb-1.5">
                         <div
                           className="w-6 h-6 rounded-lg flex items-center justify-center"
                           style={{
-                            background: chipStatus?.triple_captain.available
+                            background: tripleCaptain?.available
                               ? "linear-gradient(135deg, #fbbf24, #f59e0b)"
                               : "rgba(255,255,255,0.05)",
                           }}
                         >
-                          <Crown size={12} style={{ color: chipStatus?.triple_captain.available ? "#1a1a2e" : "var(--text-muted)" }} />
+                          <Crown size={12} style={{ color: tripleCaptain?.available ? "#1a1a2e" : "var(--text-muted)" }} />
                         </div>
                         <div className="flex-1">
                           <p className="text-xs font-bold" style={{ fontFamily: "var(--font-display)" }}>
@@ -1138,7 +1150,7 @@ export default function TeamBuilderPage() {
                           </p>
                         </div>
                       </div>
-                      {chipStatus?.triple_captain.available ? (
+                      {tripleCaptain?.available ? (
                         <button
                           onClick={() => handleActivateChip("triple_captain")}
                           disabled={activatingChip === "triple_captain" || lockStatus?.locked || !chipStatus?.active_gameweek}
@@ -1152,14 +1164,14 @@ export default function TeamBuilderPage() {
                         >
                           {activatingChip === "triple_captain" ? "ACTIVATING..." : "ACTIVATE"}
                         </button>
-                      ) : chipStatus?.triple_captain.can_deactivate ? (
+                      ) : tripleCaptain?.can_deactivate ? (
                         <div className="flex items-center gap-2">
                           <p
                             className="text-[10px] font-bold flex items-center gap-1 flex-1"
                             style={{ color: "#fbbf24", fontFamily: "var(--font-display)" }}
                           >
                             <Zap size={10} />
-                            ACTIVE — GW {chipStatus?.triple_captain.used_in_week}
+                            ACTIVE — GW {tripleCaptain?.used_in_week}
                           </p>
                           <button
                             onClick={() => handleDeactivateChip("triple_captain")}
@@ -1180,7 +1192,7 @@ export default function TeamBuilderPage() {
                           style={{ color: "var(--accent-green)", fontFamily: "var(--font-display)" }}
                         >
                           <Check size={10} />
-                          USED IN GW {chipStatus?.triple_captain.used_in_week}
+                          USED IN GW {tripleCaptain?.used_in_week}
                         </p>
                       )}
                     </div>
@@ -1189,29 +1201,29 @@ export default function TeamBuilderPage() {
                     <div
                       className="p-3 rounded-xl transition-all"
                       style={{
-                        background: chipStatus?.bench_boost.available
+                        background: benchBoost?.available
                           ? "rgba(99,102,241,0.06)"
-                          : chipStatus?.bench_boost.can_deactivate
+                          : benchBoost?.can_deactivate
                           ? "rgba(99,102,241,0.04)"
                           : "rgba(255,255,255,0.02)",
-                        border: chipStatus?.bench_boost.available
+                        border: benchBoost?.available
                           ? "1px solid rgba(99,102,241,0.25)"
-                          : chipStatus?.bench_boost.can_deactivate
+                          : benchBoost?.can_deactivate
                           ? "1px solid rgba(99,102,241,0.15)"
                           : "1px solid var(--border-color)",
-                        opacity: chipStatus?.bench_boost.available || chipStatus?.bench_boost.can_deactivate ? 1 : 0.6,
+                        opacity: benchBoost?.available || benchBoost?.can_deactivate ? 1 : 0.6,
                       }}
                     >
                       <div className="flex items-center gap-2 mb-1.5">
                         <div
                           className="w-6 h-6 rounded-lg flex items-center justify-center"
                           style={{
-                            background: chipStatus?.bench_boost.available
+                            background: benchBoost?.available
                               ? "linear-gradient(135deg, #818cf8, #6366f1)"
                               : "rgba(255,255,255,0.05)",
                           }}
                         >
-                          <Flame size={12} style={{ color: chipStatus?.bench_boost.available ? "white" : "var(--text-muted)" }} />
+                          <Flame size={12} style={{ color: benchBoost?.available ? "white" : "var(--text-muted)" }} />
                         </div>
                         <div className="flex-1">
                           <p className="text-xs font-bold" style={{ fontFamily: "var(--font-display)" }}>
@@ -1222,7 +1234,7 @@ export default function TeamBuilderPage() {
                           </p>
                         </div>
                       </div>
-                      {chipStatus?.bench_boost.available ? (
+                      {benchBoost?.available ? (
                         <button
                           onClick={() => handleActivateChip("bench_boost")}
                           disabled={activatingChip === "bench_boost" || lockStatus?.locked || !chipStatus?.active_gameweek}
@@ -1236,14 +1248,14 @@ export default function TeamBuilderPage() {
                         >
                           {activatingChip === "bench_boost" ? "ACTIVATING..." : "ACTIVATE"}
                         </button>
-                      ) : chipStatus?.bench_boost.can_deactivate ? (
+                      ) : benchBoost?.can_deactivate ? (
                         <div className="flex items-center gap-2">
                           <p
                             className="text-[10px] font-bold flex items-center gap-1 flex-1"
                             style={{ color: "#818cf8", fontFamily: "var(--font-display)" }}
                           >
                             <Zap size={10} />
-                            ACTIVE — GW {chipStatus?.bench_boost.used_in_week}
+                            ACTIVE — GW {benchBoost?.used_in_week}
                           </p>
                           <button
                             onClick={() => handleDeactivateChip("bench_boost")}
@@ -1264,7 +1276,7 @@ export default function TeamBuilderPage() {
                           style={{ color: "var(--accent-green)", fontFamily: "var(--font-display)" }}
                         >
                           <Check size={10} />
-                          USED IN GW {chipStatus?.bench_boost.used_in_week}
+                          USED IN GW {benchBoost?.used_in_week}
                         </p>
                       )}
                     </div>
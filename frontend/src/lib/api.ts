@@ -113,6 +113,8 @@ export interface FantasyTeam {
   captain_id: string | null;
   budget_limit: string;
   created_at: string;
+  bank: string;
+  selling_value: string;
   players: StarterPlayer[];
   bench: Player[];
   total_points: number;
@@ -145,20 +147,61 @@ export function setTeamPlayers(
 }
 
 // Leagues
+export type LeagueType = "classic" | "head_to_head";
+export type LeagueVisibility = "public" | "private";
+
 export interface League {
   id: string;
   name: string;
   invite_code: string;
   created_by: string;
   created_at: string;
+  league_type: LeagueType;
+  visibility: LeagueVisibility;
+  max_members: number | null;
+  requires_approval: boolean;
+}
+
+export interface JoinLeagueResponse {
+  league: League;
+  status: "joined" | "pending";
+}
+
+export interface PublicLeague {
+  id: string;
+  name: string;
+  league_type: LeagueType;
+  member_count: number;
+  max_members: number | null;
+  requires_approval: boolean;
+  created_at: string;
+}
+
+export interface LeagueJoinRequest {
+  user_id: string;
+  username: string;
+  full_name: string;
+  requested_at: string;
 }
 
 export interface LeagueMember {
+  rank: number;
+  previous_rank: number | null;
+  rank_change: number | null;
   user_id: string;
   username: string;
   full_name: string;
   team_name: string | null;
   total_points: number;
+  transfer_hits: number;
+  best_gameweek_points: number;
+  joined_at: string;
+}
+
+export interface LeaderboardPage {
+  standings: LeagueMember[];
+  next_cursor: string | null;
+  total_members: number;
 }
 
 export interface LeagueDetail {
@@ -166,28 +209,123 @@ export interface LeagueDetail {
   members: LeagueMember[];
 }
 
-export function createLeague(name: string, token: string) {
+export function createLeague(
+  name: string,
+  token: string,
+  leagueType: LeagueType = "classic",
+) {
   return apiFetch<League>("/api/leagues", {
     method: "POST",
-    body: { name },
+    body: { name, league_type: leagueType },
     token,
   });
 }
 
 export function joinLeague(inviteCode: string, token: string) {
-  return apiFetch<League>("/api/leagues/join", {
+  return apiFetch<JoinLeagueResponse>("/api/leagues/join", {
     method: "POST",
     body: { invite_code: inviteCode },
     token,
   });
 }
 
+export function getPublicLeagues() {
+  return apiFetch<PublicLeague[]>("/api/leagues/public");
+}
+
+export function joinPublicLeague(leagueId: string, token: string) {
+  return apiFetch<JoinLeagueResponse>(`/api/leagues/${leagueId}/join`, {
+    method: "POST",
+    token,
+  });
+}
+
+export function updateLeagueAccess(
+  leagueId: string,
+  body: { visibility?: LeagueVisibility; max_members?: number; requires_approval?: boolean },
+  token: string,
+) {
+  return apiFetch<League>(`/api/leagues/${leagueId}/access`, {
+    method: "PUT",
+    body,
+    token,
+  });
+}
+
+export function getJoinRequests(leagueId: string, token: string) {
+  return apiFetch<LeagueJoinRequest[]>(`/api/leagues/${leagueId}/requests`, { token });
+}
+
+export function approveJoinRequest(leagueId: string, userId: string, token: string) {
+  return apiFetch<{ ok: boolean }>(`/api/leagues/${leagueId}/requests/${userId}/approve`, {
+    method: "POST",
+    token,
+  });
+}
+
+export function rejectJoinRequest(leagueId: string, userId: string, token: string) {
+  return apiFetch<{ ok: boolean }>(`/api/leagues/${leagueId}/requests/${userId}`, {
+    method: "DELETE",
+    token,
+  });
+}
+
+export function renameLeague(leagueId: string, name: string, token: string) {
+  return apiFetch<League>(`/api/leagues/${leagueId}`, {
+    method: "PUT",
+    body: { name },
+    token,
+  });
+}
+
+export function deleteLeague(leagueId: string, token: string) {
+  return apiFetch<{ ok: boolean }>(`/api/leagues/${leagueId}`, {
+    method: "DELETE",
+    token,
+  });
+}
+
+export function regenerateInviteCode(leagueId: string, token: string) {
+  return apiFetch<League>(`/api/leagues/${leagueId}/invite-code`, {
+    method: "POST",
+    token,
+  });
+}
+
+export function transferLeagueOwnership(leagueId: string, userId: string, token: string) {
+  return apiFetch<League>(`/api/leagues/${leagueId}/owner`, {
+    method: "PUT",
+    body: { user_id: userId },
+    token,
+  });
+}
+
+export function removeLeagueMember(leagueId: string, userId: string, token: string) {
+  return apiFetch<{ ok: boolean }>(`/api/leagues/${leagueId}/members/${userId}`, {
+    method: "DELETE",
+    token,
+  });
+}
+
+export function leaveLeague(leagueId: string, token: string) {
+  return apiFetch<{ ok: boolean }>(`/api/leagues/${leagueId}/leave`, {
+    method: "POST",
+    token,
+  });
+}
+
 export function getLeague(id: string) {
   return apiFetch<LeagueDetail>(`/api/leagues/${id}`);
 }
 
-export function getLeaderboard(leagueId: string) {
-  return apiFetch<LeagueMember[]>(`/api/leagues/${leagueId}/leaderboard`);
+export function getLeaderboard(leagueId: string, cursor?: string, limit?: number) {
+  const params = new URLSearchParams();
+  if (cursor) params.set("cursor", cursor);
+  if (limit) params.set("limit", String(limit));
+  const query = params.toString();
+  return apiFetch<LeaderboardPage>(
+    `/api/leagues/${leagueId}/leaderboard${query ? `?${query}` : ""}`,
+  );
 }
 
 export interface MemberLineup {
@@ -233,10 +371,115 @@ export function getLeagueGameweek(leagueId: string, week: number) {
   return apiFetch<LeagueGameweekDetail>(`/api/leagues/${leagueId}/gameweek/${week}`);
 }
 
+export interface H2hFixture {
+  id: string;
+  week_number: number;
+  home_team_id: string;
+  home_team_name: string;
+  away_team_id: string | null;
+  away_team_name: string | null;
+  home_points: number | null;
+  away_points: number | null;
+}
+
+export interface H2hStanding {
+  rank: number;
+  team_id: string;
+  team_name: string;
+  played: number;
+  won: number;
+  drawn: number;
+  lost: number;
+  points_for: number;
+  points_against: number;
+  points: number;
+}
+
+export function getH2hFixtures(leagueId: string, week?: number) {
+  const query = week === undefined ? "" : `?week=${week}`;
+  return apiFetch<H2hFixture[]>(`/api/leagues/${leagueId}/h2h/fixtures${query}`);
+}
+
+export function generateH2hFixtures(
+  leagueId: string,
+  body: { start_week?: number; rounds?: number },
+  token: string,
+) {
+  return apiFetch<H2hFixture[]>(`/api/leagues/${leagueId}/h2h/fixtures`, {
+    method: "POST",
+    body,
+    token,
+  });
+}
+
+export function getH2hStandings(leagueId: string) {
+  return apiFetch<H2hStanding[]>(`/api/leagues/${leagueId}/h2h/standings`);
+}
+
+export type CupDecider = "points" | "captain_points" | "goals" | "seed" | "bye";
+
+export interface CupMatch {
+  home_team_id: string | null;
+  home_seed: number | null;
+  away_team_id: string | null;
+  away_seed: number | null;
+  home_points: number | null;
+  away_points: number | null;
+  winner_team_id: string | null;
+  decided_by: CupDecider | null;
+}
+
+export interface CupRound {
+  round: number;
+  week_number: number;
+  matches: CupMatch[];
+}
+
+export interface CupEntrant {
+  team_id: string;
+  team_name: string;
+  seed: number;
+  seeding_points: number;
+}
+
+export interface CupBracket {
+  league_id: string;
+  season_id: string;
+  start_week: number;
+  entrants: CupEntrant[];
+  rounds: CupRound[];
+  winner_team_id: string | null;
+}
+
+export function getLeagueCup(leagueId: string) {
+  return apiFetch<CupBracket>(`/api/leagues/${leagueId}/cup`);
+}
+
+export function createLeagueCup(leagueId: string, startWeek: number, token: string) {
+  return apiFetch<CupBracket>(`/api/leagues/${leagueId}/cup`, {
+    method: "POST",
+    body: { start_week: startWeek },
+    token,
+  });
+}
+
 // Chips
+export type ChipType =
+  | "triple_captain"
+  | "bench_boost"
+  | "double_captain"
+  | "wildcard"
+  | "free_hit";
+
 export interface ChipInfo {
+  chip_type: ChipType;
+  name: string;
+  description: string;
+  uses_per_season: number;
+  remaining: number;
   available: boolean;
   used_in_week: number | null;
+  used_in_weeks: number[];
   can_deactivate: boolean;
 }
 
@@ -246,8 +489,8 @@ export interface ActiveGameweek {
 }
 
 export interface ChipStatus {
-  triple_captain: ChipInfo;
-  bench_boost: ChipInfo;
+  chips: ChipInfo[];
+  active_chip: ChipType | null;
   active_gameweek: ActiveGameweek | null;
 }
 
@@ -257,19 +500,20 @@ export function getChipStatus(teamId: string, token: string) {
 
 export function activateChip(
   teamId: string,
-  chipType: "triple_captain" | "bench_boost",
-  token: string
+  chipType: ChipType,
+  token: string,
+  secondCaptainId?: string
 ) {
   return apiFetch<ChipStatus>(`/api/teams/${teamId}/chips`, {
     method: "POST",
-    body: { chip_type: chipType },
+    body: { chip_type: chipType, second_captain_id: secondCaptainId },
     token,
   });
 }
 
 export function deactivateChip(
   teamId: string,
-  chipType: "triple_captain" | "bench_boost",
+  chipType: ChipType,
   token: string
 ) {
   return apiFetch<ChipStatus>(`/api/teams/${teamId}/chips/${chipType}`, {
@@ -284,6 +528,9 @@ export interface TransferStatus {
   active_gameweek: number | null;
   transfers_used: number;
   free_transfers: number;
+  max_banked_transfers: number;
+  transfer_hit_cost: number;
+  unlimited_transfers: boolean;
   extra_transfers: number;
   points_hit: number;
   transferred_out: string | null;
@@ -315,19 +562,32 @@ export function transferPlayer(
 // Lock status
 export interface LockStatus {
   locked: boolean;
+  deadline_at: string | null;
   unlock_at: string | null;
   manually_unlocked: boolean;
   active_gameweek: number | null;
+  timezone: string;
 }
 
 export function getLockStatus() {
   return apiFetch<LockStatus>("/api/teams/lock-status");
 }
 
+/** Weekdays are ISO 8601: 1 is Monday, 7 is Sunday. Times are "HH:MM:SS". */
+export interface LockSchedule {
+  timezone: string;
+  lock_weekday: number;
+  lock_time: string;
+  unlock_weekday: number;
+  unlock_time: string;
+}
+
 export interface AdminLineupLockStatus {
   force_unlock: boolean;
   effective_locked: boolean;
+  deadline_at: string | null;
   unlock_at: string | null;
+  schedule: LockSchedule;
 }
 
 export function getLineupLockControl(token: string) {
@@ -342,6 +602,26 @@ export function setLineupLockControl(forceUnlock: boolean, token: string) {
   });
 }
 
+export function setLockSchedule(schedule: LockSchedule, token: string) {
+  return apiFetch<AdminLineupLockStatus>("/api/admin/lineup-lock/schedule", {
+    method: "PUT",
+    body: schedule,
+    token,
+  });
+}
+
+export function setGameweekDeadline(
+  weekNumber: number,
+  deadline: { deadline_at: string | null; unlock_at: string | null },
+  token: string
+) {
+  return apiFetch<MatchWeek>(`/api/admin/gameweek/${weekNumber}/deadline`, {
+    method: "PUT",
+    body: deadline,
+    token,
+  });
+}
+
 // Player leaderboard with aggregated stats
 export interface PlayerLeaderboard {
   id: string;
@@ -388,13 +668,53 @@ export function getPlayerPoints(playerId: string) {
   return apiFetch<PlayerPointsDisplay[]>(`/api/points/player/${playerId}`);
 }
 
+export interface LiveTeamPoints {
+  team_id: string;
+  team_name: string;
+  user_id: string;
+  total_points: number;
+}
+
+export interface LiveWeekPoints {
+  week_number: number;
+  status: GameweekStatus;
+  players: PlayerPointsDisplay[];
+  teams: LiveTeamPoints[];
+}
+
+/** Follow a gameweek's points as they are entered. Close the returned source to stop. */
+export function subscribeLivePoints(
+  week: number,
+  handlers: {
+    onPoints: (points: LiveWeekPoints) => void;
+    onLeague?: (league: LeagueGameweekDetail) => void;
+  },
+  leagueId?: string
+) {
+  const query = leagueId ? `?league_id=${leagueId}` : "";
+  const source = new EventSource(`${API_BASE}/api/points/live/${week}${query}`);
+  source.addEventListener("points", (e) => handlers.onPoints(JSON.parse((e as MessageEvent).data)));
+  if (handlers.onLeague) {
+    const onLeague = handlers.onLeague;
+    source.addEventListener("league", (e) => onLeague(JSON.parse((e as MessageEvent).data)));
+  }
+  return source;
+}
+
 // Admin
+export type GameweekStatus = "upcoming" | "live" | "provisional" | "finalised";
+
 export interface MatchWeek {
   id: string;
   week_number: number;
   start_date: string;
   end_date: string;
   is_active: boolean;
+  season_id: string;
+  status: GameweekStatus;
+  finalised_at: string | null;
+  deadline_at: string | null;
+  unlock_at: string | null;
 }
 
 export interface AdminPlayerStats {
@@ -452,6 +772,21 @@ export function toggleGameweek(weekNumber: number, token: string) {
   });
 }
 
+export function finaliseGameweek(weekNumber: number, token: string) {
+  return apiFetch<MatchWeek>(`/api/admin/gameweek/${weekNumber}/finalise`, {
+    method: "POST",
+    token,
+  });
+}
+
+export function reopenGameweek(weekNumber: number, reason: string, token: string) {
+  return apiFetch<MatchWeek>(`/api/admin/gameweek/${weekNumber}/reopen`, {
+    method: "POST",
+    body: { reason },
+    token,
+  });
+}
+
 export function getWeekStatsAdmin(week: number, token: string) {
   return apiFetch<AdminPlayerStats[]>(`/api/admin/gameweek/${week}/stats`, {
     token,
@@ -469,6 +804,129 @@ export function submitWeekStats(
   );
 }
 
+// Matches
+export type MatchSide = "home" | "away";
+
+export interface MatchLineupPlayer {
+  player_id: string;
+  player_name: string;
+  side: MatchSide;
+  minutes_played: number | null;
+}
+
+export interface MatchDetail {
+  id: string;
+  match_week_id: string;
+  week_number: number;
+  home_name: string;
+  away_name: string;
+  kickoff_at: string;
+  fantasy_minutes: number;
+  home_goals: number;
+  away_goals: number;
+  created_at: string;
+  updated_at: string;
+  fantasy_time_end: string;
+  lineups: MatchLineupPlayer[];
+}
+
+export interface MatchInput {
+  week_number: number;
+  home_name?: string;
+  away_name?: string;
+  kickoff_at: string;
+  fantasy_minutes?: number;
+  home_goals: number;
+  away_goals: number;
+  lineups: { player_id: string; side: MatchSide; minutes_played?: number }[];
+}
+
+export function getMatches(week?: number) {
+  const query = week === undefined ? "" : `?week=${week}`;
+  return apiFetch<MatchDetail[]>(`/api/matches${query}`);
+}
+
+export function getMatch(matchId: string) {
+  return apiFetch<MatchDetail>(`/api/matches/${matchId}`);
+}
+
+export function createMatch(match: MatchInput, token: string) {
+  return apiFetch<MatchDetail>("/api/admin/matches", { method: "POST", body: match, token });
+}
+
+export function updateMatch(matchId: string, match: MatchInput, token: string) {
+  return apiFetch<MatchDetail>(`/api/admin/matches/${matchId}`, {
+    method: "PUT",
+    body: match,
+    token,
+  });
+}
+
+export function deleteMatch(matchId: string, token: string) {
+  return apiFetch<{ ok: boolean }>(`/api/admin/matches/${matchId}`, { method: "DELETE", token });
+}
+
+export type MatchEventType = "goal" | "own_goal" | "save" | "penalty_save" | "penalty_miss" | "foul";
+export type FoulSeverity = "regular" | "serious";
+
+export interface MatchEventInput {
+  minute: number;
+  event_type: MatchEventType;
+  player_id: string;
+  assist_player_id?: string;
+  foul_severity?: FoulSeverity;
+}
+
+export interface MatchEvent {
+  id: string;
+  match_id: string;
+  minute: number;
+  event_type: MatchEventType;
+  player_id: string;
+  assist_player_id: string | null;
+  foul_severity: FoulSeverity | null;
+  created_at: string;
+}
+
+export interface MatchEventDetail {
+  id: string;
+  minute: number;
+  event_type: MatchEventType;
+  player_id: string;
+  player_name: string;
+  side: MatchSide;
+  assist_player_id: string | null;
+  assist_player_name: string | null;
+  foul_severity: FoulSeverity | null;
+  counts: boolean;
+}
+
+export function getMatchEvents(matchId: string) {
+  return apiFetch<MatchEventDetail[]>(`/api/matches/${matchId}/events`);
+}
+
+export function createMatchEvent(matchId: string, event: MatchEventInput, token: string) {
+  return apiFetch<MatchEvent>(`/api/admin/matches/${matchId}/events`, {
+    method: "POST",
+    body: event,
+    token,
+  });
+}
+
+export function deleteMatchEvent(matchId: string, eventId: string, token: string) {
+  return apiFetch<{ ok: boolean }>(`/api/admin/matches/${matchId}/events/${eventId}`, {
+    method: "DELETE",
+    token,
+  });
+}
+
+export function scoreWeekFromEvents(week: number, token: string) {
+  return apiFetch<{ ok: boolean; players_updated: number; week: number }>(
+    `/api/admin/gameweek/${week}/stats/from-events`,
+    { method: "POST", token }
+  );
+}
+
 // Accounting
 export interface FutsalSession {
   id: string;
@@ -0,0 +1,63 @@
+-- Versioned scoring rules. Each row is a complete rule set that applies from
+-- `effective_from_week` until the next version starts, so rates can change
+-- between seasons without touching already-scored weeks.
+CREATE TABLE scoring_rules (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL,
+    effective_from_week INTEGER NOT NULL UNIQUE CHECK (effective_from_week >= 1),
+    goal_gk INTEGER NOT NULL,
+    goal_def INTEGER NOT NULL,
+    goal_mid INTEGER NOT NULL,
+    goal_fwd INTEGER NOT NULL,
+    assist INTEGER NOT NULL,
+    clean_sheet_gk INTEGER NOT NULL,
+    clean_sheet_def INTEGER NOT NULL,
+    clean_sheet_mid INTEGER NOT NULL,
+    clean_sheet_fwd INTEGER NOT NULL,
+    -- GK saves score one point per this many saves.
+    saves_per_point INTEGER NOT NULL CHECK (saves_per_point > 0),
+    penalty_save INTEGER NOT NULL,
+    -- Appearances of at least `long_appearance_minutes` earn `long_appearance`,
+    -- anything shorter but non-zero earns `short_appearance`.
+    long_appearance_minutes INTEGER NOT NULL CHECK (long_appearance_minutes > 0),
+    long_appearance INTEGER NOT NULL,
+    short_appearance INTEGER NOT NULL,
+    -- Deductions are stored signed, e.g. -2 for an own goal.
+    own_goal INTEGER NOT NULL,
+    penalty_miss INTEGER NOT NULL,
+    regular_foul INTEGER NOT NULL,
+    serious_foul INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- The rates previously hard-coded in PointsEngine and points_sql. Clean sheets
+-- stay at 2 so existing gameweeks keep scoring exactly as they were stored; the
+-- rule book's GK=10/DEF=6 belongs in a new version for a future season.
+INSERT INTO scoring_rules (
+    name, effective_from_week,
+    goal_gk, goal_def, goal_mid, goal_fwd, assist,
+    clean_sheet_gk, clean_sheet_def, clean_sheet_mid, clean_sheet_fwd,
+    saves_per_point, penalty_save,
+    long_appearance_minutes, long_appearance, short_appearance,
+    own_goal, penalty_miss, regular_foul, serious_foul
+) VALUES (
+    'Original rules', 1,
+    10, 6, 5, 4, 5,
+    2, 2, 0, 0,
+    5, 8,
+    35, 2, 1,
+    -2, -2, -1, -3
+);
+
+-- The rule set in force for each match week: the latest version starting on or
+-- before that week.
+CREATE VIEW match_week_scoring_rules AS
+SELECT mw.id AS match_week_id, sr.*
+FROM match_weeks mw
+JOIN LATERAL (
+    SELECT *
+    FROM scoring_rules s
+    WHERE s.effective_from_week <= mw.week_number
+    ORDER BY s.effective_from_week DESC
+    LIMIT 1
+) sr ON true;
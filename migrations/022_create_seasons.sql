@@ -0,0 +1,67 @@
+-- Seasons group match weeks. Exactly one season is open at a time; closing it
+-- archives standings, reprices players and resets points and chips.
+CREATE TABLE seasons (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL CHECK (name IN ('Summer', 'Winter', 'Spring', 'Fall')),
+    year INTEGER NOT NULL,
+    start_week INTEGER NOT NULL,
+    end_week INTEGER,
+    closed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(name, year)
+);
+
+CREATE UNIQUE INDEX idx_seasons_single_open ON seasons((closed_at IS NULL)) WHERE closed_at IS NULL;
+
+INSERT INTO seasons (name, year, start_week) VALUES ('Winter', 2026, 1);
+
+ALTER TABLE match_weeks ADD COLUMN season_id UUID REFERENCES seasons(id);
+UPDATE match_weeks SET season_id = (SELECT id FROM seasons);
+ALTER TABLE match_weeks ALTER COLUMN season_id SET NOT NULL;
+CREATE INDEX idx_match_weeks_season ON match_weeks(season_id);
+
+-- New weeks join the open season unless one is given explicitly.
+CREATE OR REPLACE FUNCTION assign_match_week_season()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.season_id IS NULL THEN
+        NEW.season_id := (SELECT id FROM seasons WHERE closed_at IS NULL);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER match_weeks_assign_season
+    BEFORE INSERT ON match_weeks
+    FOR EACH ROW
+    EXECUTE FUNCTION assign_match_week_season();
+
+-- Chips are restored every season, so usage is unique per season instead of forever.
+ALTER TABLE team_chips ADD COLUMN season_id UUID REFERENCES seasons(id);
+UPDATE team_chips tc SET season_id = mw.season_id
+FROM match_weeks mw WHERE mw.id = tc.match_week_id;
+ALTER TABLE team_chips ALTER COLUMN season_id SET NOT NULL;
+ALTER TABLE team_chips DROP CONSTRAINT IF EXISTS team_chips_team_id_chip_type_key;
+ALTER TABLE team_chips ADD CONSTRAINT team_chips_team_chip_season_key
+    UNIQUE (team_id, chip_type, season_id);
+
+-- Final standings, frozen when a season closes.
+CREATE TABLE season_standings (
+    season_id UUID NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    team_id UUID NOT NULL REFERENCES fantasy_teams(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    team_name VARCHAR(100) NOT NULL,
+    total_points INTEGER NOT NULL,
+    rank INTEGER NOT NULL,
+    PRIMARY KEY (season_id, team_id)
+);
+
+-- Each player's season total and the repricing it triggered.
+CREATE TABLE player_season_results (
+    season_id UUID NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    total_points INTEGER NOT NULL,
+    price_before NUMERIC(10, 2) NOT NULL,
+    price_after NUMERIC(10, 2) NOT NULL,
+    PRIMARY KEY (season_id, player_id)
+);
@@ -0,0 +1,8 @@
+-- Optional vice-captain, a different starter from the captain.
+ALTER TABLE fantasy_teams ADD COLUMN vice_captain_id UUID REFERENCES players(id) ON DELETE SET NULL;
+ALTER TABLE team_gameweek_lineups ADD COLUMN vice_captain_id UUID REFERENCES players(id) ON DELETE SET NULL;
+
+-- Whether the vice-captain takes the captain's multiplier in a gameweek where the
+-- captain plays no minutes. Off by default, which keeps the original rule that
+-- only the captain is ever multiplied.
+ALTER TABLE seasons ADD COLUMN vice_captain_fallback BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,29 @@
+-- Opt-in automatic substitutions. When on, a starter who plays no minutes is
+-- replaced at scoring time by the first bench player who played and keeps the
+-- formation valid. Off by default, matching the original no-auto-subs rule.
+ALTER TABLE seasons ADD COLUMN auto_subs BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Bench priority, 1 first. Existing benches get an arbitrary but stable order.
+ALTER TABLE team_players ADD COLUMN bench_order SMALLINT;
+UPDATE team_players tp SET bench_order = o.n
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY team_id ORDER BY player_id) AS n
+    FROM team_players
+    WHERE is_bench
+) o
+WHERE o.id = tp.id;
+
+ALTER TABLE team_gameweek_lineup_players ADD COLUMN bench_order SMALLINT;
+UPDATE team_gameweek_lineup_players tglp SET bench_order = o.n
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY team_gameweek_lineup_id ORDER BY player_id) AS n
+    FROM team_gameweek_lineup_players
+    WHERE is_bench
+) o
+WHERE o.id = tglp.id;
+
+-- Set on a bench player who came on: the starter they replaced. While set, the
+-- row's `is_bench` and `assigned_position` describe the substituted lineup, and
+-- the replaced starter's row is on the bench.
+ALTER TABLE team_gameweek_lineup_players
+    ADD COLUMN auto_sub_for UUID REFERENCES players(id) ON DELETE SET NULL;
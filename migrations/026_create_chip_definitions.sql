@@ -0,0 +1,41 @@
+-- Chip catalogue: which chips exist and how many times each can be played per season.
+CREATE TABLE chip_definitions (
+    chip_type TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    uses_per_season INTEGER NOT NULL DEFAULT 1 CHECK (uses_per_season >= 0),
+    sort_order INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO chip_definitions (chip_type, name, description, uses_per_season, sort_order) VALUES
+    ('triple_captain', 'Triple Captain', 'Captain scores triple points instead of double', 1, 1),
+    ('bench_boost', 'Bench Boost', 'All bench players score points for that gameweek', 1, 2),
+    ('double_captain', 'Double Captain', 'A second starter also scores double points', 1, 3),
+    ('wildcard', 'Wildcard', 'Unlimited free transfers for the gameweek', 1, 4),
+    ('free_hit', 'Free Hit', 'Unlimited free transfers for one gameweek; the squad reverts afterwards', 1, 5);
+
+-- Chip types now come from the catalogue, and a chip may be allowed more than
+-- once a season, so usage is counted rather than unique.
+ALTER TABLE team_chips DROP CONSTRAINT IF EXISTS team_chips_chip_type_check;
+ALTER TABLE team_chips ADD CONSTRAINT team_chips_chip_type_fkey
+    FOREIGN KEY (chip_type) REFERENCES chip_definitions(chip_type);
+ALTER TABLE team_chips DROP CONSTRAINT IF EXISTS team_chips_team_chip_season_key;
+-- Any number of each chip per season, but only one chip per team per gameweek.
+CREATE UNIQUE INDEX idx_team_chips_team_week ON team_chips(team_id, match_week_id);
+
+-- Double Captain: the starter who scores double alongside the captain.
+ALTER TABLE team_chips ADD COLUMN second_captain_id UUID REFERENCES players(id) ON DELETE SET NULL;
+
+-- Free Hit: the squad as it was when the chip was played, restored afterwards.
+ALTER TABLE team_chips ADD COLUMN saved_captain_id UUID REFERENCES players(id) ON DELETE SET NULL;
+ALTER TABLE team_chips ADD COLUMN saved_vice_captain_id UUID REFERENCES players(id) ON DELETE SET NULL;
+ALTER TABLE team_chips ADD COLUMN reverted_at TIMESTAMPTZ;
+
+CREATE TABLE free_hit_squad_players (
+    team_chip_id UUID NOT NULL REFERENCES team_chips(id) ON DELETE CASCADE,
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    is_bench BOOLEAN NOT NULL,
+    assigned_position player_position,
+    bench_order SMALLINT,
+    PRIMARY KEY (team_chip_id, player_id)
+);
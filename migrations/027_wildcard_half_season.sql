@@ -0,0 +1,20 @@
+-- The season's midpoint: chips marked per_half_season can be played once
+-- before it and once from it. Unset means the whole season is one half.
+ALTER TABLE seasons ADD COLUMN second_half_start_week INTEGER;
+
+ALTER TABLE chip_definitions ADD COLUMN per_half_season BOOLEAN NOT NULL DEFAULT false;
+UPDATE chip_definitions
+SET per_half_season = true,
+    description = 'Unlimited free transfers for the gameweek, once in each half of the season'
+WHERE chip_type = 'wildcard';
+
+-- Set when a Wildcard or Free Hit is played, so rescoring the week never
+-- charges transfer hits, whatever happens to the chip afterwards.
+ALTER TABLE team_gameweek_lineups
+    ADD COLUMN transfer_hits_waived BOOLEAN NOT NULL DEFAULT false;
+
+UPDATE team_gameweek_lineups tgl SET transfer_hits_waived = true
+FROM team_chips tc
+WHERE tc.team_id = tgl.team_id
+  AND tc.match_week_id = tgl.match_week_id
+  AND tc.chip_type IN ('wildcard', 'free_hit');
@@ -0,0 +1,10 @@
+-- Per-season transfer policy. The defaults keep the original rule: one free
+-- transfer a week that does not roll over, and -4 for each extra.
+ALTER TABLE seasons ADD COLUMN free_transfers_per_week INTEGER NOT NULL DEFAULT 1
+    CHECK (free_transfers_per_week >= 0);
+ALTER TABLE seasons ADD COLUMN max_banked_transfers INTEGER NOT NULL DEFAULT 1
+    CHECK (max_banked_transfers >= free_transfers_per_week);
+ALTER TABLE seasons ADD COLUMN transfer_hit_cost INTEGER NOT NULL DEFAULT 4
+    CHECK (transfer_hit_cost >= 0);
+
+CREATE INDEX IF NOT EXISTS idx_transfers_team_week ON transfers(team_id, match_week_id);
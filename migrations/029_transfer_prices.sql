@@ -0,0 +1,4 @@
+-- Prices of both players at the moment of a transfer, for transfer history and
+-- undo. Transfers made before this migration have no recorded prices.
+ALTER TABLE transfers ADD COLUMN player_out_price DECIMAL(10, 2);
+ALTER TABLE transfers ADD COLUMN player_in_price DECIMAL(10, 2);
@@ -0,0 +1,32 @@
+-- What each team paid for each squad member. A player sells for their purchase
+-- price plus half of any rise since, rounded down to 0.1, or their current price
+-- after a fall. `fantasy_teams.budget_limit` is now the team's bank plus the
+-- purchase prices of its squad, so it only moves when a sale makes a profit or
+-- loss. Existing squads count as bought at today's prices.
+ALTER TABLE team_players ADD COLUMN purchase_price NUMERIC(10, 2);
+UPDATE team_players tp SET purchase_price = p.price FROM players p WHERE p.id = tp.player_id;
+ALTER TABLE team_players ALTER COLUMN purchase_price SET NOT NULL;
+
+-- A newly bought player costs their current price unless one is given.
+CREATE OR REPLACE FUNCTION default_purchase_price()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.purchase_price IS NULL THEN
+        NEW.purchase_price := (SELECT price FROM players WHERE id = NEW.player_id);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER team_players_default_purchase_price
+    BEFORE INSERT ON team_players
+    FOR EACH ROW
+    EXECUTE FUNCTION default_purchase_price();
+
+-- Undoing a transfer restores the outgoing player at what the team paid for them.
+ALTER TABLE transfers ADD COLUMN player_out_purchase_price NUMERIC(10, 2);
+
+-- A Free Hit reverts purchase prices and the budget along with the squad.
+ALTER TABLE free_hit_squad_players ADD COLUMN purchase_price NUMERIC(10, 2);
+UPDATE free_hit_squad_players fh SET purchase_price = p.price FROM players p WHERE p.id = fh.player_id;
+ALTER TABLE team_chips ADD COLUMN saved_budget_limit NUMERIC(10, 2);
@@ -0,0 +1,5 @@
+-- How player prices move after each gameweek: 'points' moves the week's top and
+-- bottom three scorers (the original rule), 'net_transfers' the players most
+-- transferred in and out.
+ALTER TABLE seasons ADD COLUMN price_model TEXT NOT NULL DEFAULT 'points'
+    CHECK (price_model IN ('points', 'net_transfers'));
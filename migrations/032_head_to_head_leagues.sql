@@ -0,0 +1,22 @@
+-- Head-to-head leagues: members are paired off each gameweek and the table is
+-- built from wins, draws and losses rather than total points.
+ALTER TABLE leagues ADD COLUMN league_type TEXT NOT NULL DEFAULT 'classic'
+    CHECK (league_type IN ('classic', 'head_to_head'));
+
+-- One pairing per row. A NULL away team is a bye. Points are copied from
+-- team_gameweek_points whenever the week is scored and stay NULL until then.
+CREATE TABLE h2h_fixtures (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    league_id UUID NOT NULL REFERENCES leagues(id) ON DELETE CASCADE,
+    season_id UUID NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    week_number INTEGER NOT NULL,
+    home_team_id UUID NOT NULL REFERENCES fantasy_teams(id) ON DELETE CASCADE,
+    away_team_id UUID REFERENCES fantasy_teams(id) ON DELETE CASCADE,
+    home_points INTEGER,
+    away_points INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (home_team_id <> away_team_id)
+);
+
+CREATE INDEX idx_h2h_fixtures_league_week ON h2h_fixtures(league_id, week_number);
+CREATE INDEX idx_h2h_fixtures_week ON h2h_fixtures(week_number);
@@ -0,0 +1,22 @@
+-- Cup tiebreakers, recorded when a gameweek is scored: the armband holder's
+-- points including the multiplier, and goals by the players who counted.
+-- Weeks scored before this migration read as 0 until they are rescored.
+ALTER TABLE team_gameweek_points ADD COLUMN captain_points INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE team_gameweek_points ADD COLUMN goals INTEGER NOT NULL DEFAULT 0;
+
+-- An optional knockout cup per league and season. The entrants are fixed when
+-- the cup is created; seeds and results are worked out from team scores.
+CREATE TABLE league_cups (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    league_id UUID NOT NULL REFERENCES leagues(id) ON DELETE CASCADE,
+    season_id UUID NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    start_week INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (league_id, season_id)
+);
+
+CREATE TABLE league_cup_entrants (
+    cup_id UUID NOT NULL REFERENCES league_cups(id) ON DELETE CASCADE,
+    team_id UUID NOT NULL REFERENCES fantasy_teams(id) ON DELETE CASCADE,
+    PRIMARY KEY (cup_id, team_id)
+);
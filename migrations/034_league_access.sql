@@ -0,0 +1,17 @@
+-- Who can find and join a league. Public leagues are listed in the directory
+-- and can be joined without the code; private ones need the invite code. Either
+-- kind can cap its size and hold joiners for the owner's approval.
+ALTER TABLE leagues ADD COLUMN visibility TEXT NOT NULL DEFAULT 'private'
+    CHECK (visibility IN ('public', 'private'));
+ALTER TABLE leagues ADD COLUMN max_members INTEGER CHECK (max_members >= 2);
+ALTER TABLE leagues ADD COLUMN requires_approval BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE league_join_requests (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    league_id UUID NOT NULL REFERENCES leagues(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (league_id, user_id)
+);
+
+CREATE INDEX idx_leagues_visibility ON leagues(visibility);
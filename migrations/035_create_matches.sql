@@ -0,0 +1,26 @@
+-- The pickup matches played in a gameweek: two sides, a kickoff time and the
+-- score. Only the first `fantasy_minutes` of a match count for fantasy, so the
+-- score recorded here is the score at the end of that window.
+CREATE TABLE matches (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    match_week_id UUID NOT NULL REFERENCES match_weeks(id) ON DELETE CASCADE,
+    home_name VARCHAR(100) NOT NULL DEFAULT 'Home',
+    away_name VARCHAR(100) NOT NULL DEFAULT 'Away',
+    kickoff_at TIMESTAMPTZ NOT NULL,
+    fantasy_minutes INTEGER NOT NULL DEFAULT 60 CHECK (fantasy_minutes > 0),
+    home_goals INTEGER NOT NULL DEFAULT 0 CHECK (home_goals >= 0),
+    away_goals INTEGER NOT NULL DEFAULT 0 CHECK (away_goals >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Who played on which side.
+CREATE TABLE match_lineups (
+    match_id UUID NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    side TEXT NOT NULL CHECK (side IN ('home', 'away')),
+    PRIMARY KEY (match_id, player_id)
+);
+
+CREATE INDEX idx_matches_week ON matches(match_week_id);
+CREATE INDEX idx_match_lineups_player ON match_lineups(player_id);
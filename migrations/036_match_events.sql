@@ -0,0 +1,22 @@
+-- How long each player was on the pitch; NULL means the whole of fantasy time.
+ALTER TABLE match_lineups ADD COLUMN minutes_played INTEGER CHECK (minutes_played >= 0);
+
+-- Individual events logged during a match. `minute` is the match clock as it
+-- would be written on a scoresheet; only events up to the match's
+-- fantasy_minutes count. A goal may name an assister from the same side, and a
+-- foul carries its severity.
+CREATE TABLE match_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    match_id UUID NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    minute INTEGER NOT NULL CHECK (minute >= 0),
+    event_type TEXT NOT NULL
+        CHECK (event_type IN ('goal', 'own_goal', 'save', 'penalty_save', 'penalty_miss', 'foul')),
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    assist_player_id UUID REFERENCES players(id) ON DELETE CASCADE,
+    foul_severity TEXT CHECK (foul_severity IN ('regular', 'serious')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (assist_player_id IS NULL OR event_type = 'goal'),
+    CHECK ((event_type = 'foul') = (foul_severity IS NOT NULL))
+);
+
+CREATE INDEX idx_match_events_match ON match_events(match_id, minute);
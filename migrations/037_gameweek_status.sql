@@ -0,0 +1,18 @@
+-- Where a gameweek is in its life: upcoming, live once activated, provisional
+-- once stats are entered and finalised once an admin confirms them. Prices and
+-- league results are only committed on finalisation.
+ALTER TABLE match_weeks ADD COLUMN status TEXT NOT NULL DEFAULT 'upcoming'
+    CHECK (status IN ('upcoming', 'live', 'provisional', 'finalised'));
+ALTER TABLE match_weeks ADD COLUMN finalised_at TIMESTAMPTZ;
+
+-- Weeks scored before this had their prices and results applied already, so
+-- they count as finalised; only the active week stays open for edits.
+UPDATE match_weeks mw SET status = CASE
+    WHEN EXISTS (SELECT 1 FROM player_points pp WHERE pp.match_week_id = mw.id)
+        THEN CASE WHEN mw.is_active THEN 'provisional' ELSE 'finalised' END
+    WHEN mw.is_active THEN 'live'
+    ELSE 'upcoming'
+END;
+UPDATE match_weeks SET finalised_at = NOW() WHERE status = 'finalised';
+
+ALTER TABLE match_weeks ADD CHECK ((status = 'finalised') = (finalised_at IS NOT NULL));
@@ -0,0 +1,17 @@
+-- The weekly lineup lock that applies when a gameweek sets no deadline of its
+-- own: from lock_weekday at lock_time to unlock_weekday at unlock_time, in
+-- `timezone`. Weekdays are ISO 8601 (1 = Monday, 7 = Sunday).
+ALTER TABLE lineup_lock_control
+    ADD COLUMN timezone TEXT NOT NULL DEFAULT 'America/New_York',
+    ADD COLUMN lock_weekday SMALLINT NOT NULL DEFAULT 6 CHECK (lock_weekday BETWEEN 1 AND 7),
+    ADD COLUMN lock_time TIME NOT NULL DEFAULT '22:00',
+    ADD COLUMN unlock_weekday SMALLINT NOT NULL DEFAULT 7 CHECK (unlock_weekday BETWEEN 1 AND 7),
+    ADD COLUMN unlock_time TIME NOT NULL DEFAULT '12:00';
+
+-- A gameweek's own lock window, for weeks played off the usual schedule. Both
+-- or neither are set; when set they replace the weekly schedule for the week.
+ALTER TABLE match_weeks
+    ADD COLUMN deadline_at TIMESTAMPTZ,
+    ADD COLUMN unlock_at TIMESTAMPTZ,
+    ADD CHECK ((deadline_at IS NULL) = (unlock_at IS NULL)),
+    ADD CHECK (unlock_at > deadline_at);
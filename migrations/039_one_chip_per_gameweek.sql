@@ -0,0 +1,33 @@
+-- Databases that ran 026 before it made idx_team_chips_team_week unique may hold
+-- more than one chip for a team in a gameweek. Keep the first chip played and
+-- move the others, with any Free Hit squad saved against them, to archive
+-- tables so they can be reviewed and restored by hand.
+CREATE TABLE team_chips_archive (
+    LIKE team_chips INCLUDING DEFAULTS,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE TABLE free_hit_squad_players_archive (LIKE free_hit_squad_players INCLUDING DEFAULTS);
+
+CREATE TEMP TABLE duplicate_team_chips AS
+SELECT tc.id
+FROM team_chips tc
+WHERE EXISTS (
+    SELECT 1 FROM team_chips earlier
+    WHERE earlier.team_id = tc.team_id
+      AND earlier.match_week_id = tc.match_week_id
+      AND (earlier.created_at, earlier.id) < (tc.created_at, tc.id)
+);
+
+INSERT INTO team_chips_archive
+SELECT tc.*, NOW() FROM team_chips tc
+WHERE tc.id IN (SELECT id FROM duplicate_team_chips);
+
+INSERT INTO free_hit_squad_players_archive
+SELECT fh.* FROM free_hit_squad_players fh
+WHERE fh.team_chip_id IN (SELECT id FROM duplicate_team_chips);
+
+DELETE FROM team_chips WHERE id IN (SELECT id FROM duplicate_team_chips);
+DROP TABLE duplicate_team_chips;
+
+DROP INDEX IF EXISTS idx_team_chips_team_week;
+CREATE UNIQUE INDEX idx_team_chips_team_week ON team_chips(team_id, match_week_id);
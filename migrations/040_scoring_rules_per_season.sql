@@ -0,0 +1,34 @@
+-- Scoring rule sets belong to a season instead of starting at a gameweek, so
+-- rates can only change between seasons. A season without a rule set of its own
+-- keeps using the one from the latest season before it.
+ALTER TABLE scoring_rules ADD COLUMN season_id UUID REFERENCES seasons(id);
+
+UPDATE scoring_rules sr SET season_id = COALESCE(
+    (SELECT s.id FROM seasons s
+     WHERE s.start_week <= sr.effective_from_week
+     ORDER BY s.start_week DESC
+     LIMIT 1),
+    (SELECT s.id FROM seasons s ORDER BY s.start_week LIMIT 1)
+);
+
+ALTER TABLE scoring_rules ALTER COLUMN season_id SET NOT NULL;
+-- Fails if two versions start within one season; merge those by hand first.
+ALTER TABLE scoring_rules ADD CONSTRAINT scoring_rules_season_key UNIQUE (season_id);
+
+DROP VIEW match_week_scoring_rules;
+ALTER TABLE scoring_rules DROP COLUMN effective_from_week;
+
+-- The rule set in force for each match week: its season's, or else the latest
+-- earlier season's.
+CREATE VIEW match_week_scoring_rules AS
+SELECT mw.id AS match_week_id, sr.*
+FROM match_weeks mw
+JOIN seasons s ON s.id = mw.season_id
+JOIN LATERAL (
+    SELECT r.*
+    FROM scoring_rules r
+    JOIN seasons rs ON rs.id = r.season_id
+    WHERE rs.start_week <= s.start_week
+    ORDER BY rs.start_week DESC
+    LIMIT 1
+) sr ON true;